@@ -7,12 +7,7 @@ type Bpchar = String;
 type Timestamptz = String;
 
 #[derive(GraphQLQuery)]
-#[graphql(
-    schema_path = "examples/schema.graphql",
-    query_path = "examples/query_1.graphql",
-    response_derives = "Debug",
-    normalization = "rust"
-)]
+#[graphql(query_path = "examples/query_1.graphql")]
 struct UpsertIssue;
 
 fn main() -> Result<(), anyhow::Error> {
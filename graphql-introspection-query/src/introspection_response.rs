@@ -135,14 +135,15 @@ pub struct FullType {
     pub interfaces: Option<Vec<FullTypeInterfaces>>,
     pub enum_values: Option<Vec<FullTypeEnumValues>>,
     pub possible_types: Option<Vec<FullTypePossibleTypes>>,
+    #[serde(rename = "specifiedByURL", alias = "specifiedByUrl", default)]
+    pub specified_by_url: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FullTypeFieldsArgs {
     #[serde(flatten)]
-    #[allow(dead_code)]
-    input_value: InputValue,
+    pub input_value: InputValue,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -247,8 +248,7 @@ pub struct SchemaTypes {
 #[serde(rename_all = "camelCase")]
 pub struct SchemaDirectivesArgs {
     #[serde(flatten)]
-    #[allow(dead_code)]
-    input_value: InputValue,
+    pub input_value: InputValue,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -267,8 +267,7 @@ pub struct Schema {
     pub mutation_type: Option<SchemaMutationType>,
     pub subscription_type: Option<SchemaSubscriptionType>,
     pub types: Option<Vec<Option<SchemaTypes>>>,
-    #[allow(dead_code)]
-    directives: Option<Vec<Option<SchemaDirectives>>>,
+    pub directives: Option<Vec<Option<SchemaDirectives>>>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -0,0 +1,135 @@
+//! Base64 strings for [`serde_bytes::ByteBuf`](serde_bytes_crate::ByteBuf), whose own serde
+//! format is a list of numbers in JSON. The generated code uses it as
+//! `#[serde(with = "graphql_client::base64")]` on the fields of `Bytes` scalars, with the
+//! `serde_bytes` feature.
+
+use base64_crate::{engine::general_purpose::STANDARD, Engine};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_bytes_crate::ByteBuf;
+use std::borrow::Cow;
+
+/// The field types the adapter applies to: `ByteBuf`, and the options, lists and
+/// [`MaybeUndefined`](crate::MaybeUndefined) of them.
+pub trait Base64Field: Sized {
+    /// Serializes the bytes of the field as base64 strings.
+    fn serialize_base64<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes the bytes of the field from base64 strings.
+    fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serializes a field with base64 bytes.
+pub fn serialize<T: Base64Field, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize_base64(serializer)
+}
+
+/// Deserializes a field with base64 bytes.
+pub fn deserialize<'de, T: Base64Field, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize_base64(deserializer)
+}
+
+struct Ser<'a, T>(&'a T);
+
+impl<T: Base64Field> Serialize for Ser<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_base64(serializer)
+    }
+}
+
+struct De<T>(T);
+
+impl<'de, T: Base64Field> Deserialize<'de> for De<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_base64(deserializer).map(De)
+    }
+}
+
+impl Base64Field for ByteBuf {
+    fn serialize_base64<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(self))
+    }
+
+    fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = Cow::<'de, str>::deserialize(deserializer)?;
+        STANDARD
+            .decode(encoded.as_bytes())
+            .map(ByteBuf::from)
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<T: Base64Field> Base64Field for Option<T> {
+    fn serialize_base64<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Ser(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<De<T>>::deserialize(deserializer).map(|value| value.map(|De(value)| value))
+    }
+}
+
+impl<T: Base64Field> Base64Field for Vec<T> {
+    fn serialize_base64<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Ser))
+    }
+
+    fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<De<T>>::deserialize(deserializer)
+            .map(|values| values.into_iter().map(|De(value)| value).collect())
+    }
+}
+
+impl<T: Base64Field> Base64Field for crate::MaybeUndefined<T> {
+    fn serialize_base64<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            crate::MaybeUndefined::Value(value) => serializer.serialize_some(&Ser(value)),
+            crate::MaybeUndefined::Undefined | crate::MaybeUndefined::Null => {
+                serializer.serialize_none()
+            }
+        }
+    }
+
+    fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize_base64(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Upload {
+        #[serde(with = "super")]
+        content: ByteBuf,
+        #[serde(default, with = "super")]
+        thumbnails: Option<Vec<ByteBuf>>,
+    }
+
+    #[test]
+    fn bytes_round_trip_as_base64_strings() {
+        let value = json!({ "content": "aGk=", "thumbnails": ["AAH/"] });
+        let upload: Upload = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(upload.content.as_slice(), b"hi");
+        assert_eq!(
+            upload.thumbnails.as_ref().unwrap()[0].as_slice(),
+            [0, 1, 255]
+        );
+        assert_eq!(serde_json::to_value(&upload).unwrap(), value);
+
+        let upload: Upload = serde_json::from_value(json!({ "content": "" })).unwrap();
+        assert_eq!(upload.thumbnails, None);
+
+        assert!(serde_json::from_value::<Upload>(json!({ "content": "not base64" })).is_err());
+    }
+}
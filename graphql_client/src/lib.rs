@@ -7,9 +7,10 @@
 //!
 //! ## Cargo features
 //!
-//! - `graphql_query_derive` (default: on): enables the `#[derive(GraphqlQuery)]` custom derive.
+//! - `graphql_query_derive` (default: on): enables the `#[derive(GraphqlQuery)]` custom derive, and the [`graphql!`] and [`schema_types!`] macros.
 //! - `reqwest` (default: off): exposes the `graphql_client::reqwest::post_graphql()` function.
 //! - `reqwest-blocking` (default: off): exposes the blocking version, `graphql_client::reqwest::post_graphql_blocking()`.
+//! - `chrono`, `time`, `uuid`, `url`, `rust_decimal` and `serde_bytes` (default: off): re-export these crates, for the well-known scalars of schemas, like RFC 3339 date-times and UUIDs, that a query maps to their types with the `scalar_crates` option. `time` also enables the `rfc3339` module, and `serde_bytes` the `base64` one.
 
 #![deny(missing_docs)]
 #![warn(rust_2018_idioms)]
@@ -23,6 +24,29 @@ extern crate graphql_query_derive;
 #[doc(hidden)]
 pub use graphql_query_derive::*;
 
+/// `graphql!` is an expression: the struct and module of the operation are generated in a block
+/// that evaluates to the struct, so their types are reached through [`GraphQLQuery`].
+///
+/// ```
+/// use graphql_client::GraphQLQuery;
+///
+/// fn operation_name<Q: GraphQLQuery>(_operation: &Q, variables: Q::Variables) -> &'static str {
+///     Q::build_query(variables).operation_name
+/// }
+///
+/// let echo = graphql_client::graphql!(
+///     schema = "tests/operation_selection/schema.graphql",
+///     variables_derives = "Default",
+///     query Echo { echo(msg: "hi") }
+/// );
+///
+/// assert_eq!(operation_name(&echo, Default::default()), "Echo");
+/// ```
+#[cfg(feature = "graphql_query_derive")]
+pub use graphql_query_derive::graphql;
+#[cfg(feature = "graphql_query_derive")]
+pub use graphql_query_derive::schema_types;
+
 #[cfg(any(
     feature = "reqwest",
     feature = "reqwest-rustls",
@@ -30,6 +54,30 @@ pub use graphql_query_derive::*;
 ))]
 pub mod reqwest;
 
+#[cfg(feature = "chrono")]
+#[doc(no_inline)]
+pub use chrono_crate as chrono;
+#[cfg(feature = "rust_decimal")]
+#[doc(no_inline)]
+pub use rust_decimal_crate as rust_decimal;
+#[cfg(feature = "serde_bytes")]
+#[doc(no_inline)]
+pub use serde_bytes_crate as serde_bytes;
+#[cfg(feature = "time")]
+#[doc(no_inline)]
+pub use time_crate as time;
+#[cfg(feature = "url")]
+#[doc(no_inline)]
+pub use url_crate as url;
+#[cfg(feature = "uuid")]
+#[doc(no_inline)]
+pub use uuid_crate as uuid;
+
+#[cfg(feature = "serde_bytes")]
+pub mod base64;
+#[cfg(feature = "time")]
+pub mod rfc3339;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{self, Display, Write};
@@ -299,6 +347,105 @@ pub struct Response<Data> {
     pub extensions: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// A nullable input value that can also be left out.
+///
+/// GraphQL tells an omitted input field or variable apart from one set to `null`: in a partial
+/// update, `null` usually clears a value while an omitted field leaves it unchanged. With the
+/// `maybe_undefined` codegen option, nullable input fields and variables have this type instead
+/// of `Option`. `Undefined` fields are not serialized, and `Null` is serialized as `null`.
+///
+/// ```
+/// use graphql_client::MaybeUndefined;
+///
+/// #[derive(serde::Serialize)]
+/// struct UserPatch {
+///     #[serde(default, skip_serializing_if = "MaybeUndefined::is_undefined")]
+///     name: MaybeUndefined<String>,
+///     #[serde(default, skip_serializing_if = "MaybeUndefined::is_undefined")]
+///     bio: MaybeUndefined<String>,
+/// }
+///
+/// let patch = UserPatch {
+///     name: "Ada".to_owned().into(),
+///     bio: MaybeUndefined::Null,
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&patch).unwrap(),
+///     r#"{"name":"Ada","bio":null}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MaybeUndefined<T> {
+    /// The value is left out.
+    #[default]
+    Undefined,
+    /// The value is explicitly `null`.
+    Null,
+    /// The value is set.
+    Value(T),
+}
+
+impl<T> MaybeUndefined<T> {
+    /// Whether the value is left out.
+    pub fn is_undefined(&self) -> bool {
+        matches!(self, MaybeUndefined::Undefined)
+    }
+
+    /// Whether the value is explicitly `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, MaybeUndefined::Null)
+    }
+
+    /// The value, if it is set.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            MaybeUndefined::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The value, if it is set, with `Undefined` and `Null` both becoming `None`.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            MaybeUndefined::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<T> for MaybeUndefined<T> {
+    fn from(value: T) -> Self {
+        MaybeUndefined::Value(value)
+    }
+}
+
+/// `None` is an explicit `null`.
+impl<T> From<Option<T>> for MaybeUndefined<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => MaybeUndefined::Value(value),
+            None => MaybeUndefined::Null,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for MaybeUndefined<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            MaybeUndefined::Value(value) => serializer.serialize_some(value),
+            MaybeUndefined::Undefined | MaybeUndefined::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+/// A missing field only deserializes to `Undefined` with `#[serde(default)]`.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MaybeUndefined<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(Into::into)
+    }
+}
+
 /// Hidden module for types used by the codegen crate.
 #[doc(hidden)]
 pub mod _private {
@@ -407,4 +554,28 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn maybe_undefined_deserializes_null_and_missing_fields() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Patch {
+            #[serde(default)]
+            name: MaybeUndefined<String>,
+            #[serde(default)]
+            bio: MaybeUndefined<String>,
+            #[serde(default)]
+            age: MaybeUndefined<i64>,
+        }
+
+        let patch: Patch = serde_json::from_value(json!({ "name": "Ada", "bio": null })).unwrap();
+
+        assert_eq!(
+            patch,
+            Patch {
+                name: MaybeUndefined::Value("Ada".to_owned()),
+                bio: MaybeUndefined::Null,
+                age: MaybeUndefined::Undefined,
+            }
+        );
+    }
 }
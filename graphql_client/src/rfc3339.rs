@@ -0,0 +1,132 @@
+//! RFC 3339 strings for [`time::OffsetDateTime`](time_crate::OffsetDateTime), whose own serde
+//! format is different. The generated code uses it as `#[serde(with = "graphql_client::rfc3339")]`
+//! on the fields of date-time scalars, with the `time` feature.
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use time_crate::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// The field types the adapter applies to: `OffsetDateTime`, and the options, lists and
+/// [`MaybeUndefined`](crate::MaybeUndefined) of them.
+pub trait Rfc3339Field: Sized {
+    /// Serializes the date-times of the field as RFC 3339 strings.
+    fn serialize_rfc3339<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes the date-times of the field from RFC 3339 strings.
+    fn deserialize_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serializes a field with RFC 3339 date-times.
+pub fn serialize<T: Rfc3339Field, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize_rfc3339(serializer)
+}
+
+/// Deserializes a field with RFC 3339 date-times.
+pub fn deserialize<'de, T: Rfc3339Field, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize_rfc3339(deserializer)
+}
+
+struct Ser<'a, T>(&'a T);
+
+impl<T: Rfc3339Field> Serialize for Ser<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_rfc3339(serializer)
+    }
+}
+
+struct De<T>(T);
+
+impl<'de, T: Rfc3339Field> Deserialize<'de> for De<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_rfc3339(deserializer).map(De)
+    }
+}
+
+impl Rfc3339Field for OffsetDateTime {
+    fn serialize_rfc3339<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let formatted = self.format(&Rfc3339).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&formatted)
+    }
+
+    fn deserialize_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let formatted = Cow::<'de, str>::deserialize(deserializer)?;
+        OffsetDateTime::parse(&formatted, &Rfc3339).map_err(de::Error::custom)
+    }
+}
+
+impl<T: Rfc3339Field> Rfc3339Field for Option<T> {
+    fn serialize_rfc3339<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Ser(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<De<T>>::deserialize(deserializer).map(|value| value.map(|De(value)| value))
+    }
+}
+
+impl<T: Rfc3339Field> Rfc3339Field for Vec<T> {
+    fn serialize_rfc3339<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Ser))
+    }
+
+    fn deserialize_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<De<T>>::deserialize(deserializer)
+            .map(|values| values.into_iter().map(|De(value)| value).collect())
+    }
+}
+
+impl<T: Rfc3339Field> Rfc3339Field for crate::MaybeUndefined<T> {
+    fn serialize_rfc3339<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            crate::MaybeUndefined::Value(value) => serializer.serialize_some(&Ser(value)),
+            crate::MaybeUndefined::Undefined | crate::MaybeUndefined::Null => {
+                serializer.serialize_none()
+            }
+        }
+    }
+
+    fn deserialize_rfc3339<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize_rfc3339(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        #[serde(with = "super")]
+        at: OffsetDateTime,
+        #[serde(default, with = "super")]
+        reminders: Option<Vec<OffsetDateTime>>,
+    }
+
+    #[test]
+    fn date_times_round_trip_as_rfc3339_strings() {
+        let value = json!({
+            "at": "2024-02-29T12:30:00Z",
+            "reminders": ["2024-02-28T12:30:00+01:00"],
+        });
+        let event: Event = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(event.at.unix_timestamp(), 1_709_209_800);
+        assert_eq!(
+            event.reminders.as_ref().unwrap()[0].offset().whole_hours(),
+            1
+        );
+        assert_eq!(serde_json::to_value(&event).unwrap(), value);
+
+        let event: Event = serde_json::from_value(json!({ "at": "2024-02-29T12:30:00Z" })).unwrap();
+        assert_eq!(event.reminders, None);
+    }
+}
@@ -0,0 +1,82 @@
+use graphql_client::*;
+
+type Email = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = r#"
+        query EchoMessage($msg: Message = { to: { email: "rosa.luxemburg@example.com" } }, $extern: String, $options: Options) {
+            echo(message: $msg, options: $options, extern: $extern) { result }
+        }
+    "#,
+    variables_derives = "Debug, PartialEq",
+    builders
+)]
+pub struct EchoMessage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = "query SaveRecursive($input: RecursiveInput!) { saveRecursiveInput(recursiveInput: $input) }",
+    variables_derives = "Debug, PartialEq",
+    builders
+)]
+pub struct SaveRecursive;
+
+#[test]
+fn optional_variables_have_setters_and_start_with_their_default() {
+    let variables = echo_message::Variables::builder().build();
+
+    assert_eq!(variables.msg, echo_message::Variables::default_msg());
+    assert_eq!(variables.extern_, None);
+    assert_eq!(variables.options, None);
+
+    let recipient = echo_message::Recipient::builder("sarah.connor@example.com".to_owned())
+        .category(echo_message::Category::PERSONAL)
+        .build();
+    let variables = echo_message::Variables::builder()
+        .msg(
+            echo_message::Message::builder()
+                .to(recipient)
+                .content("hello".to_owned())
+                .build(),
+        )
+        .extern_("x".to_owned())
+        .build();
+
+    assert_eq!(
+        variables,
+        echo_message::Variables {
+            msg: Some(echo_message::Message {
+                to: Some(echo_message::Recipient {
+                    email: "sarah.connor@example.com".to_owned(),
+                    name: None,
+                    category: Some(echo_message::Category::PERSONAL),
+                }),
+                content: Some("hello".to_owned()),
+            }),
+            extern_: Some("x".to_owned()),
+            options: None,
+        }
+    );
+}
+
+#[test]
+fn required_fields_are_builder_arguments() {
+    let input = save_recursive::RecursiveInput::builder("head".to_owned())
+        .tail(save_recursive::RecursiveInput::builder("tail".to_owned()).build())
+        .build();
+    let variables = save_recursive::Variables::builder(input).build();
+
+    assert_eq!(
+        variables.input,
+        save_recursive::RecursiveInput {
+            head: "head".to_owned(),
+            tail: Box::new(Some(save_recursive::RecursiveInput {
+                head: "tail".to_owned(),
+                tail: Box::new(None),
+            })),
+        }
+    );
+}
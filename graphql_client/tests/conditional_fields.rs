@@ -0,0 +1,66 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/conditional_fields/query.graphql",
+    schema_path = "tests/conditional_fields/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct ConditionalFieldsQuery;
+
+use conditional_fields_query::*;
+
+#[test]
+fn conditional_fields_are_present() {
+    let response = json!({
+        "user": {
+            "id": "1",
+            "name": "Ada",
+            "email": "ada@example.com",
+            "friends": [{ "name": "Charles" }],
+            "age": 36,
+        },
+        "node": { "__typename": "User", "email": "ada@example.com" },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(data.user.name, "Ada");
+    assert_eq!(data.user.email.as_deref(), Some("ada@example.com"));
+    assert_eq!(
+        data.user.friends,
+        Some(vec![ConditionalFieldsQueryUserFriends {
+            name: "Charles".to_owned()
+        }])
+    );
+    assert_eq!(data.user.never_selected, None);
+    assert_eq!(data.user.user_age, Some(UserAge { age: 36 }));
+    assert_eq!(
+        data.node.unwrap(),
+        ConditionalFieldsQueryNode::User(ConditionalFieldsQueryNodeOnUser {
+            email: Some("ada@example.com".to_owned())
+        })
+    );
+}
+
+#[test]
+fn conditional_fields_are_absent() {
+    let response = json!({
+        "user": {
+            "id": "1",
+            "name": "Ada",
+        },
+        "node": { "__typename": "User" },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(data.user.email, None);
+    assert_eq!(data.user.friends, None);
+    assert_eq!(data.user.user_age, None);
+    assert_eq!(
+        data.node.unwrap(),
+        ConditionalFieldsQueryNode::User(ConditionalFieldsQueryNodeOnUser { email: None })
+    );
+}
@@ -0,0 +1,38 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/fragment_imports/query.graphql",
+    schema_path = "tests/fragment_imports/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct FragmentImportsQuery;
+
+use fragment_imports_query::*;
+
+#[test]
+fn imported_fragments_are_generated() {
+    let response = json!({
+        "viewer": { "login": "ada", "avatar": { "url": "https://example.com/ada.png" } },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(
+        data.viewer,
+        UserFields {
+            login: "ada".into(),
+            avatar: Some(UserFieldsAvatar {
+                url: "https://example.com/ada.png".into(),
+            }),
+        }
+    );
+}
+
+#[test]
+fn only_used_imported_fragments_are_sent() {
+    assert!(QUERY.contains("fragment UserFields on User"));
+    assert!(QUERY.contains("fragment AvatarFields on Avatar"));
+    assert!(!QUERY.contains("UserIdFields"));
+}
@@ -0,0 +1,71 @@
+use graphql_client::{graphql, GraphQLQuery, QueryBody};
+
+/// The types of operations generated with `graphql!` are local to the macro, so they are named
+/// through the operation value.
+fn build_query<Q: GraphQLQuery>(
+    _operation: &Q,
+    variables: Q::Variables,
+) -> QueryBody<Q::Variables> {
+    Q::build_query(variables)
+}
+
+fn default_variables<Q: GraphQLQuery>(_operation: &Q) -> Q::Variables
+where
+    Q::Variables: Default,
+{
+    Default::default()
+}
+
+fn response_data<Q: GraphQLQuery>(_operation: &Q, json: &str) -> Q::ResponseData {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn named_operations_evaluate_to_their_struct() {
+    let echo = graphql!(
+        schema = "tests/operation_selection/schema.graphql",
+        variables_derives = "Default",
+        response_derives = "Debug, PartialEq, Eq",
+        query Echo($msg: String!) {
+            echo(msg: $msg)
+        }
+    );
+
+    let response_data = response_data(&echo, r#"{"echo": "tiramisù"}"#);
+
+    assert_eq!(response_data.echo.as_deref(), Some("tiramisù"));
+
+    let mut variables = default_variables(&echo);
+    variables.msg = "hi".to_owned();
+    let query = build_query(&echo, variables);
+
+    assert_eq!(query.operation_name, "Echo");
+    assert_eq!(
+        query.query,
+        "query Echo($msg: String!) {\n  echo(msg: $msg)\n}\n"
+    );
+    assert_eq!(
+        serde_json::to_value(&query.variables).unwrap(),
+        serde_json::json!({ "msg": "hi" })
+    );
+}
+
+#[test]
+fn anonymous_operations_are_named_after_their_first_field() {
+    let query = build_query(
+        &graphql!(
+            schema = "tests/operation_selection/schema.graphql",
+            variables_derives = "Default",
+            {
+                mountainHeight(name: "Mont Blanc")
+            }
+        ),
+        Default::default(),
+    );
+
+    assert_eq!(query.operation_name, "MountainHeightQuery");
+    assert_eq!(
+        query.query,
+        "query MountainHeightQuery {\n  mountainHeight(name: \"Mont Blanc\")\n}\n"
+    );
+}
@@ -0,0 +1,36 @@
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/operation_selection/schema.graphql",
+    query = r#"
+        query Echo($msg: String!) {
+          echo(msg: $msg)
+        }
+    "#,
+    response_derives = "Debug, PartialEq, Eq"
+)]
+pub struct Echo;
+
+#[test]
+fn inline_queries_are_generated() {
+    let response_data: echo::ResponseData =
+        serde_json::from_str(r#"{"echo": "tiramisù"}"#).unwrap();
+
+    assert_eq!(
+        response_data,
+        echo::ResponseData {
+            echo: Some("tiramisù".to_owned()),
+        }
+    );
+
+    let query = Echo::build_query(echo::Variables {
+        msg: "hi".to_owned(),
+    });
+
+    assert_eq!(query.operation_name, "Echo");
+    assert_eq!(
+        query.query,
+        "query Echo($msg: String!) {\n  echo(msg: $msg)\n}\n"
+    );
+}
@@ -0,0 +1,107 @@
+use graphql_client::*;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_defaults/schema.graphql",
+    query = r#"
+        query SearchQuery($search: Search = { term: "graphql" }) {
+            search(search: $search)
+        }
+    "#,
+    variables_derives = "Debug, PartialEq",
+    builders
+)]
+pub struct SearchQuery;
+
+#[test]
+fn schema_defaults_are_default_constructors() {
+    assert_eq!(search_query::Pagination::default_first(), Some(10));
+    assert_eq!(
+        search_query::Search::default_pagination(),
+        Some(search_query::Pagination {
+            first: Some(20),
+            order: Some(search_query::Order::DESC),
+            after: None,
+        })
+    );
+}
+
+#[test]
+fn input_objects_without_required_fields_implement_default() {
+    assert_eq!(
+        search_query::Pagination::default(),
+        search_query::Pagination {
+            first: Some(10),
+            order: Some(search_query::Order::DESC),
+            after: None,
+        }
+    );
+}
+
+#[test]
+fn fields_left_out_of_query_defaults_get_the_schema_default() {
+    assert_eq!(
+        search_query::Variables::default_search(),
+        Some(search_query::Search {
+            term: "graphql".to_owned(),
+            pagination: search_query::Search::default_pagination(),
+            case_sensitive: Some(false),
+        })
+    );
+}
+
+#[test]
+fn builders_start_with_the_schema_defaults() {
+    let search = search_query::Search::builder("rust".to_owned()).build();
+
+    assert_eq!(
+        search.pagination,
+        search_query::Search::default_pagination()
+    );
+    assert_eq!(search.case_sensitive, Some(false));
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_defaults/schema.graphql",
+    query = "query SearchUndefined($search: Search!) { search(search: $search) }",
+    variables_derives = "Debug, PartialEq",
+    maybe_undefined
+)]
+pub struct SearchUndefined;
+
+#[test]
+fn schema_defaults_work_with_maybe_undefined() {
+    assert_eq!(
+        search_undefined::Pagination::default(),
+        search_undefined::Pagination {
+            first: MaybeUndefined::Value(10),
+            order: MaybeUndefined::Value(search_undefined::Order::DESC),
+            after: MaybeUndefined::Undefined,
+        }
+    );
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_defaults/schema.graphql",
+    query = r#"
+        query PaginationQuery($search: Search!) {
+            search(search: $search)
+        }
+    "#,
+    variables_derives = "Debug, PartialEq, std::default::Default"
+)]
+pub struct PaginationQuery;
+
+#[test]
+fn path_qualified_default_derives_are_recognized() {
+    assert_eq!(
+        pagination_query::Pagination::default(),
+        pagination_query::Pagination {
+            first: None,
+            order: None,
+            after: None,
+        }
+    );
+}
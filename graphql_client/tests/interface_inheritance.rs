@@ -0,0 +1,82 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/interface_inheritance/query.graphql",
+    schema_path = "tests/interface_inheritance/schema.graphql",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct InterfaceInheritanceQuery;
+
+use interface_inheritance_query::*;
+
+#[test]
+fn spreads_on_inherited_interfaces_are_deserialized() {
+    let response = json!({
+        "resources": [
+            { "__typename": "File", "id": "1", "name": "notes.txt" },
+            { "__typename": "Photo", "id": "2", "width": 640 },
+        ],
+        "node": { "__typename": "Photo", "id": "2", "url": "https://example.com/2.png" },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(
+        data.resources,
+        vec![
+            InterfaceInheritanceQueryResources {
+                node_fields: NodeFields {
+                    id: "1".into(),
+                    on: NodeFieldsOn::File,
+                },
+                on: InterfaceInheritanceQueryResourcesOn::File(
+                    InterfaceInheritanceQueryResourcesOnFile {
+                        name: Some("notes.txt".into()),
+                    }
+                ),
+            },
+            InterfaceInheritanceQueryResources {
+                node_fields: NodeFields {
+                    id: "2".into(),
+                    on: NodeFieldsOn::Photo,
+                },
+                on: InterfaceInheritanceQueryResourcesOn::Photo(
+                    InterfaceInheritanceQueryResourcesOnPhoto { width: Some(640) }
+                ),
+            },
+        ]
+    );
+
+    assert_eq!(
+        data.node,
+        Some(InterfaceInheritanceQueryNode {
+            resource_fields: Some(ResourceFields {
+                url: "https://example.com/2.png".into(),
+                on: ResourceFieldsOn::Photo,
+            }),
+            on: InterfaceInheritanceQueryNodeOn::Photo(InterfaceInheritanceQueryNodeOnPhoto {
+                id: "2".into(),
+            }),
+        })
+    );
+}
+
+#[test]
+fn objects_outside_the_inherited_interface_have_empty_variants() {
+    let response = json!({
+        "resources": [],
+        "node": { "__typename": "Team" },
+    });
+
+    let data: ResponseData = serde_json::from_value(response).unwrap();
+
+    assert_eq!(
+        data.node,
+        Some(InterfaceInheritanceQueryNode {
+            resource_fields: None,
+            on: InterfaceInheritanceQueryNodeOn::Team,
+        })
+    );
+}
@@ -0,0 +1,57 @@
+use graphql_client::*;
+use serde_json::json;
+
+type Email = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = r#"
+        query EchoMessage($msg: Message = { content: "hi" }, $extern: String) {
+            echo(message: $msg, extern: $extern) { result }
+        }
+    "#,
+    maybe_undefined,
+    builders
+)]
+pub struct EchoMessage;
+
+#[test]
+fn undefined_inputs_are_left_out_and_null_ones_are_sent() {
+    let variables = echo_message::Variables {
+        msg: MaybeUndefined::Value(echo_message::Message {
+            to: MaybeUndefined::Null,
+            content: MaybeUndefined::Undefined,
+        }),
+        extern_: MaybeUndefined::Undefined,
+    };
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({ "msg": { "to": null } })
+    );
+}
+
+#[test]
+fn query_defaults_and_builders_use_maybe_undefined() {
+    assert_eq!(
+        serde_json::to_value(echo_message::Variables::default_msg()).unwrap(),
+        json!({ "content": "hi" })
+    );
+
+    let recipient = echo_message::Recipient::builder("sarah.connor@example.com".to_owned())
+        .name(MaybeUndefined::Null)
+        .build();
+    let variables = echo_message::Variables::builder()
+        .msg(echo_message::Message::builder().to(recipient).build())
+        .extern_("x".to_owned())
+        .build();
+
+    assert_eq!(
+        serde_json::to_value(variables).unwrap(),
+        json!({
+            "msg": { "to": { "email": "sarah.connor@example.com", "name": null } },
+            "extern": "x"
+        })
+    );
+}
@@ -0,0 +1,64 @@
+use graphql_client::{graphql, GraphQLQuery};
+use std::net::Ipv4Addr;
+
+// No `NetworkAddress` alias is in scope: the mapping below stands in for it.
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/custom_scalars/query.graphql",
+    schema_path = "tests/custom_scalars/schema.graphql",
+    scalars(NetworkAddress = "std::net::Ipv4Addr")
+)]
+pub struct CustomScalarQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_defaults/schema.graphql",
+    query = r#"
+        query SearchQuery($search: Search!) {
+            search(search: $search)
+        }
+    "#,
+    scalars(Int = "i32", Boolean = "bool")
+)]
+pub struct SearchQuery;
+
+/// The variables of an operation generated with `graphql!`, whose types are local to the macro.
+fn default_variables<Q: GraphQLQuery>(_operation: &Q) -> Q::Variables
+where
+    Q::Variables: Default,
+{
+    Default::default()
+}
+
+#[test]
+fn custom_scalars_can_be_mapped_to_rust_types() {
+    let response_data: custom_scalar_query::ResponseData =
+        serde_json::from_str(r#"{"address": "127.0.1.2"}"#).unwrap();
+
+    assert_eq!(response_data.address, Some(Ipv4Addr::new(127, 0, 1, 2)));
+}
+
+#[test]
+fn built_in_scalars_can_be_mapped_to_rust_types() {
+    let first: Option<i32> = search_query::Pagination::default_first();
+    assert_eq!(first, Some(10));
+
+    let page_query = graphql!(
+        schema = "tests/input_defaults/schema.graphql",
+        variables_derives = "Default",
+        scalars(Int = "u8"),
+        query PageQuery($search: Search!) {
+            search(search: $search)
+        }
+    );
+    let mut variables = default_variables(&page_query);
+    let pagination = variables
+        .search
+        .pagination
+        .get_or_insert_with(Default::default);
+    pagination.first = Some(u8::MAX);
+    assert_eq!(
+        serde_json::to_value(&pagination).unwrap(),
+        serde_json::json!({ "first": 255, "order": null, "after": null })
+    );
+}
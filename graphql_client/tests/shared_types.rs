@@ -0,0 +1,81 @@
+use graphql_client::*;
+
+type Email = String;
+
+mod graphql_types {
+    graphql_client::schema_types!(
+        schema = "tests/input_object_variables/input_object_variables_schema.graphql",
+        variables_derives = "Debug, PartialEq, Clone",
+        response_derives = "Debug, PartialEq"
+    );
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = "query SaveRecursive($input: RecursiveInput!) { saveRecursiveInput(recursiveInput: $input) }",
+    shared_types_module = "crate::graphql_types",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SaveRecursive;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = "query SaveIndirectlyRecursive($input: IndirectlyRecursiveInput!) { saveIndirectlyRecursiveInput(indirectlyRecursiveInput: $input) }",
+    shared_types_module = "crate::graphql_types",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SaveIndirectlyRecursive;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/input_object_variables/input_object_variables_schema.graphql",
+    query = "query Echo($msg: Message!) { echo(message: $msg) { result } }",
+    shared_types_module = "crate::graphql_types"
+)]
+pub struct Echo;
+
+#[test]
+fn operations_share_the_schema_enums() {
+    let recursive: save_recursive::ResponseData =
+        serde_json::from_str(r#"{"saveRecursiveInput": "PERSONAL"}"#).unwrap();
+    let indirectly_recursive: save_indirectly_recursive::ResponseData =
+        serde_json::from_str(r#"{"saveIndirectlyRecursiveInput": "PERSONAL"}"#).unwrap();
+
+    let categories: [Option<graphql_types::Category>; 2] = [
+        recursive.save_recursive_input,
+        indirectly_recursive.save_indirectly_recursive_input,
+    ];
+
+    assert_eq!(
+        categories,
+        [
+            Some(graphql_types::Category::PERSONAL),
+            Some(graphql_types::Category::PERSONAL)
+        ]
+    );
+}
+
+#[test]
+fn operations_share_the_schema_input_objects() {
+    let message = graphql_types::Message {
+        to: Some(graphql_types::Recipient {
+            email: "sarah.connor@example.com".to_owned(),
+            name: None,
+            category: Some(save_recursive::Category::PROFESSIONAL),
+        }),
+        content: None,
+    };
+
+    let query = Echo::build_query(echo::Variables {
+        msg: message.clone(),
+    });
+    assert_eq!(query.variables.msg, message);
+
+    let input: save_recursive::RecursiveInput = graphql_types::RecursiveInput {
+        head: "a".to_owned(),
+        tail: Box::new(None),
+    };
+    SaveRecursive::build_query(save_recursive::Variables { input });
+}
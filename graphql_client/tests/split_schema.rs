@@ -0,0 +1,55 @@
+use graphql_client::*;
+use serde_json::json;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "tests/split_schema/query.graphql",
+    schema_path = "tests/split_schema/schema",
+    response_derives = "Debug, PartialEq"
+)]
+pub struct SplitSchemaQuery;
+
+pub mod glob {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/split_schema/query.graphql",
+        schema_path = "tests/split_schema/schema/**/*.graphqls"
+    )]
+    pub struct SplitSchemaQuery;
+}
+
+pub mod list {
+    use graphql_client::GraphQLQuery;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        query_path = "tests/split_schema/query.graphql",
+        schema_path(
+            "tests/split_schema/schema/schema.graphqls",
+            "tests/split_schema/schema/posts/posts.graphqls"
+        )
+    )]
+    pub struct SplitSchemaQuery;
+}
+
+#[test]
+fn schema_directory_is_merged() {
+    let response = json!({
+        "user": { "name": "Ada", "posts": [{ "title": "Notes" }] },
+        "post": { "author": { "name": "Ada" } },
+    });
+
+    let data: split_schema_query::ResponseData = serde_json::from_value(response.clone()).unwrap();
+
+    assert_eq!(data.user.unwrap().posts[0].title, "Notes");
+    assert_eq!(data.post.unwrap().author.name, "Ada");
+
+    let data: glob::split_schema_query::ResponseData =
+        serde_json::from_value(response.clone()).unwrap();
+    assert_eq!(data.post.unwrap().author.name, "Ada");
+
+    let data: list::split_schema_query::ResponseData = serde_json::from_value(response).unwrap();
+    assert_eq!(data.user.unwrap().name, "Ada");
+}
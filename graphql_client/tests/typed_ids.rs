@@ -0,0 +1,108 @@
+use graphql_client::*;
+use serde_json::json;
+use std::collections::HashSet;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/typed_ids/schema.graphql",
+    query = r#"
+        query UserQuery($userId: ID!) {
+            user(id: $userId) { id login repositories { id name owner { id } } }
+        }
+    "#,
+    typed_ids,
+    typed_id_inputs(userId = "User")
+)]
+pub struct UserQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/typed_ids/schema.graphql",
+    query = r#"
+        mutation StarRepository($input: StarInput!) {
+            starRepository(input: $input) { id }
+        }
+    "#,
+    typed_ids,
+    typed_id_inputs(StarInput.repositoryId = "Repository")
+)]
+pub struct StarRepository;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "tests/typed_ids/schema.graphql",
+    query = r#"
+        query NodeQuery($id: ID!) {
+            node(id: $id) { __typename id }
+            user: node(id: $id) { __typename ... on User { id login } }
+        }
+    "#,
+    typed_ids
+)]
+pub struct NodeQuery;
+
+#[test]
+fn id_fields_are_typed_by_their_owner() {
+    let response_data: user_query::ResponseData = serde_json::from_value(json!({
+        "user": {
+            "id": "u1",
+            "login": "octocat",
+            "repositories": [{ "id": "r1", "name": "hello", "owner": { "id": "u1" } }],
+        }
+    }))
+    .unwrap();
+    let user = response_data.user.unwrap();
+
+    let user_id: user_query::UserId = user.id;
+    let repository_id: user_query::RepositoryId = user.repositories[0].id.clone();
+    assert_eq!(user_id.to_string(), "u1");
+    assert_eq!(repository_id, "r1".parse().unwrap());
+    assert_eq!(user.repositories[0].owner.id, user_id);
+
+    let ids: HashSet<user_query::UserId> =
+        vec![user_id.clone(), user_id.clone()].into_iter().collect();
+    assert_eq!(ids.len(), 1);
+    assert_eq!(String::from(user_id), "u1");
+}
+
+#[test]
+fn mapped_inputs_are_typed() {
+    let variables = user_query::Variables {
+        user_id: user_query::UserId("u1".to_owned()),
+    };
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({ "userId": "u1" })
+    );
+
+    let variables = star_repository::Variables {
+        input: star_repository::StarInput {
+            repository_id: star_repository::RepositoryId::from("r1".to_owned()),
+            // Only the mapped input fields are typed.
+            user_id: Some("u1".to_owned()),
+        },
+    };
+    assert_eq!(
+        serde_json::to_value(&variables).unwrap(),
+        json!({ "input": { "repositoryId": "r1", "userId": "u1" } })
+    );
+}
+
+#[test]
+fn ids_of_implementers_compare_with_ids_of_their_interfaces() {
+    let response_data: node_query::ResponseData = serde_json::from_value(json!({
+        "node": { "__typename": "User", "id": "u1" },
+        "user": { "__typename": "User", "id": "u1", "login": "octocat" },
+    }))
+    .unwrap();
+    let user = match response_data.user.unwrap() {
+        node_query::NodeQueryUser::User(user) => user,
+        _ => panic!("expected a user"),
+    };
+
+    let node_id: node_query::NodeId = response_data.node.unwrap().id;
+    let user_id: node_query::UserId = user.id;
+    assert_eq!(user_id, node_id);
+    assert_eq!(node_id, user_id);
+    assert_eq!(node_query::NodeId::from(user_id), node_id);
+}
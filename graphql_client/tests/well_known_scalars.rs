@@ -0,0 +1,102 @@
+//! Run with `--features chrono,uuid,url,rust_decimal,serde_bytes`, or `--features time`, or both.
+
+#[cfg(all(
+    feature = "chrono",
+    feature = "uuid",
+    feature = "url",
+    feature = "rust_decimal",
+    feature = "serde_bytes"
+))]
+pub mod with_chrono {
+    use graphql_client::*;
+    use serde_json::json;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/well_known_scalars/schema.graphql",
+        query = r#"
+            query EventQuery($id: UUID!) {
+                event(id: $id) { id createdAt startsAt link price attachment }
+            }
+        "#,
+        scalar_crates = "chrono, uuid, url, rust_decimal, serde_bytes"
+    )]
+    pub struct EventQuery;
+
+    #[test]
+    fn well_known_scalars_are_mapped_by_name_and_specified_by_url() {
+        let response_data: event_query::ResponseData = serde_json::from_value(json!({
+            "event": {
+                "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                "createdAt": "2024-02-29T12:30:00Z",
+                "startsAt": "2024-03-01T09:00:00+01:00",
+                "link": "https://example.com/events/1",
+                "price": "12.50",
+                "attachment": "aGk=",
+            }
+        }))
+        .unwrap();
+        let event = response_data.event.unwrap();
+
+        let id: uuid::Uuid = event.id;
+        assert_eq!(id.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        let created_at: chrono::DateTime<chrono::Utc> = event.created_at;
+        assert_eq!(created_at.timestamp(), 1_709_209_800);
+        let starts_at: Option<chrono::DateTime<chrono::Utc>> = event.starts_at;
+        assert_eq!(starts_at.unwrap().timestamp(), 1_709_280_000);
+        let link: Option<url::Url> = event.link;
+        assert_eq!(link.unwrap().path(), "/events/1");
+        let price: Option<rust_decimal::Decimal> = event.price;
+        assert_eq!(price.unwrap().to_string(), "12.50");
+        let attachment: Option<serde_bytes::ByteBuf> = event.attachment;
+        assert_eq!(attachment.unwrap().as_slice(), b"hi");
+    }
+}
+
+#[cfg(feature = "time")]
+pub mod with_time {
+    use graphql_client::*;
+    use serde_json::json;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "tests/well_known_scalars/schema.graphql",
+        query = r#"
+            mutation CreateEvent($input: EventInput!) {
+                createEvent(input: $input) { createdAt startsAt }
+            }
+        "#,
+        scalar_crates = "time"
+    )]
+    pub struct CreateEvent;
+
+    #[test]
+    fn date_times_are_rfc3339_strings() {
+        let starts_at = time::OffsetDateTime::from_unix_timestamp(1_709_209_800).unwrap();
+        let variables = create_event::Variables {
+            input: create_event::EventInput {
+                starts_at,
+                reminders: Some(vec![starts_at]),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(&variables).unwrap(),
+            json!({
+                "input": {
+                    "startsAt": "2024-02-29T12:30:00Z",
+                    "reminders": ["2024-02-29T12:30:00Z"],
+                }
+            })
+        );
+
+        let response_data: create_event::ResponseData = serde_json::from_value(json!({
+            "createEvent": { "createdAt": "2024-02-29T12:30:00Z" }
+        }))
+        .unwrap();
+        let event = response_data.create_event.unwrap();
+
+        assert_eq!(event.created_at, starts_at);
+        assert_eq!(event.starts_at, None);
+    }
+}
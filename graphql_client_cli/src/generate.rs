@@ -1,18 +1,20 @@
 use crate::error::Error;
 use crate::CliResult;
 use graphql_client_codegen::{
-    generate_module_token_stream, CodegenMode, GraphQLClientCodegenOptions,
+    format_generated_code, generate_module_token_stream_with_schema_paths,
+    manifest::ManifestDefaults, well_known_scalars::parse_scalar_crates, CodegenMode,
+    GraphQLClientCodegenOptions,
 };
 use std::ffi::OsString;
 use std::fs::File;
 use std::io::Write as _;
 use std::path::PathBuf;
-use std::process::Stdio;
 use syn::{token::Paren, token::Pub, VisRestricted, Visibility};
 
 pub(crate) struct CliCodegenParams {
     pub query_path: PathBuf,
-    pub schema_path: PathBuf,
+    pub schema_paths: Vec<PathBuf>,
+    pub manifest_path: Option<PathBuf>,
     pub selected_operation: Option<String>,
     pub variables_derives: Option<String>,
     pub response_derives: Option<String>,
@@ -21,13 +23,23 @@ pub(crate) struct CliCodegenParams {
     pub module_visibility: Option<String>,
     pub output_directory: Option<PathBuf>,
     pub custom_scalars_module: Option<String>,
-    pub fragments_other_variant: bool,
+    pub scalars: Vec<String>,
+    pub scalar_crates: Vec<String>,
+    pub shared_types_module: Option<String>,
+    pub fragments_other_variant: Option<bool>,
+    pub skip_descriptions: Option<bool>,
+    pub builders: Option<bool>,
+    pub maybe_undefined: Option<bool>,
+    pub typed_ids: Option<bool>,
+    pub typed_id_inputs: Vec<String>,
+    pub minify_query: Option<bool>,
+    pub deny_unknown_directives: Option<bool>,
     pub external_enums: Option<Vec<String>>,
     pub custom_variable_types: Option<String>,
     pub custom_response_type: Option<String>,
 }
 
-const WARNING_SUPPRESSION: &str = "#![allow(clippy::all, warnings)]";
+pub(crate) const WARNING_SUPPRESSION: &str = "#![allow(clippy::all, warnings)]";
 
 pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
     let CliCodegenParams {
@@ -38,10 +50,21 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
         output_directory,
         module_visibility: _module_visibility,
         query_path,
-        schema_path,
+        schema_paths,
+        manifest_path,
         selected_operation,
         custom_scalars_module,
+        scalars,
+        scalar_crates,
+        shared_types_module,
         fragments_other_variant,
+        skip_descriptions,
+        builders,
+        maybe_undefined,
+        typed_ids,
+        typed_id_inputs,
+        minify_query,
+        deny_unknown_directives,
         external_enums,
         custom_variable_types,
         custom_response_type,
@@ -51,6 +74,24 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
 
     let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
 
+    // The defaults of the Cargo.toml metadata, which the flags below override.
+    let defaults = manifest_defaults(manifest_path)?;
+    defaults
+        .apply(&mut options)
+        .map_err(|err| Error::message(err.to_string()))?;
+
+    let schema_paths = if schema_paths.is_empty() {
+        defaults.schema_paths()
+    } else {
+        schema_paths
+    };
+
+    if schema_paths.is_empty() {
+        return Err(Error::message(
+            "A schema must be given with --schema-path, or with `schema_path` in the [package.metadata.graphql-client] table of Cargo.toml.".to_owned(),
+        ));
+    }
+
     options.set_module_visibility(match _module_visibility {
         Some(v) => match v.to_lowercase().as_str() {
             "pub" => Visibility::Public(Pub::default()),
@@ -65,7 +106,27 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
         None => Visibility::Public(Pub::default()),
     });
 
-    options.set_fragments_other_variant(fragments_other_variant);
+    if let Some(fragments_other_variant) = fragments_other_variant {
+        options.set_fragments_other_variant(fragments_other_variant);
+    }
+    if let Some(skip_descriptions) = skip_descriptions {
+        options.set_skip_descriptions(skip_descriptions);
+    }
+    if let Some(builders) = builders {
+        options.set_builders(builders);
+    }
+    if let Some(maybe_undefined) = maybe_undefined {
+        options.set_maybe_undefined(maybe_undefined);
+    }
+    if let Some(typed_ids) = typed_ids {
+        options.set_typed_ids(typed_ids);
+    }
+    if let Some(minify_query) = minify_query {
+        options.set_minify_query(minify_query);
+    }
+    if let Some(deny_unknown_directives) = deny_unknown_directives {
+        options.set_deny_unknown_directives(deny_unknown_directives);
+    }
 
     if let Some(selected_operation) = selected_operation {
         options.set_operation_name(selected_operation);
@@ -94,6 +155,17 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
         options.set_custom_scalars_module(custom_scalars_module);
     }
 
+    set_scalar_types(&mut options, &scalars)?;
+    set_scalar_crates(&mut options, &scalar_crates)?;
+    set_typed_id_inputs(&mut options, &typed_id_inputs)?;
+
+    if let Some(shared_types_module) = shared_types_module {
+        let shared_types_module = syn::parse_str(&shared_types_module)
+            .map_err(|_| Error::message("Invalid shared types module path".to_owned()))?;
+
+        options.set_shared_types_module(shared_types_module);
+    }
+
     if let Some(custom_variable_types) = custom_variable_types {
         options.set_custom_variable_types(
             custom_variable_types.split(",").map(String::from).collect(),
@@ -104,15 +176,18 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
         options.set_custom_response_type(custom_response_type);
     }
 
-    let gen = generate_module_token_stream(query_path.clone(), &schema_path, options)
-        .map_err(|err| Error::message(format!("Error generating module code: {}", err)))?;
+    let gen =
+        generate_module_token_stream_with_schema_paths(query_path.clone(), &schema_paths, options)
+            .map_err(|err| Error::message(format!("Error generating module code:\n{}", err)))?;
 
-    let generated_code = format!("{}\n{}", WARNING_SUPPRESSION, gen);
     let generated_code = if !no_formatting {
-        format(&generated_code)?
+        format_generated_code(gen).map_err(|err| {
+            Error::message(format!("Error formatting the generated code:\n{}", err))
+        })?
     } else {
-        generated_code
+        gen.to_string()
     };
+    let generated_code = format!("{}\n{}", WARNING_SUPPRESSION, generated_code);
 
     let query_file_name: OsString =
         query_path
@@ -139,25 +214,84 @@ pub(crate) fn generate_code(params: CliCodegenParams) -> CliResult<()> {
     Ok(())
 }
 
-fn format(code: &str) -> CliResult<String> {
-    let binary = "rustfmt";
+/// Maps the scalars of `--scalar NAME=TYPE` flags to their Rust types.
+pub(crate) fn set_scalar_types(
+    options: &mut GraphQLClientCodegenOptions,
+    scalars: &[String],
+) -> CliResult<()> {
+    for scalar in scalars {
+        let (name, rust_type) = scalar
+            .split_once('=')
+            .and_then(|(name, rust_type)| Some((name.trim(), syn::parse_str(rust_type).ok()?)))
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| {
+                Error::message(format!(
+                    "Invalid scalar mapping `{}`, expected NAME=TYPE, like Int=i32",
+                    scalar
+                ))
+            })?;
+
+        options.set_scalar_type(name, rust_type);
+    }
+
+    Ok(())
+}
+
+/// Maps the ID variables and input fields of `--typed-id-input NAME=TYPE` flags to their types.
+pub(crate) fn set_typed_id_inputs(
+    options: &mut GraphQLClientCodegenOptions,
+    typed_id_inputs: &[String],
+) -> CliResult<()> {
+    for typed_id_input in typed_id_inputs {
+        let (name, owner) = typed_id_input
+            .split_once('=')
+            .map(|(name, owner)| (name.trim(), owner.trim()))
+            .filter(|(name, owner)| !name.is_empty() && !owner.is_empty())
+            .ok_or_else(|| {
+                Error::message(format!(
+                    "Invalid typed ID input `{}`, expected NAME=TYPE, like userId=User or StarInput.userId=User",
+                    typed_id_input
+                ))
+            })?;
 
-    let mut child = std::process::Command::new(binary)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .map_err(|err| Error::source_with_message(err, "Error spawning rustfmt".to_owned()))?;
-    let child_stdin = child.stdin.as_mut().unwrap();
-    write!(child_stdin, "{}", code)?;
+        options.set_typed_id_input(name, owner);
+    }
 
-    let output = child.wait_with_output()?;
+    Ok(())
+}
 
-    if !output.status.success() {
-        panic!(
-            "rustfmt error\n\n{}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+/// Maps the well-known scalars to the crates of `--scalar-crate` flags.
+pub(crate) fn set_scalar_crates(
+    options: &mut GraphQLClientCodegenOptions,
+    scalar_crates: &[String],
+) -> CliResult<()> {
+    if scalar_crates.is_empty() {
+        return Ok(());
     }
 
-    Ok(String::from_utf8(output.stdout)?)
+    let scalar_crates = parse_scalar_crates(&scalar_crates.join(",")).map_err(Error::message)?;
+
+    options.set_scalar_crates(scalar_crates);
+    Ok(())
+}
+
+/// The defaults of the given Cargo.toml, or else of the nearest one from the current directory up.
+pub(crate) fn manifest_defaults(manifest_path: Option<PathBuf>) -> CliResult<ManifestDefaults> {
+    let manifest_path = match manifest_path {
+        Some(manifest_path) => manifest_path,
+        None => {
+            let current_dir = std::env::current_dir()?;
+            let nearest = current_dir
+                .ancestors()
+                .map(|dir| dir.join("Cargo.toml"))
+                .find(|manifest_path| manifest_path.is_file());
+
+            match nearest {
+                Some(manifest_path) => manifest_path,
+                None => return Ok(ManifestDefaults::default()),
+            }
+        }
+    };
+
+    ManifestDefaults::read(&manifest_path).map_err(|err| Error::message(err.to_string()))
 }
@@ -0,0 +1,187 @@
+use crate::error::Error;
+use crate::generate::{
+    manifest_defaults, set_scalar_crates, set_scalar_types, set_typed_id_inputs,
+    WARNING_SUPPRESSION,
+};
+use crate::CliResult;
+use graphql_client_codegen::{
+    format_generated_code, generate_schema_types_token_stream, CodegenMode,
+    GraphQLClientCodegenOptions,
+};
+use std::path::PathBuf;
+
+pub(crate) struct SchemaTypesParams {
+    pub schema_paths: Vec<PathBuf>,
+    pub manifest_path: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub variables_derives: Option<String>,
+    pub response_derives: Option<String>,
+    pub custom_scalars_module: Option<String>,
+    pub scalars: Vec<String>,
+    pub scalar_crates: Vec<String>,
+    pub skip_descriptions: Option<bool>,
+    pub builders: Option<bool>,
+    pub maybe_undefined: Option<bool>,
+    pub typed_ids: Option<bool>,
+    pub typed_id_inputs: Vec<String>,
+    pub no_formatting: bool,
+}
+
+pub(crate) fn generate_schema_types(params: SchemaTypesParams) -> CliResult<()> {
+    let SchemaTypesParams {
+        schema_paths,
+        manifest_path,
+        output,
+        variables_derives,
+        response_derives,
+        custom_scalars_module,
+        scalars,
+        scalar_crates,
+        skip_descriptions,
+        builders,
+        maybe_undefined,
+        typed_ids,
+        typed_id_inputs,
+        no_formatting,
+    } = params;
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+
+    // The defaults of the Cargo.toml metadata, which the flags below override.
+    let defaults = manifest_defaults(manifest_path)?;
+    defaults
+        .apply(&mut options)
+        .map_err(|err| Error::message(err.to_string()))?;
+
+    let schema_paths = if schema_paths.is_empty() {
+        defaults.schema_paths()
+    } else {
+        schema_paths
+    };
+
+    if schema_paths.is_empty() {
+        return Err(Error::message(
+            "A schema must be given with --schema-path, or with `schema_path` in the [package.metadata.graphql-client] table of Cargo.toml.".to_owned(),
+        ));
+    }
+
+    if let Some(skip_descriptions) = skip_descriptions {
+        options.set_skip_descriptions(skip_descriptions);
+    }
+    if let Some(builders) = builders {
+        options.set_builders(builders);
+    }
+    if let Some(maybe_undefined) = maybe_undefined {
+        options.set_maybe_undefined(maybe_undefined);
+    }
+    if let Some(typed_ids) = typed_ids {
+        options.set_typed_ids(typed_ids);
+    }
+
+    if let Some(variables_derives) = variables_derives {
+        options.set_variables_derives(variables_derives);
+    }
+
+    if let Some(response_derives) = response_derives {
+        options.set_response_derives(response_derives);
+    }
+
+    if let Some(custom_scalars_module) = custom_scalars_module {
+        let custom_scalars_module = syn::parse_str(&custom_scalars_module)
+            .map_err(|_| Error::message("Invalid custom scalar module path".to_owned()))?;
+
+        options.set_custom_scalars_module(custom_scalars_module);
+    }
+
+    set_scalar_types(&mut options, &scalars)?;
+    set_scalar_crates(&mut options, &scalar_crates)?;
+    set_typed_id_inputs(&mut options, &typed_id_inputs)?;
+
+    let gen = generate_schema_types_token_stream(&schema_paths, options)
+        .map_err(|err| Error::message(format!("Error generating schema types:\n{}", err)))?;
+
+    let generated_code = if !no_formatting {
+        format_generated_code(gen).map_err(|err| {
+            Error::message(format!("Error formatting the generated code:\n{}", err))
+        })?
+    } else {
+        gen.to_string()
+    };
+    let generated_code = format!("{}\n{}", WARNING_SUPPRESSION, generated_code);
+
+    match output {
+        Some(output) => {
+            log::info!("Writing generated schema types to {:?}", output);
+
+            std::fs::write(&output, generated_code).map_err(|err| {
+                Error::source_with_message(err, format!("Creating file at {}", output.display()))
+            })?;
+        }
+        None => print!("{}", generated_code),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_types_are_written_from_the_manifest_schema() {
+        let dir = std::env::temp_dir().join(format!(
+            "graphql_client_cli_schema_types_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package.metadata.graphql-client]\nschema_path = \"schema.graphql\"\nvariables_derives = \"Debug\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("schema.graphql"),
+            "type Query { paint(input: PaintInput!): Color }\n\
+             enum Color { RED GREEN }\n\
+             input PaintInput { color: Color! }\n",
+        )
+        .unwrap();
+        let output = dir.join("graphql_types.rs");
+
+        generate_schema_types(SchemaTypesParams {
+            schema_paths: Vec::new(),
+            manifest_path: Some(dir.join("Cargo.toml")),
+            output: Some(output.clone()),
+            variables_derives: None,
+            response_derives: None,
+            custom_scalars_module: None,
+            scalars: Vec::new(),
+            scalar_crates: Vec::new(),
+            skip_descriptions: None,
+            builders: Some(true),
+            maybe_undefined: None,
+            typed_ids: None,
+            typed_id_inputs: Vec::new(),
+            no_formatting: false,
+        })
+        .unwrap();
+
+        let generated_code = std::fs::read_to_string(&output).unwrap();
+        let file = syn::parse_file(&generated_code).unwrap();
+        let item_names: Vec<String> = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Enum(item) => Some(item.ident.to_string()),
+                syn::Item::Struct(item) => Some(item.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert!(generated_code.starts_with(WARNING_SUPPRESSION));
+        assert_eq!(item_names, ["Color", "PaintInput", "PaintInputBuilder"]);
+        assert!(generated_code.contains("#[derive(Serialize, Debug)]"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
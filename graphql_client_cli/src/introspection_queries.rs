@@ -5,7 +5,7 @@ use graphql_client::GraphQLQuery;
     schema_path = "src/graphql/introspection_schema.graphql",
     query_path = "src/graphql/introspection_query.graphql",
     response_derives = "Serialize",
-    variable_derives = "Deserialize"
+    variables_derives = "Deserialize"
 )]
 #[allow(dead_code)]
 pub struct IntrospectionQuery;
@@ -15,7 +15,7 @@ pub struct IntrospectionQuery;
     schema_path = "src/graphql/introspection_schema.graphql",
     query_path = "src/graphql/introspection_query_with_is_one_of.graphql",
     response_derives = "Serialize",
-    variable_derives = "Deserialize"
+    variables_derives = "Deserialize"
 )]
 #[allow(dead_code)]
 pub struct IntrospectionQueryWithIsOneOf;
@@ -25,7 +25,7 @@ pub struct IntrospectionQueryWithIsOneOf;
     schema_path = "src/graphql/introspection_schema.graphql",
     query_path = "src/graphql/introspection_query_with_specified_by.graphql",
     response_derives = "Serialize",
-    variable_derives = "Deserialize"
+    variables_derives = "Deserialize"
 )]
 #[allow(dead_code)]
 pub struct IntrospectionQueryWithSpecifiedBy;
@@ -35,7 +35,7 @@ pub struct IntrospectionQueryWithSpecifiedBy;
     schema_path = "src/graphql/introspection_schema.graphql",
     query_path = "src/graphql/introspection_query_with_isOneOf_specifiedByUrl.graphql",
     response_derives = "Serialize",
-    variable_derives = "Deserialize"
+    variables_derives = "Deserialize"
 )]
 #[allow(dead_code)]
 pub struct IntrospectionQueryWithIsOneOfSpecifiedByURL;
@@ -1,7 +1,9 @@
 mod error;
 mod generate;
+mod generate_schema_types;
 mod introspection_queries;
 mod introspection_schema;
+mod mock_server;
 
 use clap::Parser;
 use env_logger::fmt::{Color, Style, StyledValue};
@@ -16,6 +18,7 @@ type CliResult<T> = Result<T, Error>;
 
 #[derive(Parser)]
 #[clap(author, about, version)]
+#[allow(clippy::large_enum_variant)] // Parsed once, the size does not matter.
 enum Cli {
     /// Get the schema from a live GraphQL API. The schema is printed to stdout.
     #[clap(name = "introspect-schema")]
@@ -49,9 +52,15 @@ enum Cli {
     },
     #[clap(name = "generate")]
     Generate {
-        /// Path to GraphQL schema file (.json or .graphql).
+        /// Path to GraphQL schema file (.json or .graphql), or a directory or glob pattern
+        /// matching the SDL files the schema is split across. Can be given several times.
+        /// Defaults to the `schema_path` of the Cargo.toml metadata.
         #[clap(short = 's', long = "schema-path")]
-        schema_path: PathBuf,
+        schema_paths: Vec<PathBuf>,
+        /// The Cargo.toml whose [package.metadata.graphql-client] table provides the defaults
+        /// of the options. Defaults to the nearest Cargo.toml, from the current directory up.
+        #[clap(long = "manifest-path")]
+        manifest_path: Option<PathBuf>,
         /// Path to the GraphQL query file.
         query_path: PathBuf,
         /// Name of target query. If you don't set this parameter, cli generate all queries in query file.
@@ -69,7 +78,7 @@ enum Cli {
         /// Default value is warn.
         #[clap(short = 'd', long = "deprecation-strategy")]
         deprecation_strategy: Option<String>,
-        /// If you don't want to execute rustfmt to generated code, set this option.
+        /// Write the generated code on a single line, instead of formatting it.
         /// Default value is false.
         #[clap(long = "no-formatting")]
         no_formatting: bool,
@@ -87,10 +96,90 @@ enum Cli {
         /// --custom-scalars-module='crate::gql::custom_scalars'
         #[clap(short = 'p', long = "custom-scalars-module")]
         custom_scalars_module: Option<String>,
+        /// Map a scalar to a Rust type, instead of an alias the crate defines. Can be repeated.
+        /// --scalar='Int=i32' --scalar='DateTime=chrono::DateTime<chrono::Utc>'
+        #[clap(long = "scalar")]
+        scalars: Vec<String>,
+        /// Map the well-known scalars, like RFC 3339 date-times, to the types of a crate that
+        /// graphql_client re-exports with the feature of the same name. Can be repeated.
+        /// --scalar-crate=chrono --scalar-crate=uuid
+        #[clap(long = "scalar-crate")]
+        scalar_crates: Vec<String>,
+        /// The module where the enums, input objects and custom scalars of the schema are
+        /// defined, instead of in each operation module.
+        /// --shared-types-module='crate::graphql_types'
+        #[clap(long = "shared-types-module")]
+        shared_types_module: Option<String>,
         /// A flag indicating if the enum representing the variants of a fragment union/interface should have a "other" variant
         /// --fragments-other-variant
-        #[clap(long = "fragments-other-variant")]
+        #[clap(
+            long = "fragments-other-variant",
+            overrides_with = "no_fragments_other_variant"
+        )]
         fragments_other_variant: bool,
+        /// Turn off --fragments-other-variant, when the Cargo.toml metadata turns it on.
+        #[clap(
+            long = "no-fragments-other-variant",
+            overrides_with = "fragments_other_variant"
+        )]
+        no_fragments_other_variant: bool,
+        /// Leave the schema descriptions out of the generated code, instead of rendering them as
+        /// doc comments.
+        /// --skip-descriptions
+        #[clap(long = "skip-descriptions", overrides_with = "no_skip_descriptions")]
+        skip_descriptions: bool,
+        /// Turn off --skip-descriptions, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-skip-descriptions", overrides_with = "skip_descriptions")]
+        no_skip_descriptions: bool,
+        /// Generate builders for the variables and input objects, taking the required fields and
+        /// with setters for the others.
+        /// --builders
+        #[clap(long = "builders", overrides_with = "no_builders")]
+        builders: bool,
+        /// Turn off --builders, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-builders", overrides_with = "builders")]
+        no_builders: bool,
+        /// Use graphql_client::MaybeUndefined instead of Option for the nullable input fields and
+        /// variables, to tell an omitted value apart from an explicit null.
+        /// --maybe-undefined
+        #[clap(long = "maybe-undefined", overrides_with = "no_maybe_undefined")]
+        maybe_undefined: bool,
+        /// Turn off --maybe-undefined, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-maybe-undefined", overrides_with = "maybe_undefined")]
+        no_maybe_undefined: bool,
+        /// Generate a newtype for the ID fields of each object and interface, like UserId for
+        /// the id of a User.
+        /// --typed-ids
+        #[clap(long = "typed-ids", overrides_with = "no_typed_ids")]
+        typed_ids: bool,
+        /// Turn off --typed-ids, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-typed-ids", overrides_with = "typed_ids")]
+        no_typed_ids: bool,
+        /// Type the ID variable with this name, or the input field as Input.field, as the IDs of
+        /// a type, with --typed-ids. Can be repeated.
+        /// --typed-id-input='userId=User' --typed-id-input='StarInput.repositoryId=Repository'
+        #[clap(long = "typed-id-input")]
+        typed_id_inputs: Vec<String>,
+        /// Strip the whitespace that is not needed from the query documents sent to the server.
+        /// --minify-query
+        #[clap(long = "minify-query", overrides_with = "no_minify_query")]
+        minify_query: bool,
+        /// Turn off --minify-query, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-minify-query", overrides_with = "minify_query")]
+        no_minify_query: bool,
+        /// Reject the directives that the schema does not declare, instead of passing them through.
+        /// --deny-unknown-directives
+        #[clap(
+            long = "deny-unknown-directives",
+            overrides_with = "no_deny_unknown_directives"
+        )]
+        deny_unknown_directives: bool,
+        /// Turn off --deny-unknown-directives, when the Cargo.toml metadata turns it on.
+        #[clap(
+            long = "no-deny-unknown-directives",
+            overrides_with = "deny_unknown_directives"
+        )]
+        no_deny_unknown_directives: bool,
         /// List of externally defined enum types. Type names must match those used in the schema exactly
         #[clap(long = "external-enums", num_args(0..), action(clap::ArgAction::Append))]
         external_enums: Option<Vec<String>>,
@@ -103,6 +192,103 @@ enum Cli {
         #[clap(long = "custom-response-type")]
         custom_response_type: Option<String>,
     },
+    /// Generate the types of every enum, input object and custom scalar of a schema,
+    /// independently of any query.
+    #[clap(name = "generate-schema-types")]
+    GenerateSchemaTypes {
+        /// Path to GraphQL schema file (.json or .graphql), or a directory or glob pattern
+        /// matching the SDL files the schema is split across. Can be given several times.
+        /// Defaults to the `schema_path` of the Cargo.toml metadata.
+        #[clap(short = 's', long = "schema-path")]
+        schema_paths: Vec<PathBuf>,
+        /// The Cargo.toml whose [package.metadata.graphql-client] table provides the defaults of
+        /// the options. Defaults to the nearest Cargo.toml, from the current directory up.
+        #[clap(long = "manifest-path")]
+        manifest_path: Option<PathBuf>,
+        /// Where to write the generated code. It is printed to stdout by default.
+        #[clap(long = "output")]
+        output: Option<PathBuf>,
+        /// Additional derives that will be added to the generated enums and input objects.
+        /// --variables-derives='PartialEq'
+        #[clap(short = 'I', long = "variables-derives")]
+        variables_derives: Option<String>,
+        /// Additional derives that will be added to the generated enums.
+        /// --response-derives='PartialEq'
+        #[clap(short = 'O', long = "response-derives")]
+        response_derives: Option<String>,
+        /// The module where the custom scalar definitions are located.
+        /// --custom-scalars-module='crate::gql::custom_scalars'
+        #[clap(short = 'p', long = "custom-scalars-module")]
+        custom_scalars_module: Option<String>,
+        /// Map a scalar to a Rust type, instead of an alias the crate defines. Can be repeated.
+        /// --scalar='Int=i32' --scalar='DateTime=chrono::DateTime<chrono::Utc>'
+        #[clap(long = "scalar")]
+        scalars: Vec<String>,
+        /// Map the well-known scalars, like RFC 3339 date-times, to the types of a crate that
+        /// graphql_client re-exports with the feature of the same name. Can be repeated.
+        /// --scalar-crate=chrono --scalar-crate=uuid
+        #[clap(long = "scalar-crate")]
+        scalar_crates: Vec<String>,
+        /// Leave the schema descriptions out of the generated code, instead of rendering them as
+        /// doc comments.
+        #[clap(long = "skip-descriptions", overrides_with = "no_skip_descriptions")]
+        skip_descriptions: bool,
+        /// Turn off --skip-descriptions, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-skip-descriptions", overrides_with = "skip_descriptions")]
+        no_skip_descriptions: bool,
+        /// Generate builders for the variables and input objects, taking the required fields and
+        /// with setters for the others.
+        #[clap(long = "builders", overrides_with = "no_builders")]
+        builders: bool,
+        /// Turn off --builders, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-builders", overrides_with = "builders")]
+        no_builders: bool,
+        /// Use graphql_client::MaybeUndefined instead of Option for the nullable input fields, to
+        /// tell an omitted value apart from an explicit null.
+        #[clap(long = "maybe-undefined", overrides_with = "no_maybe_undefined")]
+        maybe_undefined: bool,
+        /// Turn off --maybe-undefined, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-maybe-undefined", overrides_with = "maybe_undefined")]
+        no_maybe_undefined: bool,
+        /// Generate a newtype for the ID fields of each object and interface, like UserId for
+        /// the id of a User.
+        /// --typed-ids
+        #[clap(long = "typed-ids", overrides_with = "no_typed_ids")]
+        typed_ids: bool,
+        /// Turn off --typed-ids, when the Cargo.toml metadata turns it on.
+        #[clap(long = "no-typed-ids", overrides_with = "typed_ids")]
+        no_typed_ids: bool,
+        /// Type the ID variable with this name, or the input field as Input.field, as the IDs of
+        /// a type, with --typed-ids. Can be repeated.
+        /// --typed-id-input='userId=User' --typed-id-input='StarInput.repositoryId=Repository'
+        #[clap(long = "typed-id-input")]
+        typed_id_inputs: Vec<String>,
+        /// Write the generated code on a single line, instead of formatting it.
+        #[clap(long = "no-formatting")]
+        no_formatting: bool,
+    },
+    /// Serve schema-conformant fake data for any operation sent to a local GraphQL endpoint.
+    #[clap(name = "mock-server")]
+    MockServer {
+        /// Path to GraphQL schema file (.json or .graphql).
+        #[clap(short = 's', long = "schema")]
+        schema_path: PathBuf,
+        /// The local port to listen on.
+        /// Default value is 4000.
+        #[clap(short = 'p', long = "port", default_value_t = 4000)]
+        port: u16,
+        /// Path to a JSON file with values overriding the generated ones. Keys are type names or
+        /// field coordinates.
+        /// {"DateTime": "2020-01-01T00:00:00Z", "User.login": "octocat", "Node": "User"}
+        /// Only JSON is read: Rust code can pass the same map to MockSchema::set_overrides in
+        /// graphql_client_codegen instead.
+        #[clap(short = 'c', long = "config")]
+        config: Option<PathBuf>,
+        /// The number of items returned for list fields.
+        /// Default value is 2.
+        #[clap(long = "list-length")]
+        list_length: Option<usize>,
+    },
 }
 
 fn main() -> CliResult<()> {
@@ -135,16 +321,35 @@ fn main() -> CliResult<()> {
             no_formatting,
             output_directory,
             query_path,
-            schema_path,
+            schema_paths,
+            manifest_path,
             selected_operation,
             custom_scalars_module,
+            scalars,
+            scalar_crates,
+            shared_types_module,
             fragments_other_variant,
+            no_fragments_other_variant,
+            skip_descriptions,
+            no_skip_descriptions,
+            builders,
+            no_builders,
+            maybe_undefined,
+            no_maybe_undefined,
+            typed_ids,
+            no_typed_ids,
+            typed_id_inputs,
+            minify_query,
+            no_minify_query,
+            deny_unknown_directives,
+            no_deny_unknown_directives,
             external_enums,
             custom_variable_types,
             custom_response_type,
         } => generate::generate_code(generate::CliCodegenParams {
             query_path,
-            schema_path,
+            schema_paths,
+            manifest_path,
             selected_operation,
             variables_derives,
             response_derives,
@@ -153,11 +358,79 @@ fn main() -> CliResult<()> {
             module_visibility,
             output_directory,
             custom_scalars_module,
-            fragments_other_variant,
+            scalars,
+            scalar_crates,
+            shared_types_module,
+            fragments_other_variant: flag(fragments_other_variant, no_fragments_other_variant),
+            skip_descriptions: flag(skip_descriptions, no_skip_descriptions),
+            builders: flag(builders, no_builders),
+            maybe_undefined: flag(maybe_undefined, no_maybe_undefined),
+            typed_ids: flag(typed_ids, no_typed_ids),
+            typed_id_inputs,
+            minify_query: flag(minify_query, no_minify_query),
+            deny_unknown_directives: flag(deny_unknown_directives, no_deny_unknown_directives),
             external_enums,
             custom_variable_types,
             custom_response_type,
         }),
+        Cli::GenerateSchemaTypes {
+            schema_paths,
+            manifest_path,
+            output,
+            variables_derives,
+            response_derives,
+            custom_scalars_module,
+            scalars,
+            scalar_crates,
+            skip_descriptions,
+            no_skip_descriptions,
+            builders,
+            no_builders,
+            maybe_undefined,
+            no_maybe_undefined,
+            typed_ids,
+            no_typed_ids,
+            typed_id_inputs,
+            no_formatting,
+        } => {
+            generate_schema_types::generate_schema_types(generate_schema_types::SchemaTypesParams {
+                schema_paths,
+                manifest_path,
+                output,
+                variables_derives,
+                response_derives,
+                custom_scalars_module,
+                scalars,
+                scalar_crates,
+                skip_descriptions: flag(skip_descriptions, no_skip_descriptions),
+                builders: flag(builders, no_builders),
+                maybe_undefined: flag(maybe_undefined, no_maybe_undefined),
+                typed_ids: flag(typed_ids, no_typed_ids),
+                typed_id_inputs,
+                no_formatting,
+            })
+        }
+        Cli::MockServer {
+            schema_path,
+            port,
+            config,
+            list_length,
+        } => mock_server::mock_server(mock_server::MockServerParams {
+            schema_path,
+            port,
+            config,
+            list_length,
+        }),
+    }
+}
+
+/// The value of a flag and of its `--no-` counterpart, when either is given. Only the last one
+/// given is set.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
     }
 }
 
@@ -195,3 +468,35 @@ fn colored_level(style: &mut Style, level: Level) -> StyledValue<'_, &'static st
         Level::Error => style.set_color(Color::Red).value("ERROR"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builders(args: &[&str]) -> Option<bool> {
+        let cli = Cli::try_parse_from(
+            ["graphql-client", "generate", "query.graphql"]
+                .iter()
+                .chain(args),
+        )
+        .unwrap();
+
+        match cli {
+            Generate {
+                builders,
+                no_builders,
+                ..
+            } => flag(builders, no_builders),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn flags_can_be_turned_off() {
+        assert_eq!(builders(&[]), None);
+        assert_eq!(builders(&["--builders"]), Some(true));
+        assert_eq!(builders(&["--no-builders"]), Some(false));
+        assert_eq!(builders(&["--no-builders", "--builders"]), Some(true));
+        assert_eq!(builders(&["--builders", "--no-builders"]), Some(false));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::Error;
+use crate::CliResult;
+use graphql_client_codegen::mock::MockSchema;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Larger request bodies are rejected, rather than allocated.
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// How long a client can stay silent before its connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) struct MockServerParams {
+    pub schema_path: PathBuf,
+    pub port: u16,
+    pub config: Option<PathBuf>,
+    pub list_length: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQLRequest {
+    query: String,
+    operation_name: Option<String>,
+    #[serde(default)]
+    variables: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+pub(crate) fn mock_server(params: MockServerParams) -> CliResult<()> {
+    let MockServerParams {
+        schema_path,
+        port,
+        config,
+        list_length,
+    } = params;
+
+    let mut mock = MockSchema::from_path(&schema_path)
+        .map_err(|err| Error::message(format!("Error loading schema: {}", err)))?;
+
+    if let Some(config) = config {
+        let contents = std::fs::read_to_string(&config).map_err(|err| {
+            Error::source_with_message(err, format!("Reading mock config at {}", config.display()))
+        })?;
+        let overrides = serde_json::from_str(&contents).map_err(|err| {
+            Error::source_with_message(
+                err,
+                "The mock config must be a JSON object mapping type names or `Type.field` coordinates to values.".to_owned(),
+            )
+        })?;
+        mock.set_overrides(overrides);
+    }
+
+    if let Some(list_length) = list_length {
+        mock.set_list_length(list_length);
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|err| Error::source_with_message(err, format!("Binding to port {}", port)))?;
+
+    log::info!("Mock GraphQL server listening on http://127.0.0.1:{}", port);
+
+    // Each connection gets its own thread, so that a slow client does not hold up the others.
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("Failed to accept connection: {}", err);
+                    continue;
+                }
+            };
+            let mock = &mock;
+
+            scope.spawn(move || {
+                if let Err(err) = handle_connection(mock, stream) {
+                    log::warn!("Failed to handle request: {:?}", err);
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+fn handle_connection(mock: &MockSchema, stream: TcpStream) -> CliResult<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let method = request_line.split_whitespace().next().unwrap_or_default();
+
+    let mut content_length = Some(0);
+
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let content_length = match content_length {
+        Some(content_length) if content_length > MAX_BODY_SIZE => {
+            return respond(reader.get_mut(), "413 Payload Too Large", "");
+        }
+        Some(content_length) => content_length,
+        None => return respond(reader.get_mut(), "400 Bad Request", ""),
+    };
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut stream = reader.into_inner();
+
+    match method {
+        // CORS preflight, so the server can be used from a frontend dev server.
+        "OPTIONS" => respond(&mut stream, "204 No Content", ""),
+        "POST" => {
+            let response = match serde_json::from_slice::<GraphQLRequest>(&body) {
+                Ok(request) => mock.execute(
+                    &request.query,
+                    request.operation_name.as_deref(),
+                    &request.variables.unwrap_or_default(),
+                ),
+                Err(err) => serde_json::json!({
+                    "errors": [{ "message": format!("Invalid GraphQL request: {}", err) }]
+                }),
+            };
+
+            respond(&mut stream, "200 OK", &response.to_string())
+        }
+        _ => respond(&mut stream, "405 Method Not Allowed", ""),
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> CliResult<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: *\r\n\
+         Access-Control-Allow-Methods: POST, OPTIONS\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {}",
+        status,
+        body.len(),
+        body
+    )?;
+
+    Ok(stream.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::path::Path;
+
+    /// Sends a raw request to `handle_connection` over a local socket, and returns the response.
+    fn send(request: &str) -> String {
+        let mock = MockSchema::from_path(Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/graphql/introspection_schema.graphql"
+        )))
+        .unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        client.write_all(request.as_bytes()).unwrap();
+        handle_connection(&mock, server).unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn operations_are_executed() {
+        let body = r#"{"query": "{ __typename }"}"#;
+        let response = send(&format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        ));
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(
+            response.ends_with(r#"{"data":{"__typename":"Query"}}"#),
+            "{}",
+            response
+        );
+    }
+
+    #[test]
+    fn invalid_content_lengths_are_rejected() {
+        let response = send("POST / HTTP/1.1\r\nContent-Length: many\r\n\r\n");
+        assert!(
+            response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+            "{}",
+            response
+        );
+
+        let response = send(&format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        ));
+        assert!(
+            response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"),
+            "{}",
+            response
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Code generation from build scripts.
+
+use crate::{
+    format_generated_code, generate_module_token_stream_inner, get_set_query_from_file,
+    get_set_schema_from_paths, schema_source, BoxError, CodegenMode, GeneralError,
+    GraphQLClientCodegenOptions,
+};
+use heck::ToSnakeCase;
+use std::path::{Path, PathBuf};
+
+/// Generates the modules of query files from a build script.
+///
+/// Each query file becomes a formatted `<file_stem>.rs` in `OUT_DIR`, and cargo is told to run
+/// the build script again when the schema, a query file or a file it imports fragments from
+/// changes, or when a file is added to a schema directory. The generated files are meant to be included in a module:
+///
+/// ```no_run
+/// // build.rs
+/// graphql_client_codegen::Builder::new("schema.graphql")
+///     .query_path("src/queries/viewer.graphql")
+///     .query_path("src/queries/repositories.graphql")
+///     .mod_file("queries.rs")
+///     .generate()
+///     .unwrap();
+/// ```
+///
+/// ```ignore
+/// // src/lib.rs
+/// mod queries {
+///     include!(concat!(env!("OUT_DIR"), "/queries.rs"));
+/// }
+///
+/// let body = queries::viewer::Viewer::build_query(queries::viewer::viewer::Variables);
+/// ```
+pub struct Builder {
+    schema_paths: Vec<PathBuf>,
+    query_paths: Vec<PathBuf>,
+    options: GraphQLClientCodegenOptions,
+    out_dir: Option<PathBuf>,
+    mod_file: Option<PathBuf>,
+}
+
+impl Builder {
+    /// A builder for the given schema file, directory or glob pattern.
+    pub fn new(schema_path: impl Into<PathBuf>) -> Builder {
+        Builder {
+            schema_paths: vec![schema_path.into()],
+            query_paths: Vec::new(),
+            options: GraphQLClientCodegenOptions::new(CodegenMode::Cli),
+            out_dir: None,
+            mod_file: None,
+        }
+    }
+
+    /// Adds another file, directory or glob pattern the schema is split across.
+    pub fn schema_path(mut self, schema_path: impl Into<PathBuf>) -> Builder {
+        self.schema_paths.push(schema_path.into());
+        self
+    }
+
+    /// Adds a query file to generate a module for.
+    pub fn query_path(mut self, query_path: impl Into<PathBuf>) -> Builder {
+        self.query_paths.push(query_path.into());
+        self
+    }
+
+    /// Adds several query files to generate modules for.
+    pub fn query_paths<P: Into<PathBuf>>(
+        mut self,
+        query_paths: impl IntoIterator<Item = P>,
+    ) -> Builder {
+        self.query_paths
+            .extend(query_paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// The options of the generated code. The modules are public unless the options set another
+    /// visibility.
+    pub fn options(mut self, options: GraphQLClientCodegenOptions) -> Builder {
+        self.options = options;
+        self
+    }
+
+    /// Where to write the generated files. Defaults to `OUT_DIR`.
+    pub fn out_dir(mut self, out_dir: impl Into<PathBuf>) -> Builder {
+        self.out_dir = Some(out_dir.into());
+        self
+    }
+
+    /// Also writes a file, relative to the output directory, declaring a module for each query
+    /// file. It can be brought in with `include!`.
+    pub fn mod_file(mut self, mod_file: impl Into<PathBuf>) -> Builder {
+        self.mod_file = Some(mod_file.into());
+        self
+    }
+
+    /// Writes the generated files, and returns their paths.
+    pub fn generate(self) -> Result<Vec<PathBuf>, BoxError> {
+        let out_dir = match self.out_dir {
+            Some(out_dir) => out_dir,
+            None => std::env::var_os("OUT_DIR").map(PathBuf::from).ok_or_else(|| {
+                GeneralError(
+                    "OUT_DIR is not set. Run the builder from a build script, or give it an output directory."
+                        .to_owned(),
+                )
+            })?,
+        };
+        std::fs::create_dir_all(&out_dir)?;
+
+        let (schema_files, schema) = get_set_schema_from_paths(&self.schema_paths)?;
+        let mut inputs = schema_files.clone();
+        let mut modules: Vec<(String, PathBuf)> = Vec::with_capacity(self.query_paths.len());
+
+        for query_path in &self.query_paths {
+            let module_name = module_name(query_path)?;
+
+            if modules.iter().any(|(name, _)| *name == module_name) {
+                return Err(GeneralError(format!(
+                    "Several query files generate the module `{}`.",
+                    module_name
+                ))
+                .into());
+            }
+
+            let query = get_set_query_from_file(query_path)?;
+            let mut options = self.options.clone();
+            options.mode = CodegenMode::Cli;
+            if options.module_visibility.is_none() {
+                options.set_module_visibility(syn::parse_quote!(pub));
+            }
+
+            let tokens = generate_module_token_stream_inner(
+                &query,
+                Some(query_path),
+                &schema,
+                &schema_files,
+                options,
+            )?;
+
+            let path = out_dir.join(format!("{}.rs", module_name));
+            std::fs::write(&path, format_generated_code(tokens)?)?;
+
+            inputs.push(query_path.clone());
+            inputs.extend(query.imported_files);
+            modules.push((module_name, path));
+        }
+
+        let mut generated: Vec<PathBuf> = modules.iter().map(|(_, path)| path.clone()).collect();
+
+        if let Some(mod_file) = self.mod_file {
+            let mod_file = out_dir.join(mod_file);
+            std::fs::write(&mod_file, mod_file_contents(&modules))?;
+            generated.push(mod_file);
+        }
+
+        // Cargo scans directories for changes, which catches the files added to them.
+        inputs.extend(schema_source::schema_directories(&self.schema_paths));
+        for input in inputs {
+            println!("cargo:rerun-if-changed={}", input.display());
+        }
+
+        Ok(generated)
+    }
+}
+
+/// The snake case file stem of the query file.
+fn module_name(query_path: &Path) -> Result<String, BoxError> {
+    query_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.to_snake_case())
+        .ok_or_else(|| {
+            GeneralError(format!(
+                "Failed to find a file name in the query path {}.",
+                query_path.display()
+            ))
+            .into()
+        })
+}
+
+/// A module including each generated file. The warnings of the generated code are allowed here,
+/// since inner attributes cannot be included.
+fn mod_file_contents(modules: &[(String, PathBuf)]) -> String {
+    let mut contents = String::from("// Generated by graphql_client_codegen. Do not edit.\n");
+
+    for (name, path) in modules {
+        contents.push_str(&format!(
+            "\n#[allow(clippy::all, warnings)]\npub mod {} {{\n    include!({:?});\n}}\n",
+            name,
+            path.display().to_string(),
+        ));
+    }
+
+    contents
+}
@@ -1,12 +1,18 @@
+mod builders;
 mod enums;
 mod inputs;
 mod selection;
 mod shared;
+pub(crate) mod typed_ids;
 
 use crate::{
     query::*,
-    schema::{InputId, TypeId},
+    schema::{
+        input_is_recursive_without_indirection, InputId, Schema, StoredInputFieldType,
+        StoredScalar, TypeId,
+    },
     type_qualifiers::GraphqlTypeQualifier,
+    well_known_scalars::{self, WellKnownScalar},
     GeneralError, GraphQLClientCodegenOptions,
 };
 use heck::ToSnakeCase;
@@ -27,8 +33,12 @@ pub(crate) fn response_for_query(
     let response_derives = render_derives(options.all_response_derives());
     let variable_derives = render_derives(options.all_variable_derives());
 
-    let scalar_definitions = generate_scalar_definitions(&all_used_types, options, query);
+    let builtin_scalar_definitions = generate_builtin_scalar_definitions(options)?;
+    let scalar_definitions =
+        generate_scalar_definitions(&all_used_types, options, query, TokenStream::new());
     let enum_definitions = enums::generate_enum_definitions(&all_used_types, options, query);
+    let typed_id_definitions =
+        typed_ids::generate_typed_id_definitions(&all_used_types, options, &query);
     let fragment_definitions =
         generate_fragment_definitions(&all_used_types, &response_derives, options, &query);
     let input_object_definitions = inputs::generate_input_object_definitions(
@@ -48,19 +58,14 @@ pub(crate) fn response_for_query(
         use #serde::{Serialize, Deserialize};
         use super::*;
 
-        #[allow(dead_code)]
-        type Boolean = bool;
-        #[allow(dead_code)]
-        type Float = f64;
-        #[allow(dead_code)]
-        type Int = i64;
-        #[allow(dead_code)]
-        type ID = String;
+        #builtin_scalar_definitions
 
         #(#scalar_definitions)*
 
         #(#enum_definitions)*
 
+        #(#typed_id_definitions)*
+
         #(#input_object_definitions)*
 
         #variables_struct
@@ -73,6 +78,55 @@ pub(crate) fn response_for_query(
     Ok(q)
 }
 
+/// The enums, input objects and custom scalars of the whole schema, for the module set with
+/// `shared_types_module`.
+pub(crate) fn schema_types(
+    options: &GraphQLClientCodegenOptions,
+    schema: &crate::schema::Schema,
+) -> Result<TokenStream, GeneralError> {
+    let serde = options.serde_path();
+
+    // The shared module defines the types, instead of re-exporting them from itself.
+    let mut options = options.clone();
+    options.shared_types_module = None;
+    let options = &options;
+
+    let query = Query::default();
+    let query = BoundQuery {
+        query: &query,
+        schema,
+    };
+    let all_used_types = UsedTypes::schema_types(schema);
+    let variable_derives = render_derives(options.all_variable_derives());
+
+    let builtin_scalar_definitions = generate_builtin_scalar_definitions(options)?;
+    let scalar_definitions =
+        generate_scalar_definitions(&all_used_types, options, query, quote!(pub));
+    let enum_definitions = enums::generate_enum_definitions(&all_used_types, options, query);
+    let typed_id_definitions =
+        typed_ids::generate_typed_id_definitions(&all_used_types, options, &query);
+    let input_object_definitions = inputs::generate_input_object_definitions(
+        &all_used_types,
+        options,
+        &variable_derives,
+        &query,
+    );
+
+    Ok(quote! {
+        use #serde::{Serialize, Deserialize};
+
+        #builtin_scalar_definitions
+
+        #(#scalar_definitions)*
+
+        #(#enum_definitions)*
+
+        #(#typed_id_definitions)*
+
+        #(#input_object_definitions)*
+    })
+}
+
 fn generate_variables_struct(
     operation_id: OperationId,
     variable_derives: &impl quote::ToTokens,
@@ -108,6 +162,7 @@ fn generate_variables_struct(
                         .first()
                         .map(|qual| !qual.is_required())
                         .unwrap_or(true),
+                    options,
                     query,
                 );
 
@@ -119,6 +174,18 @@ fn generate_variables_struct(
             })
         });
 
+    let variables_builder = if *options.builders() {
+        let fields: Vec<_> = walk_operation_variables(operation_id, query.query)
+            .map(|(_id, variable)| variable_builder_field(variable, options, query))
+            .collect();
+        Some(builders::generate_builder(
+            &Ident::new("Variables", Span::call_site()),
+            &fields,
+        ))
+    } else {
+        None
+    };
+
     let variables_struct = quote!(
         #variable_derives
         #[serde(crate = #serde_path)]
@@ -129,6 +196,8 @@ fn generate_variables_struct(
         impl Variables {
             #(#variable_defaults)*
         }
+
+        #variables_builder
     );
 
     variables_struct
@@ -143,8 +212,11 @@ fn generate_variable_struct_field(
     let safe_name = shared::keyword_replace(&snake_case_name);
     let ident = Ident::new(&safe_name, Span::call_site());
     let rename_annotation = shared::field_rename_annotation(&variable.name, &safe_name);
-    let skip_serializing_annotation = if *options.skip_serializing_none() {
-        if variable.r#type.qualifiers.first() != Some(&GraphqlTypeQualifier::Required) {
+    let is_optional = variable.r#type.qualifiers.first() != Some(&GraphqlTypeQualifier::Required);
+    let skip_serializing_annotation = if *options.maybe_undefined() && is_optional {
+        Some(maybe_undefined_annotation())
+    } else if *options.skip_serializing_none() {
+        if is_optional {
             Some(quote!(#[serde(skip_serializing_if = "Option::is_none")]))
         } else {
             None
@@ -152,15 +224,85 @@ fn generate_variable_struct_field(
     } else {
         None
     };
+    let serde_with_annotation = serde_with_annotation(
+        variable.r#type.id,
+        is_optional && !*options.maybe_undefined(),
+        options,
+        query.schema,
+    );
     let r#type = render_variable_field_type(variable, options, query);
 
-    quote::quote!(#skip_serializing_annotation #rename_annotation pub #ident : #r#type)
+    quote::quote!(#skip_serializing_annotation #serde_with_annotation #rename_annotation pub #ident : #r#type)
+}
+
+/// Variables with a default in the query start with it, instead of being required.
+fn variable_builder_field(
+    variable: &ResolvedVariable,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> builders::BuilderField {
+    let safe_name = shared::keyword_replace(variable.name.to_snake_case());
+    let optional = variable.r#type.qualifiers.first() != Some(&GraphqlTypeQualifier::Required);
+
+    let type_name = variable_type_ident(variable, options, query);
+    let mut qualifiers = variable.r#type.qualifiers.clone();
+    if optional {
+        qualifiers.insert(0, GraphqlTypeQualifier::Required);
+    }
+
+    let default = variable.default.as_ref().map(|_| {
+        let method_name = Ident::new(&format!("default_{}", variable.name), Span::call_site());
+        quote!(Variables::#method_name())
+    });
+
+    builders::BuilderField {
+        ident: Ident::new(&safe_name, Span::call_site()),
+        value_ty: decorate_type(&type_name, &qualifiers),
+        optional,
+        maybe_undefined: optional && *options.maybe_undefined(),
+        boxed: false,
+        default,
+    }
+}
+
+/// The aliases of the built-in scalars, to their Rust type or to the one they are mapped to.
+fn generate_builtin_scalar_definitions(
+    options: &GraphQLClientCodegenOptions,
+) -> Result<TokenStream, GeneralError> {
+    if options.scalar_types().contains_key("String") {
+        return Err(GeneralError(
+            "The String scalar cannot be mapped to another type.".to_owned(),
+        ));
+    }
+
+    let builtin_scalars = [
+        ("Boolean", quote!(bool)),
+        ("Float", quote!(f64)),
+        ("Int", quote!(i64)),
+        ("ID", quote!(String)),
+    ];
+    let definitions = builtin_scalars.iter().map(|(name, default_type)| {
+        let ident = Ident::new(name, Span::call_site());
+        let rust_type = options
+            .scalar_types()
+            .get(*name)
+            .map(ToTokens::to_token_stream)
+            .unwrap_or_else(|| default_type.clone());
+
+        quote! {
+            #[allow(dead_code)]
+            type #ident = #rust_type;
+        }
+    });
+
+    Ok(quote!(#(#definitions)*))
 }
 
 fn generate_scalar_definitions<'a, 'schema: 'a>(
     all_used_types: &'a crate::query::UsedTypes,
     options: &'a GraphQLClientCodegenOptions,
     query: BoundQuery<'schema>,
+    visibility: TokenStream,
 ) -> impl Iterator<Item = TokenStream> + 'a {
     all_used_types
         .scalars(query.schema)
@@ -170,10 +312,17 @@ fn generate_scalar_definitions<'a, 'schema: 'a>(
                 proc_macro2::Span::call_site(),
             );
 
-            if let Some(custom_scalars_module) = options.custom_scalars_module() {
-                quote!(type #ident = #custom_scalars_module::#ident;)
+            if let Some(shared_types_module) = options.shared_types_module() {
+                quote!(use #shared_types_module::#ident;)
+            } else if let Some(rust_type) = options.scalar_types().get(&scalar.name) {
+                quote!(#visibility type #ident = #rust_type;)
+            } else if let Some(well_known) = well_known_scalar(scalar, options) {
+                let rust_type = well_known.rust_type;
+                quote!(#visibility type #ident = #rust_type;)
+            } else if let Some(custom_scalars_module) = options.custom_scalars_module() {
+                quote!(#visibility type #ident = #custom_scalars_module::#ident;)
             } else {
-                quote!(type #ident = super::#ident;)
+                quote!(#visibility type #ident = super::#ident;)
             }
         })
 }
@@ -193,13 +342,88 @@ fn render_variable_field_type(
     options: &GraphQLClientCodegenOptions,
     query: &BoundQuery<'_>,
 ) -> TokenStream {
+    let full_name = variable_type_ident(variable, options, query);
+
+    if *options.maybe_undefined()
+        && variable.r#type.qualifiers.first() != Some(&GraphqlTypeQualifier::Required)
+    {
+        return maybe_undefined_type(&full_name, &variable.r#type.qualifiers, false);
+    }
+
+    decorate_type(&full_name, &variable.r#type.qualifiers)
+}
+
+/// The typed ID of the variable, or else its normalized schema type.
+fn variable_type_ident(
+    variable: &ResolvedVariable,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Ident {
+    if let Some(typed_id) =
+        typed_ids::input_typed_id(&variable.name, variable.r#type.id, options, query)
+    {
+        return Ident::new(&typed_id, Span::call_site());
+    }
+
     let normalized_name = options
         .normalization()
         .input_name(variable.type_name(query.schema));
-    let safe_name = shared::keyword_replace(normalized_name.clone());
-    let full_name = Ident::new(safe_name.as_ref(), Span::call_site());
+    let safe_name = shared::keyword_replace(normalized_name);
+    Ident::new(safe_name.as_ref(), Span::call_site())
+}
 
-    decorate_type(&full_name, &variable.r#type.qualifiers)
+/// `graphql_client::MaybeUndefined<T>` for a nullable input, where `decorate_type` would give
+/// `Option<T>`. Recursive input objects are boxed inside it.
+fn maybe_undefined_type(
+    ident: &Ident,
+    qualifiers: &[GraphqlTypeQualifier],
+    boxed: bool,
+) -> TokenStream {
+    let mut required = vec![GraphqlTypeQualifier::Required];
+    required.extend(qualifiers.iter().cloned());
+    let inner = decorate_type(ident, &required);
+
+    if boxed {
+        quote!(graphql_client::MaybeUndefined<Box<#inner>>)
+    } else {
+        quote!(graphql_client::MaybeUndefined<#inner>)
+    }
+}
+
+/// The type of a well-known scalar for the enabled crates, unless it is mapped explicitly.
+fn well_known_scalar(
+    scalar: &StoredScalar,
+    options: &GraphQLClientCodegenOptions,
+) -> Option<WellKnownScalar> {
+    if options.scalar_types().contains_key(&scalar.name) {
+        return None;
+    }
+
+    well_known_scalars::well_known_scalar(scalar, options.scalar_crates())
+}
+
+/// `#[serde(with = "...")]` on the fields of well-known scalars whose type needs a serde adapter.
+/// Serde only treats missing `Option` fields as `None` without `with`, hence `default` for the
+/// nullable ones.
+fn serde_with_annotation(
+    type_id: TypeId,
+    default: bool,
+    options: &GraphQLClientCodegenOptions,
+    schema: &Schema,
+) -> Option<TokenStream> {
+    let scalar = schema.get_scalar(type_id.as_scalar_id()?);
+    let serde_with = well_known_scalar(scalar, options)?.serde_with?;
+
+    if default {
+        Some(quote!(#[serde(default, with = #serde_with)]))
+    } else {
+        Some(quote!(#[serde(with = #serde_with)]))
+    }
+}
+
+/// The serde attribute of `MaybeUndefined` fields, leaving out the undefined ones.
+fn maybe_undefined_annotation() -> TokenStream {
+    quote!(#[serde(default, skip_serializing_if = "graphql_client::MaybeUndefined::is_undefined")])
 }
 
 fn decorate_type(ident: &Ident, qualifiers: &[GraphqlTypeQualifier]) -> TokenStream {
@@ -256,6 +480,7 @@ fn graphql_parser_value_to_literal<'doc, T>(
     value: &graphql_parser::query::Value<'doc, T>,
     ty: TypeId,
     is_optional: bool,
+    options: &GraphQLClientCodegenOptions,
     query: &BoundQuery<'_>,
 ) -> TokenStream
 where
@@ -272,19 +497,39 @@ where
                 quote!(false)
             }
         }
+        // Typed IDs convert from the `ID` string.
+        Value::String(s) if *options.typed_ids() && ty.is_id(query.schema) => {
+            quote!(#s.to_string().into())
+        }
         Value::String(s) => quote!(#s.to_string()),
         Value::Variable(_) => panic!("variable in variable"),
-        Value::Null => panic!("null as default value"),
-        Value::Float(f) => quote!(#f),
+        Value::Null if *options.maybe_undefined() => {
+            return quote!(graphql_client::MaybeUndefined::Null)
+        }
+        Value::Null => return quote!(None),
+        // Unsuffixed, since `Int` and `Float` can be mapped to other types.
+        Value::Float(f) => proc_macro2::Literal::f64_unsuffixed(*f).into_token_stream(),
         Value::Int(i) => {
-            let i = i.as_i64();
-            quote!(#i)
+            proc_macro2::Literal::i64_unsuffixed(i.as_i64().expect("Int literal out of range"))
+                .into_token_stream()
         }
-        Value::Enum(en) => quote!(#en),
+        Value::Enum(en) => match ty {
+            TypeId::Enum(enum_id) => {
+                let normalization = options.normalization();
+                let enum_name =
+                    normalization.enum_name(query.schema.get_enum(enum_id).name.as_str());
+                let enum_name = Ident::new(&enum_name, Span::call_site());
+                let safe_variant = shared::keyword_replace(en.as_ref());
+                let variant = normalization.enum_variant(safe_variant.as_ref());
+                let variant = Ident::new(&variant, Span::call_site());
+                quote!(#enum_name::#variant)
+            }
+            _ => quote!(compile_error!("Enum literal on a non-enum field.")),
+        },
         Value::List(inner) => {
             let elements = inner
                 .iter()
-                .map(|val| graphql_parser_value_to_literal(val, ty, false, query));
+                .map(|val| graphql_parser_value_to_literal(val, ty, false, options, query));
             quote! {
                 vec![
                     #(#elements,)*
@@ -293,7 +538,7 @@ where
         }
         Value::Object(obj) => ty
             .as_input_id()
-            .map(|input_id| render_object_literal(obj, input_id, query))
+            .map(|input_id| render_object_literal(obj, input_id, options, query))
             .unwrap_or_else(|| {
                 quote!(compile_error!(
                     "Object literal on a non-input-object field."
@@ -301,10 +546,10 @@ where
             }),
     };
 
-    if is_optional {
-        quote!(Some(#inner))
-    } else {
-        inner
+    match (is_optional, *options.maybe_undefined()) {
+        (true, true) => quote!(graphql_client::MaybeUndefined::Value(#inner)),
+        (true, false) => quote!(Some(#inner)),
+        (false, _) => inner,
     }
 }
 
@@ -312,6 +557,7 @@ where
 fn render_object_literal<'doc, T>(
     object_map: &BTreeMap<T::Value, graphql_parser::query::Value<'doc, T>>,
     input_id: InputId,
+    options: &GraphQLClientCodegenOptions,
     query: &BoundQuery<'_>,
 ) -> TokenStream
 where
@@ -319,25 +565,29 @@ where
     T::Value: quote::ToTokens,
 {
     let input = query.schema.get_input(input_id);
-    let constructor = Ident::new(&input.name, Span::call_site());
+    let normalized_name = options.normalization().input_name(input.name.as_str());
+    let constructor = Ident::new(
+        shared::keyword_replace(normalized_name).as_ref(),
+        Span::call_site(),
+    );
     let fields: Vec<TokenStream> = input
         .fields
         .iter()
-        .map(|(name, r#type)| {
-            let field_name = Ident::new(name, Span::call_site());
-            let provided_value = object_map.get(name);
-            match provided_value {
-                Some(default_value) => {
-                    let value = graphql_parser_value_to_literal(
-                        default_value,
-                        r#type.id,
-                        r#type.is_optional(),
-                        query,
-                    );
-                    quote!(#field_name: #value)
+        .zip(&input.field_defaults)
+        .map(|((name, r#type), schema_default)| {
+            let safe_name = shared::keyword_replace(name.to_snake_case());
+            let field_name = Ident::new(&safe_name, Span::call_site());
+            // The fields left out of the literal get their default from the schema.
+            let value = match (object_map.get(name), schema_default) {
+                (Some(value), _) => input_field_literal(value, r#type, options, query),
+                (None, Some(value)) => input_field_literal(value, r#type, options, query),
+                (None, None) if *options.maybe_undefined() => {
+                    quote!(graphql_client::MaybeUndefined::Undefined)
                 }
-                None => quote!(#field_name: None),
-            }
+                (None, None) if is_boxed(r#type, query) => quote!(Box::new(None)),
+                (None, None) => quote!(None),
+            };
+            quote!(#field_name: #value)
         })
         .collect();
 
@@ -345,3 +595,45 @@ where
         #(#fields,)*
     })
 }
+
+/// The value of an input object field, boxed like the field when the input object is recursive.
+fn input_field_literal<'doc, T>(
+    value: &graphql_parser::query::Value<'doc, T>,
+    field_type: &StoredInputFieldType,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> TokenStream
+where
+    T: graphql_parser::query::Text<'doc>,
+    T::Value: quote::ToTokens,
+{
+    let is_optional = field_type.is_optional();
+
+    if !is_boxed(field_type, query) {
+        return graphql_parser_value_to_literal(value, field_type.id, is_optional, options, query);
+    }
+
+    if let graphql_parser::query::Value::Null = value {
+        return if *options.maybe_undefined() {
+            quote!(graphql_client::MaybeUndefined::Null)
+        } else {
+            quote!(Box::new(None))
+        };
+    }
+
+    let inner = graphql_parser_value_to_literal(value, field_type.id, false, options, query);
+
+    match (is_optional, *options.maybe_undefined()) {
+        (true, true) => quote!(graphql_client::MaybeUndefined::Value(Box::new(#inner))),
+        (true, false) => quote!(Box::new(Some(#inner))),
+        (false, _) => quote!(Box::new(#inner)),
+    }
+}
+
+fn is_boxed(field_type: &StoredInputFieldType, query: &BoundQuery<'_>) -> bool {
+    field_type
+        .id
+        .as_input_id()
+        .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
+        .unwrap_or(false)
+}
@@ -0,0 +1,145 @@
+//! Builders for the `Variables` struct and the input objects, generated with the `builders`
+//! option.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+/// A field of the struct a builder is generated for.
+pub(super) struct BuilderField {
+    pub(super) ident: Ident,
+    /// The type taken by `builder()` or by the setter: the field type without the outer `Option`
+    /// and `Box`.
+    pub(super) value_ty: TokenStream,
+    /// Whether the value is wrapped in `Some` before it is stored.
+    pub(super) optional: bool,
+    /// Whether the field is a `MaybeUndefined` instead of an `Option`. The setter then also
+    /// takes `MaybeUndefined::Null`, unless the value is boxed.
+    pub(super) maybe_undefined: bool,
+    /// Whether the value is boxed before it is stored.
+    pub(super) boxed: bool,
+    /// The value of the field when it is not set, for fields that are not required.
+    pub(super) default: Option<TokenStream>,
+}
+
+impl BuilderField {
+    fn is_required(&self) -> bool {
+        !self.optional && self.default.is_none()
+    }
+
+    fn store(&self, value: TokenStream) -> TokenStream {
+        if self.maybe_undefined {
+            return if self.boxed {
+                quote!(graphql_client::MaybeUndefined::Value(Box::new(#value)))
+            } else {
+                quote!(#value.into())
+            };
+        }
+
+        let value = if self.optional {
+            quote!(Some(#value))
+        } else {
+            value
+        };
+
+        if self.boxed {
+            quote!(Box::new(#value))
+        } else {
+            value
+        }
+    }
+
+    fn unset(&self) -> TokenStream {
+        if let Some(default) = &self.default {
+            return default.clone();
+        }
+
+        if self.maybe_undefined {
+            return quote!(graphql_client::MaybeUndefined::Undefined);
+        }
+
+        if self.boxed {
+            quote!(Box::new(None))
+        } else {
+            quote!(None)
+        }
+    }
+}
+
+/// `Name::builder(required...)`, returning a `NameBuilder` with a setter for each field that is
+/// not required, and `build()`.
+pub(super) fn generate_builder(struct_name: &Ident, fields: &[BuilderField]) -> TokenStream {
+    let builder_name = Ident::new(&format!("{}Builder", struct_name), Span::call_site());
+    let builder_doc = format!("A builder for [`{}`].", struct_name);
+
+    let required_args = fields
+        .iter()
+        .filter(|field| field.is_required())
+        .map(|field| {
+            let ident = &field.ident;
+            let value_ty = &field.value_ty;
+            quote!(#ident: #value_ty)
+        });
+    let initializers = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let value = if field.is_required() {
+            field.store(quote!(#ident))
+        } else {
+            field.unset()
+        };
+        quote!(#ident: #value)
+    });
+
+    let setters = fields
+        .iter()
+        .filter(|field| !field.is_required())
+        .map(|field| {
+            let ident = &field.ident;
+            let value_ty = &field.value_ty;
+            let value_ty = if field.maybe_undefined && !field.boxed {
+                quote!(impl Into<graphql_client::MaybeUndefined<#value_ty>>)
+            } else {
+                quote!(#value_ty)
+            };
+            let value = field.store(quote!(value));
+
+            // `build` is taken by the method returning the struct.
+            let setter = if ident == "build" {
+                Ident::new("build_", Span::call_site())
+            } else {
+                ident.clone()
+            };
+
+            quote! {
+                pub fn #setter(mut self, value: #value_ty) -> Self {
+                    self.inner.#ident = #value;
+                    self
+                }
+            }
+        });
+
+    quote! {
+        impl #struct_name {
+            /// Starts building the struct from its required fields.
+            pub fn builder(#(#required_args),*) -> #builder_name {
+                #builder_name {
+                    inner: #struct_name {
+                        #(#initializers,)*
+                    },
+                }
+            }
+        }
+
+        #[doc = #builder_doc]
+        pub struct #builder_name {
+            inner: #struct_name,
+        }
+
+        impl #builder_name {
+            #(#setters)*
+
+            pub fn build(self) -> #struct_name {
+                self.inner
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
-    codegen::render_derives, codegen_options::GraphQLClientCodegenOptions, query::BoundQuery,
+    codegen::{render_derives, shared::doc_comment},
+    codegen_options::{derive_name, GraphQLClientCodegenOptions},
+    query::BoundQuery,
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
@@ -20,7 +22,7 @@ pub(super) fn generate_enum_definitions<'a, 'schema: 'a>(
     let traits = options
         .all_response_derives()
         .chain(options.all_variable_derives())
-        .filter(|d| !&["Serialize", "Deserialize", "Default"].contains(d))
+        .filter(|d| !["Serialize", "Deserialize", "Default"].contains(&derive_name(d)))
         // Use BTreeSet instead of HashSet for a stable ordering.
         .collect::<std::collections::BTreeSet<_>>();
     let derives = render_derives(traits.into_iter());
@@ -29,6 +31,11 @@ pub(super) fn generate_enum_definitions<'a, 'schema: 'a>(
     all_used_types.enums(query.schema)
         .filter(move |(_id, r#enum)| !options.extern_enums().contains(&r#enum.name))
         .map(move |(_id, r#enum)| {
+        if let Some(shared_types_module) = options.shared_types_module() {
+            let name = Ident::new(&normalization.enum_name(r#enum.name.as_str()), Span::call_site());
+            return quote!(pub use #shared_types_module::#name;);
+        }
+
         let variant_names: Vec<TokenStream> = r#enum
             .variants
             .iter()
@@ -40,6 +47,11 @@ pub(super) fn generate_enum_definitions<'a, 'schema: 'a>(
                 quote!(#name)
             })
             .collect();
+        let variant_docs = r#enum
+            .variant_descriptions
+            .iter()
+            .map(|description| doc_comment(description.as_deref(), options));
+        let enum_doc = doc_comment(r#enum.description.as_deref(), options);
         let variant_names = &variant_names;
         let name_ident = normalization.enum_name(r#enum.name.as_str());
         let name_ident = Ident::new(&name_ident, Span::call_site());
@@ -61,9 +73,10 @@ pub(super) fn generate_enum_definitions<'a, 'schema: 'a>(
         let name = name_ident;
 
         quote! {
+            #enum_doc
             #derives
             pub enum #name {
-                #(#variant_names,)*
+                #(#variant_docs #variant_names,)*
                 Other(String),
             }
 
@@ -1,13 +1,17 @@
-use super::shared::{field_rename_annotation, keyword_replace};
+use super::builders::{generate_builder, BuilderField};
+use super::shared::{doc_comment, field_rename_annotation, keyword_replace};
 use crate::{
-    codegen_options::GraphQLClientCodegenOptions,
+    codegen_options::{derive_name, GraphQLClientCodegenOptions},
     query::{BoundQuery, UsedTypes},
-    schema::{input_is_recursive_without_indirection, StoredInputType},
+    schema::{
+        input_is_recursive_without_indirection, InputId, StoredInputFieldType, StoredInputType,
+    },
     type_qualifiers::GraphqlTypeQualifier,
 };
 use heck::{ToSnakeCase, ToUpperCamelCase};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
+use std::borrow::Cow;
 
 pub(super) fn generate_input_object_definitions(
     all_used_types: &UsedTypes,
@@ -34,10 +38,12 @@ pub(super) fn generate_input_object_definitions(
                 .and_then(|(index, _)| custom_variable_types.get(index));
             if let Some(custom_type) = custom_variable_type {
                 generate_type_def(input, options, custom_type)
+            } else if let Some(shared_types_module) = options.shared_types_module() {
+                generate_reexport(input, options, shared_types_module)
             } else if input.is_one_of {
                 generate_enum(input, options, variable_derives, query)
             } else {
-                generate_struct(input, options, variable_derives, query)
+                generate_struct(input_id, input, options, variable_derives, query)
             }
         })
         .collect()
@@ -55,7 +61,19 @@ fn generate_type_def(
     quote!(pub type #struct_name = #custom_type;)
 }
 
+fn generate_reexport(
+    input: &StoredInputType,
+    options: &GraphQLClientCodegenOptions,
+    shared_types_module: &syn::Path,
+) -> TokenStream {
+    let normalized_name = options.normalization().input_name(input.name.as_str());
+    let safe_name = keyword_replace(normalized_name);
+    let struct_name = Ident::new(safe_name.as_ref(), Span::call_site());
+    quote!(pub use #shared_types_module::#struct_name;)
+}
+
 fn generate_struct(
+    input_id: InputId,
     input: &StoredInputType,
     options: &GraphQLClientCodegenOptions,
     variable_derives: &impl quote::ToTokens,
@@ -68,44 +86,220 @@ fn generate_struct(
     let safe_name = keyword_replace(normalized_name);
     let struct_name = Ident::new(safe_name.as_ref(), Span::call_site());
 
-    let fields = input.fields.iter().map(|(field_name, field_type)| {
-        let safe_field_name = keyword_replace(field_name.to_snake_case());
-        let annotation = field_rename_annotation(field_name, safe_field_name.as_ref());
-        let name_ident = Ident::new(safe_field_name.as_ref(), Span::call_site());
-        let normalized_field_type_name = options
-            .normalization()
-            .field_type(field_type.id.name(query.schema));
-        let optional_skip_serializing_none =
-            if *options.skip_serializing_none() && field_type.is_optional() {
+    let fields = input
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, (field_name, field_type))| {
+            let doc_comment = doc_comment(input.field_descriptions[idx].as_deref(), options);
+            let safe_field_name = keyword_replace(field_name.to_snake_case());
+            let annotation = field_rename_annotation(field_name, safe_field_name.as_ref());
+            let name_ident = Ident::new(safe_field_name.as_ref(), Span::call_site());
+            let maybe_undefined = *options.maybe_undefined() && field_type.is_optional();
+            let optional_skip_serializing_none = if maybe_undefined {
+                Some(super::maybe_undefined_annotation())
+            } else if *options.skip_serializing_none() && field_type.is_optional() {
                 Some(quote!(#[serde(skip_serializing_if = "Option::is_none")]))
             } else {
                 None
             };
-        let type_name = Ident::new(normalized_field_type_name.as_ref(), Span::call_site());
-        let field_type_tokens = super::decorate_type(&type_name, &field_type.qualifiers);
-        let field_type = if field_type
-            .id
-            .as_input_id()
-            .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
-            .unwrap_or(false)
-        {
-            quote!(Box<#field_type_tokens>)
-        } else {
-            field_type_tokens
-        };
-
-        quote!(
-            #optional_skip_serializing_none
-            #annotation pub #name_ident: #field_type
-        )
-    });
+            let serde_with_annotation = super::serde_with_annotation(
+                field_type.id,
+                field_type.is_optional() && !maybe_undefined,
+                options,
+                query.schema,
+            );
+            let field_type = render_field_type(&input.name, field_name, field_type, options, query);
+
+            quote!(
+                #doc_comment
+                #optional_skip_serializing_none
+                #serde_with_annotation
+                #annotation pub #name_ident: #field_type
+            )
+        });
+    let struct_doc = doc_comment(input.description.as_deref(), options);
+
+    // The defaults declared in the schema, as `default_field()` constructors.
+    let defaults: Vec<_> = input
+        .fields
+        .iter()
+        .zip(&input.field_defaults)
+        .filter_map(|((field_name, field_type), default)| {
+            let default = default.as_ref()?;
+            let method_name = format!("default_{}", field_name.to_snake_case());
+            let method_name = Ident::new(&method_name, Span::call_site());
+            let return_type =
+                render_field_type(&input.name, field_name, field_type, options, query);
+            let value = super::input_field_literal(default, field_type, options, query);
+
+            Some(quote! {
+                pub fn #method_name() -> #return_type {
+                    #value
+                }
+            })
+        })
+        .collect();
+    let default_constructors = if defaults.is_empty() {
+        None
+    } else {
+        Some(quote! {
+            impl #struct_name {
+                #(#defaults)*
+            }
+        })
+    };
+
+    let default_impl = generate_default_impl(input_id, input, &struct_name, options, query);
+
+    let builder = if *options.builders() {
+        let fields: Vec<_> = input
+            .fields
+            .iter()
+            .zip(&input.field_defaults)
+            .map(|((field_name, field_type), default)| {
+                let mut field = builder_field(&input.name, field_name, field_type, options, query);
+                if default.is_some() {
+                    let method_name = format!("default_{}", field_name.to_snake_case());
+                    let method_name = Ident::new(&method_name, Span::call_site());
+                    field.default = Some(quote!(#struct_name::#method_name()));
+                }
+                field
+            })
+            .collect();
+        Some(generate_builder(&struct_name, &fields))
+    } else {
+        None
+    };
 
     quote! {
+        #struct_doc
         #variable_derives
         #[serde(crate = #serde_path)]
         pub struct #struct_name{
             #(#fields,)*
         }
+
+        #default_constructors
+
+        #default_impl
+
+        #builder
+    }
+}
+
+/// `Default` for the input objects with schema defaults whose fields are all nullable or have a
+/// default, unless `Default` is derived.
+fn generate_default_impl(
+    input_id: InputId,
+    input: &StoredInputType,
+    struct_name: &Ident,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Option<TokenStream> {
+    let has_defaults = input.field_defaults.iter().any(Option::is_some);
+    let derives_default = options
+        .all_variable_derives()
+        .any(|derive| derive_name(derive) == "Default");
+    let all_fields_can_be_left_out = input
+        .fields
+        .iter()
+        .zip(&input.field_defaults)
+        .all(|((_, field_type), default)| field_type.is_optional() || default.is_some());
+
+    if !has_defaults || derives_default || !all_fields_can_be_left_out {
+        return None;
+    }
+
+    let object_literal = super::render_object_literal::<&str>(
+        &std::collections::BTreeMap::new(),
+        input_id,
+        options,
+        query,
+    );
+
+    Some(quote! {
+        impl Default for #struct_name {
+            fn default() -> Self {
+                #object_literal
+            }
+        }
+    })
+}
+
+/// The type of the field in the struct, boxed when the input object is recursive.
+fn render_field_type(
+    input_name: &str,
+    field_name: &str,
+    field_type: &StoredInputFieldType,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> TokenStream {
+    let type_name = field_type_ident(input_name, field_name, field_type, options, query);
+    let boxed = field_type
+        .id
+        .as_input_id()
+        .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
+        .unwrap_or(false);
+
+    if *options.maybe_undefined() && field_type.is_optional() {
+        return super::maybe_undefined_type(&type_name, &field_type.qualifiers, boxed);
+    }
+
+    let field_type_tokens = super::decorate_type(&type_name, &field_type.qualifiers);
+    if boxed {
+        quote!(Box<#field_type_tokens>)
+    } else {
+        field_type_tokens
+    }
+}
+
+/// The typed ID of the field, mapped as `Input.field`, or else its normalized schema type.
+fn field_type_ident(
+    input_name: &str,
+    field_name: &str,
+    field_type: &StoredInputFieldType,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Ident {
+    let key = format!("{}.{}", input_name, field_name);
+    let typed_id = super::typed_ids::input_typed_id(&key, field_type.id, options, query);
+    let type_name = typed_id.map(Cow::Owned).unwrap_or_else(|| {
+        options
+            .normalization()
+            .field_type(field_type.id.name(query.schema))
+    });
+
+    Ident::new(type_name.as_ref(), Span::call_site())
+}
+
+fn builder_field(
+    input_name: &str,
+    field_name: &str,
+    field_type: &StoredInputFieldType,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> BuilderField {
+    let safe_field_name = keyword_replace(field_name.to_snake_case());
+    let type_name = field_type_ident(input_name, field_name, field_type, options, query);
+
+    let optional = field_type.is_optional();
+    let mut qualifiers = field_type.qualifiers.clone();
+    if optional {
+        qualifiers.insert(0, GraphqlTypeQualifier::Required);
+    }
+
+    BuilderField {
+        ident: Ident::new(safe_field_name.as_ref(), Span::call_site()),
+        value_ty: super::decorate_type(&type_name, &qualifiers),
+        optional,
+        maybe_undefined: optional && *options.maybe_undefined(),
+        boxed: field_type
+            .id
+            .as_input_id()
+            .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
+            .unwrap_or(false),
+        default: None,
     }
 }
 
@@ -119,40 +313,48 @@ fn generate_enum(
     let safe_name = keyword_replace(normalized_name);
     let enum_name = Ident::new(safe_name.as_ref(), Span::call_site());
 
-    let variants = input.fields.iter().map(|(field_name, field_type)| {
-        let variant_name = field_name.to_upper_camel_case();
-        let safe_variant_name = keyword_replace(&variant_name);
+    let variants = input
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(idx, (field_name, field_type))| {
+            let doc_comment = doc_comment(input.field_descriptions[idx].as_deref(), options);
+            let variant_name = field_name.to_upper_camel_case();
+            let safe_variant_name = keyword_replace(&variant_name);
 
-        let annotation = field_rename_annotation(field_name.as_ref(), &variant_name);
-        let name_ident = Ident::new(safe_variant_name.as_ref(), Span::call_site());
+            let annotation = field_rename_annotation(field_name.as_ref(), &variant_name);
+            let name_ident = Ident::new(safe_variant_name.as_ref(), Span::call_site());
 
-        let normalized_field_type_name = options
-            .normalization()
-            .field_type(field_type.id.name(query.schema));
-        let type_name = Ident::new(normalized_field_type_name.as_ref(), Span::call_site());
+            let normalized_field_type_name = options
+                .normalization()
+                .field_type(field_type.id.name(query.schema));
+            let type_name = Ident::new(normalized_field_type_name.as_ref(), Span::call_site());
 
-        // Add the required qualifier so that the variant's field isn't wrapped in Option
-        let mut qualifiers = vec![GraphqlTypeQualifier::Required];
-        qualifiers.extend(field_type.qualifiers.iter().cloned());
+            // Add the required qualifier so that the variant's field isn't wrapped in Option
+            let mut qualifiers = vec![GraphqlTypeQualifier::Required];
+            qualifiers.extend(field_type.qualifiers.iter().cloned());
 
-        let field_type_tokens = super::decorate_type(&type_name, &qualifiers);
-        let field_type = if field_type
-            .id
-            .as_input_id()
-            .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
-            .unwrap_or(false)
-        {
-            quote!(Box<#field_type_tokens>)
-        } else {
-            field_type_tokens
-        };
-
-        quote!(
-            #annotation #name_ident(#field_type)
-        )
-    });
+            let field_type_tokens = super::decorate_type(&type_name, &qualifiers);
+            let field_type = if field_type
+                .id
+                .as_input_id()
+                .map(|input_id| input_is_recursive_without_indirection(input_id, query.schema))
+                .unwrap_or(false)
+            {
+                quote!(Box<#field_type_tokens>)
+            } else {
+                field_type_tokens
+            };
+
+            quote!(
+                #doc_comment
+                #annotation #name_ident(#field_type)
+            )
+        });
+    let enum_doc = doc_comment(input.description.as_deref(), options);
 
     quote! {
+        #enum_doc
         #variable_derives
         pub enum #enum_name{
             #(#variants,)*
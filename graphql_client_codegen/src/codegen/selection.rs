@@ -3,7 +3,7 @@
 use crate::{
     codegen::{
         decorate_type,
-        shared::{field_rename_annotation, keyword_replace},
+        shared::{doc_comment, field_rename_annotation, keyword_replace},
     },
     deprecation::DeprecationStrategy,
     query::{
@@ -37,6 +37,7 @@ pub(crate) fn render_response_data_fields<'a>(
 
     let response_data_type_id = expanded_selection.push_type(ExpandedType {
         name: Cow::Borrowed("ResponseData"),
+        description: None,
     });
 
     if let Some(custom_response_type) = options.custom_response_type() {
@@ -71,6 +72,7 @@ pub(crate) fn render_response_data_fields<'a>(
         &operation.selection_set,
         response_data_type_id,
         TypeId::Object(operation.object_id),
+        false,
         options,
     );
 
@@ -96,10 +98,13 @@ fn calculate_custom_response_type_selection<'a>(
         flatten: false,
         boxed: false,
         deprecation: field.deprecation(),
+        description: field.description.as_deref(),
+        serde_with: None,
     });
 
     let struct_id = context.push_type(ExpandedType {
         name: struct_name_string.into(),
+        description: None,
     });
     context.push_type_alias(TypeAlias {
         name: custom_response_type,
@@ -125,6 +130,7 @@ pub(super) fn render_fragment<'a>(
 
     let response_type_id = expanded_selection.push_type(ExpandedType {
         name: fragment.name.as_str().into(),
+        description: fragment.on.description(query.schema),
     });
 
     calculate_selection(
@@ -132,6 +138,7 @@ pub(super) fn render_fragment<'a>(
         &fragment.selection_set,
         response_type_id,
         fragment.on,
+        false,
         options,
     );
 
@@ -158,8 +165,13 @@ impl<'a> VariantSelection<'a> {
             Selection::FragmentSpread(fragment_id) => {
                 let fragment = query.query.get_fragment(*fragment_id);
 
-                if fragment.on == type_id {
-                    // The selection is on the type itself.
+                if query.schema.type_condition_applies(fragment.on, type_id) {
+                    // The selection is on the type itself, or on an interface it implements.
+                    None
+                } else if let TypeId::Interface(_) | TypeId::Union(_) = fragment.on {
+                    // The fragment refines the type to another abstract type. The fragment's
+                    // own variants need `__typename`, which the enclosing enum consumes, so
+                    // it is rendered as an optional field on the type itself instead.
                     None
                 } else {
                     // The selection is on one of the variants of the type.
@@ -178,16 +190,22 @@ impl<'a> VariantSelection<'a> {
     }
 }
 
+/// `conditional` is true when the whole selection set is under a `@skip` or `@include`
+/// condition, so that none of its fields are guaranteed to be in the response.
 fn calculate_selection<'a>(
     context: &mut ExpandedSelection<'a>,
     selection_set: &[SelectionId],
     struct_id: ResponseTypeId,
     type_id: TypeId,
+    conditional: bool,
     options: &'a GraphQLClientCodegenOptions,
 ) {
+    let query = context.query;
+    let is_conditional = |id: SelectionId| conditional || query.query.is_conditional(id);
+
     // If the selection only contains a fragment, replace the selection with
     // that fragment.
-    if selection_set.len() == 1 {
+    if selection_set.len() == 1 && !is_conditional(selection_set[0]) {
         if let Selection::FragmentSpread(fragment_id) =
             context.query.query.get_selection(selection_set[0])
         {
@@ -204,13 +222,12 @@ fn calculate_selection<'a>(
     // If we are on a union or an interface, we need to generate an enum that matches the variants _exhaustively_.
     {
         let variants: Option<Cow<'_, [TypeId]>> = match type_id {
-            TypeId::Interface(interface_id) => {
-                let variants = context
-                    .query
+            TypeId::Interface(_) => {
+                let variants = query
                     .schema
-                    .objects()
-                    .filter(|(_, obj)| obj.implements_interfaces.contains(&interface_id))
-                    .map(|(id, _)| TypeId::Object(id));
+                    .possible_types(type_id)
+                    .into_iter()
+                    .map(TypeId::Object);
 
                 Some(variants.collect::<Vec<TypeId>>().into())
             }
@@ -241,11 +258,13 @@ fn calculate_selection<'a>(
                 let variant_selections: Vec<_> = variant_selections
                     .iter()
                     .filter(|(_id, _selection_ref, variant)| {
-                        variant.variant_type_id() == *variant_type_id
+                        query
+                            .schema
+                            .type_condition_applies(variant.variant_type_id(), *variant_type_id)
                     })
                     .collect();
 
-                if let Some((selection_id, selection, _variant)) = variant_selections.first() {
+                if let Some((selection_id, _selection, _variant)) = variant_selections.first() {
                     let mut variant_struct_name_str =
                         full_path_prefix(*selection_id, context.query);
                     variant_struct_name_str.reserve(2 + variant_name_str.len());
@@ -261,11 +280,12 @@ fn calculate_selection<'a>(
 
                     let expanded_type = ExpandedType {
                         name: variant_struct_name_str.into(),
+                        description: variant_type_id.description(context.schema()),
                     };
 
                     let struct_id = context.push_type(expanded_type);
 
-                    if variant_selections.len() == 1 {
+                    if variant_selections.len() == 1 && !is_conditional(variant_selections[0].0) {
                         if let VariantSelection::FragmentSpread((fragment_id, fragment)) =
                             variant_selections[0].2
                         {
@@ -278,7 +298,7 @@ fn calculate_selection<'a>(
                         }
                     }
 
-                    for (_selection_id, _selection, variant_selection) in variant_selections {
+                    for (selection_id, selection, variant_selection) in variant_selections {
                         match variant_selection {
                             VariantSelection::InlineFragment(_) => {
                                 calculate_selection(
@@ -286,19 +306,24 @@ fn calculate_selection<'a>(
                                     selection.subselection(),
                                     struct_id,
                                     *variant_type_id,
+                                    is_conditional(*selection_id),
                                     options,
                                 );
                             }
                             VariantSelection::FragmentSpread((fragment_id, fragment)) => context
                                 .push_field(ExpandedField {
                                     field_type: fragment.name.as_str().into(),
-                                    field_type_qualifiers: &[GraphqlTypeQualifier::Required],
+                                    field_type_qualifiers: fragment_spread_qualifiers(
+                                        is_conditional(*selection_id),
+                                    ),
                                     flatten: true,
                                     graphql_name: None,
                                     rust_name: fragment.name.to_snake_case().into(),
                                     struct_id,
                                     deprecation: None,
                                     boxed: fragment_is_recursive(*fragment_id, context.query.query),
+                                    description: None,
+                                    serde_with: None,
                                 }),
                         }
                     }
@@ -331,6 +356,8 @@ fn calculate_selection<'a>(
                 let (graphql_name, rust_name) = context.field_name(field);
                 let schema_field = field.schema_field(context.schema());
                 let field_type_id = schema_field.r#type.id;
+                let field_type_qualifiers =
+                    selected_field_qualifiers(&schema_field.r#type.qualifiers, is_conditional(*id));
 
                 match field_type_id {
                     TypeId::Enum(enm) => {
@@ -341,27 +368,41 @@ fn calculate_selection<'a>(
                             field_type: options
                                 .normalization()
                                 .field_type(&context.schema().get_enum(enm).name),
-                            field_type_qualifiers: &schema_field.r#type.qualifiers,
+                            field_type_qualifiers,
                             flatten: false,
                             deprecation: schema_field.deprecation(),
                             boxed: false,
+                            description: schema_field.description.as_deref(),
+                            serde_with: None,
                         });
                     }
                     TypeId::Scalar(scalar) => {
+                        let typed_id =
+                            super::typed_ids::field_typed_id(schema_field, options, context.query);
+
                         context.push_field(ExpandedField {
-                            field_type: options
-                                .normalization()
-                                .field_type(context.schema().get_scalar(scalar).name.as_str()),
-                            field_type_qualifiers: &field
-                                .schema_field(context.schema())
-                                .r#type
-                                .qualifiers,
+                            field_type: typed_id.map(Cow::Owned).unwrap_or_else(|| {
+                                options
+                                    .normalization()
+                                    .field_type(context.schema().get_scalar(scalar).name.as_str())
+                            }),
+                            field_type_qualifiers,
                             graphql_name: Some(graphql_name),
                             struct_id,
                             rust_name,
                             flatten: false,
                             deprecation: schema_field.deprecation(),
                             boxed: false,
+                            description: schema_field.description.as_deref(),
+                            serde_with: super::serde_with_annotation(
+                                field_type_id,
+                                !field_type_qualifiers
+                                    .first()
+                                    .map(GraphqlTypeQualifier::is_required)
+                                    .unwrap_or(false),
+                                options,
+                                context.schema(),
+                            ),
                         });
                     }
                     TypeId::Object(_) | TypeId::Interface(_) | TypeId::Union(_) => {
@@ -371,15 +412,28 @@ fn calculate_selection<'a>(
                             struct_id,
                             graphql_name: Some(graphql_name),
                             rust_name,
-                            field_type_qualifiers: &schema_field.r#type.qualifiers,
+                            field_type_qualifiers,
                             field_type: Cow::Owned(struct_name_string.clone()),
                             flatten: false,
                             boxed: false,
                             deprecation: schema_field.deprecation(),
+                            description: schema_field.description.as_deref(),
+                            serde_with: None,
                         });
 
+                        // An inline fragment on an abstract type is expanded into every variant
+                        // it applies to, but its fields only need their types generated once.
+                        if context
+                            .types
+                            .iter()
+                            .any(|tpe| tpe.name == struct_name_string)
+                        {
+                            continue;
+                        }
+
                         let type_id = context.push_type(ExpandedType {
                             name: Cow::Owned(struct_name_string),
+                            description: field_type_id.description(context.schema()),
                         });
 
                         calculate_selection(
@@ -387,6 +441,7 @@ fn calculate_selection<'a>(
                             selection.subselection(),
                             type_id,
                             field_type_id,
+                            false,
                             options,
                         );
                     }
@@ -402,10 +457,14 @@ fn calculate_selection<'a>(
                 let fragment = context.query.query.get_fragment(*fragment_id);
 
                 // Assuming the query was validated properly, a fragment spread
-                // is either on the field's type itself, or on one of the
-                // variants (union or interfaces). If it's not directly a field
-                // on the struct, it will be handled in the `on` variants.
-                if fragment.on != type_id {
+                // is either on the field's type itself (or an interface it
+                // implements), on another abstract type that only some of its
+                // variants belong to, or on one of the variants (union or
+                // interfaces). Spreads on a variant are handled in the `on`
+                // variants.
+                let applies = query.schema.type_condition_applies(fragment.on, type_id);
+
+                if !applies && !matches!(fragment.on, TypeId::Interface(_) | TypeId::Union(_)) {
                     continue;
                 }
 
@@ -414,13 +473,17 @@ fn calculate_selection<'a>(
 
                 context.push_field(ExpandedField {
                     field_type: fragment.name.as_str().into(),
-                    field_type_qualifiers: &[GraphqlTypeQualifier::Required],
+                    field_type_qualifiers: fragment_spread_qualifiers(
+                        !applies || is_conditional(*id),
+                    ),
                     graphql_name: None,
                     rust_name: final_field_name,
                     struct_id,
                     flatten: true,
                     deprecation: None,
                     boxed: fragment_is_recursive(*fragment_id, context.query.query),
+                    description: None,
+                    serde_with: None,
                 });
 
                 // We stop here, because the structs for the fragments are generated separately, to
@@ -430,6 +493,29 @@ fn calculate_selection<'a>(
     }
 }
 
+/// Conditionally selected fields can be missing from the response even when they are non-null
+/// in the schema.
+fn selected_field_qualifiers(
+    qualifiers: &[GraphqlTypeQualifier],
+    conditional: bool,
+) -> &[GraphqlTypeQualifier] {
+    match qualifiers.split_first() {
+        Some((GraphqlTypeQualifier::Required, rest)) if conditional => rest,
+        _ => qualifiers,
+    }
+}
+
+/// Fragment spreads are flattened into their parent struct. A conditional one, or one on an
+/// abstract type that the parent only sometimes belongs to, becomes an `Option`, which is `None`
+/// when the fragment does not apply to the response.
+fn fragment_spread_qualifiers(conditional: bool) -> &'static [GraphqlTypeQualifier] {
+    if conditional {
+        &[]
+    } else {
+        &[GraphqlTypeQualifier::Required]
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 struct ResponseTypeId(u32);
 
@@ -448,6 +534,9 @@ struct ExpandedField<'a> {
     flatten: bool,
     deprecation: Option<Option<&'a str>>,
     boxed: bool,
+    description: Option<&'a str>,
+    /// The serde adapter of a well-known scalar.
+    serde_with: Option<TokenStream>,
 }
 
 impl ExpandedField<'_> {
@@ -497,8 +586,13 @@ impl ExpandedField<'_> {
                 (Some(_), DeprecationStrategy::Deny) => return None,
             };
 
+        let doc_comment = doc_comment(self.description, options);
+        let serde_with = &self.serde_with;
+
         let tokens = quote! {
+            #doc_comment
             #optional_skip_serializing_none
+            #serde_with
             #optional_flatten
             #optional_rename
             #optional_deprecation_annotation
@@ -537,6 +631,7 @@ impl ExpandedVariant<'_> {
 
 pub(crate) struct ExpandedType<'a> {
     name: Cow<'a, str>,
+    description: Option<&'a str>,
 }
 
 pub(crate) struct ExpandedSelection<'a> {
@@ -598,6 +693,7 @@ impl<'a> ExpandedSelection<'a> {
 
         for (type_id, ty) in self.types() {
             let struct_name = Ident::new(&ty.name, Span::call_site());
+            let doc_comment = doc_comment(ty.description, self.options);
 
             // If the type is aliased, stop here.
             if let Some(alias) = self.aliases.iter().find(|alias| alias.struct_id == type_id) {
@@ -632,6 +728,7 @@ impl<'a> ExpandedSelection<'a> {
             // of the variants.
             if fields.peek().is_none() {
                 let item = quote! {
+                    #doc_comment
                     #response_derives
                     #[serde(tag = "__typename")]
                     pub enum #struct_name {
@@ -661,6 +758,7 @@ impl<'a> ExpandedSelection<'a> {
             };
 
             let tokens = quote! {
+                #doc_comment
                 #response_derives
                 #[serde(crate = #serde_path)]
                 pub struct #struct_name {
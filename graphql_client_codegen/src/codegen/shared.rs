@@ -1,3 +1,4 @@
+use crate::codegen_options::GraphQLClientCodegenOptions;
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::borrow::Cow;
@@ -31,6 +32,144 @@ pub(crate) fn field_rename_annotation(graphql_name: &str, rust_name: &str) -> Op
     }
 }
 
+/// Renders a schema description as doc attributes, one per line, like `///` comments would.
+pub(crate) fn doc_comment(
+    description: Option<&str>,
+    options: &GraphQLClientCodegenOptions,
+) -> Option<TokenStream> {
+    if *options.skip_descriptions() {
+        return None;
+    }
+
+    let lines = escape_code_blocks(description?.trim())
+        .into_iter()
+        .map(|line| {
+            if line.is_empty() {
+                line
+            } else {
+                format!(" {}", line)
+            }
+        });
+
+    Some(quote!(#(#[doc = #lines])*))
+}
+
+/// rustdoc compiles the code blocks of doc comments as doctests, unless they have a language
+/// other than Rust. So the fenced blocks without a language, or with a Rust one, are marked as
+/// `text`, and the indented blocks are turned into `text` fences.
+fn escape_code_blocks(description: &str) -> Vec<String> {
+    let source: Vec<&str> = description.lines().map(str::trim_end).collect();
+    let mut lines = Vec::with_capacity(source.len());
+    let mut fence: Option<(char, usize)> = None;
+    let mut in_indented_block = false;
+    let mut after_blank_line = true;
+
+    for (idx, line) in source.iter().enumerate() {
+        if let Some((fence_char, fence_len)) = fence {
+            if closes_fence(line, fence_char, fence_len) {
+                fence = None;
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if in_indented_block {
+            if let Some(code) = strip_indentation(line) {
+                lines.push(code.to_owned());
+                continue;
+            }
+
+            let continues = source[idx..]
+                .iter()
+                .find(|line| !line.is_empty())
+                .and_then(|line| strip_indentation(line))
+                .is_some();
+            if line.is_empty() && continues {
+                lines.push(String::new());
+                continue;
+            }
+
+            lines.push("```".to_owned());
+            in_indented_block = false;
+        }
+
+        match (after_blank_line, strip_indentation(line)) {
+            (true, Some(code)) => {
+                lines.push("```text".to_owned());
+                lines.push(code.to_owned());
+                in_indented_block = true;
+            }
+            _ => match opening_fence(line) {
+                Some((indentation, fence_char, fence_len, info)) => {
+                    fence = Some((fence_char, fence_len));
+
+                    if is_rust_info(info) {
+                        let marker = fence_char.to_string().repeat(fence_len);
+                        lines.push(format!("{}{}text", indentation, marker));
+                    } else {
+                        lines.push(line.to_string());
+                    }
+                }
+                None => lines.push(line.to_string()),
+            },
+        }
+
+        after_blank_line = line.is_empty();
+    }
+
+    if in_indented_block {
+        lines.push("```".to_owned());
+    }
+
+    lines
+}
+
+fn strip_indentation(line: &str) -> Option<&str> {
+    line.strip_prefix("    ")
+        .or_else(|| line.strip_prefix('\t'))
+}
+
+/// The indentation, fence character and length, and info string of a line opening a fenced
+/// code block.
+fn opening_fence(line: &str) -> Option<(&str, char, usize, &str)> {
+    let trimmed = line.trim_start();
+    let indentation = &line[..line.len() - trimmed.len()];
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = trimmed.chars().take_while(|c| *c == fence_char).count();
+    let info = trimmed[fence_len..].trim();
+
+    if fence_len < 3 || indentation.len() > 3 || (fence_char == '`' && info.contains('`')) {
+        return None;
+    }
+
+    Some((indentation, fence_char, fence_len, info))
+}
+
+fn closes_fence(line: &str, fence_char: char, fence_len: usize) -> bool {
+    let trimmed = line.trim();
+
+    trimmed.len() >= fence_len && trimmed.chars().all(|c| c == fence_char)
+}
+
+/// Whether rustdoc would compile a code block with this info string.
+fn is_rust_info(info: &str) -> bool {
+    info.is_empty()
+        || info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .any(|tag| {
+                matches!(
+                    tag,
+                    "rust"
+                        | "ignore"
+                        | "should_panic"
+                        | "no_run"
+                        | "compile_fail"
+                        | "test_harness"
+                        | "standalone_crate"
+                ) || tag.starts_with("edition")
+            })
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -41,4 +180,67 @@ mod tests {
         assert_eq!("fn_", keyword_replace("fn"));
         assert_eq!("struct_", keyword_replace("struct"));
     }
+
+    #[test]
+    fn doc_comment_marks_code_blocks_as_text() {
+        use super::doc_comment;
+        use crate::{CodegenMode, GraphQLClientCodegenOptions};
+
+        let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        let description = "A user.\n\n```\nquery { viewer }\n```\n```graphql\n{ a }\n```\n";
+
+        assert_eq!(
+            doc_comment(Some(description), &options)
+                .unwrap()
+                .to_string(),
+            quote::quote! {
+                #[doc = " A user."]
+                #[doc = ""]
+                #[doc = " ```text"]
+                #[doc = " query { viewer }"]
+                #[doc = " ```"]
+                #[doc = " ```graphql"]
+                #[doc = " { a }"]
+                #[doc = " ```"]
+            }
+            .to_string()
+        );
+
+        options.set_skip_descriptions(true);
+        assert!(doc_comment(Some(description), &options).is_none());
+    }
+
+    #[test]
+    fn doc_comment_marks_rust_and_indented_code_blocks_as_text() {
+        use super::doc_comment;
+        use crate::{CodegenMode, GraphQLClientCodegenOptions};
+
+        let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        let description = "Example:\n\n```rust,no_run\nlet a = 1;\n```\n\n    let b = 2;\n\n    let c = 3;\nDone.\n\n~~~~\n```\n~~~~\n";
+
+        assert_eq!(
+            doc_comment(Some(description), &options)
+                .unwrap()
+                .to_string(),
+            quote::quote! {
+                #[doc = " Example:"]
+                #[doc = ""]
+                #[doc = " ```text"]
+                #[doc = " let a = 1;"]
+                #[doc = " ```"]
+                #[doc = ""]
+                #[doc = " ```text"]
+                #[doc = " let b = 2;"]
+                #[doc = ""]
+                #[doc = " let c = 3;"]
+                #[doc = " ```"]
+                #[doc = " Done."]
+                #[doc = ""]
+                #[doc = " ~~~~text"]
+                #[doc = " ```"]
+                #[doc = " ~~~~"]
+            }
+            .to_string()
+        );
+    }
 }
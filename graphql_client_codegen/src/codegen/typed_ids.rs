@@ -0,0 +1,232 @@
+//! Newtypes for the `ID`s of each object and interface, generated with the `typed_ids` option, so
+//! that a `UserId` cannot be passed where a `RepositoryId` is expected.
+
+use crate::{
+    codegen_options::GraphQLClientCodegenOptions,
+    diagnostics::Diagnostic,
+    query::{BoundQuery, ResolvedVariable, UsedTypes},
+    schema::{Schema, StoredField, StoredFieldParent, TypeId},
+};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::collections::BTreeMap;
+
+/// `UserId` for `User`.
+fn typed_id_name(owner: &str, options: &GraphQLClientCodegenOptions) -> String {
+    options
+        .normalization()
+        .field_type(&format!("{}Id", owner))
+        .into_owned()
+}
+
+/// The newtype of an `ID` field of an object or interface.
+pub(super) fn field_typed_id(
+    field: &StoredField,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Option<String> {
+    if !*options.typed_ids() || !field.r#type.id.is_id(query.schema) {
+        return None;
+    }
+
+    Some(typed_id_name(field.parent.name(query.schema), options))
+}
+
+/// The newtype of an `ID` variable or input field, when it is mapped in `typed_id_inputs`: by
+/// name for variables, and as `Input.field` for input fields.
+pub(super) fn input_typed_id(
+    key: &str,
+    type_id: TypeId,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Option<String> {
+    if !*options.typed_ids() || !type_id.is_id(query.schema) {
+        return None;
+    }
+
+    let owner = options.typed_id_inputs().get(key)?;
+    Some(typed_id_name(owner, options))
+}
+
+/// The problems with the `typed_id_inputs` entries: the input fields that are not `ID` input
+/// fields of the schema, the variables that are not `ID` variables of the query, and the owners
+/// that are not objects or interfaces. Variables are not checked without a query, nor when they
+/// are set for the whole crate.
+pub(crate) fn validate_typed_id_inputs(
+    options: &GraphQLClientCodegenOptions,
+    schema: &Schema,
+    variables: Option<&[ResolvedVariable]>,
+) -> Vec<Diagnostic> {
+    if !*options.typed_ids() {
+        return Vec::new();
+    }
+
+    let mut diagnostics = Vec::new();
+
+    for (key, owner) in options.typed_id_inputs() {
+        if let Some((input_name, field_name)) = key.split_once('.') {
+            let is_id_input_field = match schema.find_type(input_name) {
+                Some(TypeId::Input(input_id)) => schema
+                    .get_input(input_id)
+                    .fields
+                    .iter()
+                    .any(|(name, field)| name == field_name && field.id.is_id(schema)),
+                _ => false,
+            };
+
+            if !is_id_input_field {
+                diagnostics.push(Diagnostic::new(format!(
+                    "The typed ID input `{}` is not an `ID` input field of the schema.",
+                    key
+                )));
+            }
+        } else if let Some(variables) = variables {
+            let is_id_variable = variables
+                .iter()
+                .any(|variable| variable.name == *key && variable.r#type.id.is_id(schema));
+
+            if !is_id_variable && !options.is_crate_wide_typed_id_variable(key) {
+                diagnostics.push(Diagnostic::new(format!(
+                    "The typed ID input `{}` is not an `ID` variable of the query.",
+                    key
+                )));
+            }
+        }
+
+        if !matches!(
+            schema.find_type(owner),
+            Some(TypeId::Object(_)) | Some(TypeId::Interface(_))
+        ) {
+            diagnostics.push(Diagnostic::new(format!(
+                "The owner `{}` of the typed ID input `{}` is not an object or interface of the schema.",
+                owner, key
+            )));
+        }
+    }
+
+    diagnostics
+}
+
+/// The newtypes of the selected `ID` fields, and of the mapped inputs. Unused ones are harmless,
+/// as the generated modules allow dead code.
+pub(super) fn generate_typed_id_definitions(
+    all_used_types: &UsedTypes,
+    options: &GraphQLClientCodegenOptions,
+    query: &BoundQuery<'_>,
+) -> Vec<TokenStream> {
+    if !*options.typed_ids() {
+        return Vec::new();
+    }
+
+    // The owners by newtype name, with their type when they are in the schema. Use BTreeMap
+    // instead of HashMap for a stable ordering.
+    let mut owners: BTreeMap<String, Option<TypeId>> = BTreeMap::new();
+    for field_id in &all_used_types.id_fields {
+        let parent = &query.schema.get_field(*field_id).parent;
+        let owner = match parent {
+            StoredFieldParent::Object(id) => TypeId::Object(*id),
+            StoredFieldParent::Interface(id) => TypeId::Interface(*id),
+        };
+        owners.insert(
+            typed_id_name(parent.name(query.schema), options),
+            Some(owner),
+        );
+    }
+    for owner in options.typed_id_inputs().values() {
+        owners
+            .entry(typed_id_name(owner, options))
+            .or_insert_with(|| query.schema.find_type(owner));
+    }
+
+    owners
+        .iter()
+        .map(|(name, owner)| {
+            let name = Ident::new(name, Span::call_site());
+
+            if let Some(shared_types_module) = options.shared_types_module() {
+                return quote!(pub use #shared_types_module::#name;);
+            }
+
+            let definition = generate_typed_id(&name, options);
+            let conversions = owners.iter().filter_map(|(interface_name, interface)| {
+                let interface_id = match interface {
+                    Some(TypeId::Interface(id)) => *id,
+                    _ => return None,
+                };
+                let owner = (*owner)?;
+                if !query.schema.implements_interface(owner, interface_id) {
+                    return None;
+                }
+
+                let interface_name = Ident::new(interface_name, Span::call_site());
+                Some(generate_interface_conversions(&name, &interface_name))
+            });
+
+            quote! {
+                #definition
+
+                #(#conversions)*
+            }
+        })
+        .collect()
+}
+
+/// A `UserId` is also a `NodeId` when `User` implements `Node`, so that the ID of a `node` can be
+/// compared with the ID of a `... on User` selection.
+fn generate_interface_conversions(name: &Ident, interface_name: &Ident) -> TokenStream {
+    quote! {
+        impl From<#name> for #interface_name {
+            fn from(id: #name) -> Self {
+                #interface_name(id.0)
+            }
+        }
+
+        impl PartialEq<#interface_name> for #name {
+            fn eq(&self, other: &#interface_name) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl PartialEq<#name> for #interface_name {
+            fn eq(&self, other: &#name) -> bool {
+                self.0 == other.0
+            }
+        }
+    }
+}
+
+fn generate_typed_id(name: &Ident, options: &GraphQLClientCodegenOptions) -> TokenStream {
+    let serde_path = options.serde_path().to_token_stream().to_string();
+
+    quote! {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(crate = #serde_path, transparent)]
+        pub struct #name(pub ID);
+
+        impl std::fmt::Display for #name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl std::str::FromStr for #name {
+            type Err = <ID as std::str::FromStr>::Err;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map(#name)
+            }
+        }
+
+        impl From<ID> for #name {
+            fn from(id: ID) -> Self {
+                #name(id)
+            }
+        }
+
+        impl From<#name> for ID {
+            fn from(id: #name) -> Self {
+                id.0
+            }
+        }
+    }
+}
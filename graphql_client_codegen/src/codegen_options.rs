@@ -1,11 +1,13 @@
 use crate::deprecation::DeprecationStrategy;
 use crate::normalization::Normalization;
+use crate::well_known_scalars::ScalarCrate;
 use proc_macro2::Ident;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use syn::{self, Visibility};
 
 /// Which context is this code generation effort taking place.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CodegenMode {
     /// The graphql-client CLI.
     Cli,
@@ -14,6 +16,7 @@ pub enum CodegenMode {
 }
 
 /// Used to configure code generation.
+#[derive(Clone)]
 pub struct GraphQLClientCodegenOptions {
     /// Which context is this code generation effort taking place.
     pub mode: CodegenMode,
@@ -30,7 +33,7 @@ pub struct GraphQLClientCodegenOptions {
     /// The deprecation strategy to adopt.
     deprecation_strategy: Option<DeprecationStrategy>,
     /// Target module visibility.
-    module_visibility: Option<Visibility>,
+    pub(crate) module_visibility: Option<Visibility>,
     /// A path to a file to include in the module to force Cargo to take into account changes in
     /// the query files when recompiling.
     query_file: Option<PathBuf>,
@@ -41,12 +44,37 @@ pub struct GraphQLClientCodegenOptions {
     normalization: Normalization,
     /// Custom scalar definitions module path
     custom_scalars_module: Option<syn::Path>,
+    /// The Rust types of scalars, by GraphQL name. They take precedence over the built-in types
+    /// and `custom_scalars_module`.
+    scalar_types: BTreeMap<String, syn::Type>,
+    /// The crates the well-known scalars, like RFC 3339 date-times, are mapped to.
+    scalar_crates: Vec<ScalarCrate>,
+    /// The module the schema enums, input objects and scalars are generated in, instead of in
+    /// each operation module.
+    pub(crate) shared_types_module: Option<syn::Path>,
     /// List of externally defined enum types. Type names must match those used in the schema exactly.
     extern_enums: Vec<String>,
     /// Flag to trigger generation of Other variant for fragments Enum
     fragments_other_variant: bool,
     /// Skip Serialization of None values.
     skip_serializing_none: bool,
+    /// Leave schema descriptions out of the generated code, instead of rendering them as doc
+    /// comments.
+    skip_descriptions: bool,
+    /// Whether to generate builders for the variables and input objects.
+    builders: bool,
+    /// Whether nullable inputs are `MaybeUndefined` instead of `Option`.
+    maybe_undefined: bool,
+    /// Whether `ID` fields are newtypes named after the type that owns them, like `UserId`.
+    typed_ids: bool,
+    /// The owning types of `ID` variables and input fields, by name.
+    typed_id_inputs: BTreeMap<String, String>,
+    /// The `typed_id_inputs` variables set for the whole crate, which not every query declares.
+    crate_wide_typed_id_variables: BTreeSet<String>,
+    /// Strip the whitespace that is not needed from the `QUERY` sent to the server.
+    minify_query: bool,
+    /// Reject the directives the schema does not declare, instead of passing them through.
+    deny_unknown_directives: bool,
     /// Path to the serde crate.
     serde_path: syn::Path,
     /// list of custom type paths to use for input variables
@@ -71,9 +99,20 @@ impl GraphQLClientCodegenOptions {
             schema_file: Default::default(),
             normalization: Normalization::None,
             custom_scalars_module: Default::default(),
+            scalar_types: Default::default(),
+            scalar_crates: Vec::new(),
+            shared_types_module: Default::default(),
             extern_enums: Default::default(),
             fragments_other_variant: Default::default(),
             skip_serializing_none: Default::default(),
+            skip_descriptions: Default::default(),
+            builders: Default::default(),
+            typed_ids: Default::default(),
+            typed_id_inputs: Default::default(),
+            crate_wide_typed_id_variables: Default::default(),
+            maybe_undefined: Default::default(),
+            minify_query: Default::default(),
+            deny_unknown_directives: Default::default(),
             serde_path: syn::parse_quote!(::serde),
             custom_variable_types: Default::default(),
             custom_response_type: Default::default(),
@@ -126,7 +165,7 @@ impl GraphQLClientCodegenOptions {
 
         base_derives.chain(
             self.additional_response_derives()
-                .filter(|additional| additional != &"Deserialize"),
+                .filter(|additional| derive_name(additional) != "Deserialize"),
         )
     }
 
@@ -227,6 +266,41 @@ impl GraphQLClientCodegenOptions {
         self.custom_scalars_module = Some(module)
     }
 
+    /// Get the Rust types the scalars are mapped to, by GraphQL name.
+    pub fn scalar_types(&self) -> &BTreeMap<String, syn::Type> {
+        &self.scalar_types
+    }
+
+    /// Map a scalar to a Rust type, e.g. `Int` to `i32` or `DateTime` to
+    /// `chrono::DateTime<chrono::Utc>`. `String` cannot be mapped.
+    pub fn set_scalar_type(&mut self, scalar: impl Into<String>, rust_type: syn::Type) {
+        self.scalar_types.insert(scalar.into(), rust_type);
+    }
+
+    /// Get the crates the well-known scalars are mapped to.
+    pub fn scalar_crates(&self) -> &[ScalarCrate] {
+        &self.scalar_crates
+    }
+
+    /// Map the well-known scalars, by `@specifiedBy` URL or by name, to the types of these crates,
+    /// as re-exported by graphql_client. Scalars mapped with `set_scalar_type` are left alone.
+    pub fn set_scalar_crates(&mut self, scalar_crates: Vec<ScalarCrate>) {
+        self.scalar_crates = scalar_crates;
+    }
+
+    /// Get the module the schema types are shared from
+    pub fn shared_types_module(&self) -> Option<&syn::Path> {
+        self.shared_types_module.as_ref()
+    }
+
+    /// Set the module the schema types are shared from. Operation modules re-export the types
+    /// they use from it, instead of defining their own, so the variables and response derives
+    /// only apply to their structs, and the ones of the shared types are set where the shared
+    /// module is generated.
+    pub fn set_shared_types_module(&mut self, module: syn::Path) {
+        self.shared_types_module = Some(module)
+    }
+
     /// Get the externally defined enums type names
     pub fn extern_enums(&self) -> &[String] {
         &self.extern_enums
@@ -257,6 +331,106 @@ impl GraphQLClientCodegenOptions {
         &self.skip_serializing_none
     }
 
+    /// Set whether schema descriptions should be left out of the generated code.
+    pub fn set_skip_descriptions(&mut self, skip_descriptions: bool) {
+        self.skip_descriptions = skip_descriptions
+    }
+
+    /// Get a reference to whether schema descriptions are left out of the generated code.
+    pub fn skip_descriptions(&self) -> &bool {
+        &self.skip_descriptions
+    }
+
+    /// Set whether builders are generated for the `Variables` struct and the input objects.
+    /// Required fields are arguments of `builder()`, and the other fields have setters.
+    pub fn set_builders(&mut self, builders: bool) {
+        self.builders = builders
+    }
+
+    /// Get a reference to whether builders are generated for the variables and input objects.
+    pub fn builders(&self) -> &bool {
+        &self.builders
+    }
+
+    /// Set whether nullable input fields and variables are `graphql_client::MaybeUndefined`
+    /// instead of `Option`, to tell an omitted value apart from an explicit `null`.
+    pub fn set_maybe_undefined(&mut self, maybe_undefined: bool) {
+        self.maybe_undefined = maybe_undefined
+    }
+
+    /// Get a reference to whether nullable inputs are `graphql_client::MaybeUndefined`.
+    pub fn maybe_undefined(&self) -> &bool {
+        &self.maybe_undefined
+    }
+
+    /// Set whether the `ID` fields of objects and interfaces are newtypes named after their type,
+    /// so that the `id` of a `User` is a `UserId`.
+    ///
+    /// The newtypes wrap `ID`, so an `ID` mapped with [`set_scalar_type`](Self::set_scalar_type)
+    /// must implement `Debug`, `Clone`, `Eq`, `Hash`, `Ord`, `Display`, `FromStr`, `Serialize`
+    /// and `Deserialize`.
+    pub fn set_typed_ids(&mut self, typed_ids: bool) {
+        self.typed_ids = typed_ids
+    }
+
+    /// Get a reference to whether `ID` fields are typed after their owning type.
+    pub fn typed_ids(&self) -> &bool {
+        &self.typed_ids
+    }
+
+    /// Get the owning types of `ID` variables, by name, and of input fields, as `Input.field`.
+    pub fn typed_id_inputs(&self) -> &BTreeMap<String, String> {
+        &self.typed_id_inputs
+    }
+
+    /// Type the `ID` variable, or the input field as `Input.field`, as the IDs of `owner`, e.g.
+    /// `userId` or `StarInput.userId` as `UserId` with `User`. Only used with `typed_ids`.
+    pub fn set_typed_id_input(&mut self, name: impl Into<String>, owner: impl Into<String>) {
+        let name = name.into();
+        self.crate_wide_typed_id_variables.remove(&name);
+        self.typed_id_inputs.insert(name, owner.into());
+    }
+
+    /// Like [`set_typed_id_input`](Self::set_typed_id_input), for the defaults of the whole crate:
+    /// a variable the query does not declare is not reported.
+    pub fn set_crate_wide_typed_id_input(
+        &mut self,
+        name: impl Into<String>,
+        owner: impl Into<String>,
+    ) {
+        let name = name.into();
+        if !name.contains('.') {
+            self.crate_wide_typed_id_variables.insert(name.clone());
+        }
+        self.typed_id_inputs.insert(name, owner.into());
+    }
+
+    /// Whether the `typed_id_inputs` variable comes from the defaults of the whole crate.
+    pub(crate) fn is_crate_wide_typed_id_variable(&self, name: &str) -> bool {
+        self.crate_wide_typed_id_variables.contains(name)
+    }
+
+    /// Set whether the unneeded whitespace should be stripped from the generated `QUERY`.
+    pub fn set_minify_query(&mut self, minify_query: bool) {
+        self.minify_query = minify_query
+    }
+
+    /// Get a reference to whether the generated `QUERY` is minified.
+    pub fn minify_query(&self) -> &bool {
+        &self.minify_query
+    }
+
+    /// Set whether directives the schema does not declare are errors. They are passed through by
+    /// default, since client-only directives like `@client` are often left out of schemas.
+    pub fn set_deny_unknown_directives(&mut self, deny_unknown_directives: bool) {
+        self.deny_unknown_directives = deny_unknown_directives
+    }
+
+    /// Get a reference to whether directives the schema does not declare are errors.
+    pub fn deny_unknown_directives(&self) -> &bool {
+        &self.deny_unknown_directives
+    }
+
     /// Set the path to used to resolve serde traits.
     pub fn set_serde_path(&mut self, path: syn::Path) {
         self.serde_path = path;
@@ -267,3 +441,8 @@ impl GraphQLClientCodegenOptions {
         &self.serde_path
     }
 }
+
+/// The name of a derive, without its path: `Default` for `std::default::Default`.
+pub(crate) fn derive_name(derive: &str) -> &str {
+    derive.rsplit("::").next().unwrap_or(derive).trim()
+}
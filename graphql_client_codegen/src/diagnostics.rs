@@ -0,0 +1,188 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+const MAX_SNIPPET_LENGTH: usize = 200;
+
+/// A problem found in a query or schema document, with its location in the source when it is
+/// known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    message: String,
+    path: Option<PathBuf>,
+    location: Option<SourceLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SourceLocation {
+    line: usize,
+    column: usize,
+    source_line: Option<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(message: String) -> Self {
+        Diagnostic {
+            message,
+            path: None,
+            location: None,
+        }
+    }
+
+    pub(crate) fn with_path(mut self, path: Option<&Path>) -> Self {
+        self.path = path.map(Path::to_path_buf);
+        self
+    }
+
+    /// Lines and columns start at 1, like in graphql-parser's `Pos`. When the source is given,
+    /// the offending line is shown under the message, unless it is too long to be useful, as in
+    /// minified JSON.
+    pub(crate) fn with_location(
+        mut self,
+        line: usize,
+        column: usize,
+        source: Option<&str>,
+    ) -> Self {
+        let source_line = source
+            .and_then(|source| source.lines().nth(line.saturating_sub(1)))
+            .map(str::trim_end)
+            .filter(|source_line| source_line.len() <= MAX_SNIPPET_LENGTH)
+            .map(ToOwned::to_owned);
+
+        self.location = Some(SourceLocation {
+            line,
+            column,
+            source_line,
+        });
+        self
+    }
+
+    /// The description of the problem, without its location.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The file the problem was found in, when the document was read from a file.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The line of the problem in the document, starting at 1.
+    pub fn line(&self) -> Option<usize> {
+        self.location.as_ref().map(|location| location.line)
+    }
+
+    /// The column of the problem in the document, starting at 1.
+    pub fn column(&self) -> Option<usize> {
+        self.location.as_ref().map(|location| location.column)
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)?;
+
+        let path = self
+            .path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "<query>".to_owned());
+
+        let location = match &self.location {
+            Some(location) => location,
+            None if self.path.is_some() => return write!(f, "\n  --> {}", path),
+            None => return Ok(()),
+        };
+
+        write!(f, "\n  --> {}:{}:{}", path, location.line, location.column)?;
+
+        if let Some(source_line) = &location.source_line {
+            let gutter = " ".repeat(location.line.to_string().len());
+
+            write!(
+                f,
+                "\n{gutter} |\n{line} | {source_line}\n{gutter} | {marker:>column$}",
+                gutter = gutter,
+                line = location.line,
+                source_line = source_line,
+                marker = "^",
+                column = location.column,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// All the problems found in one pass over a query or schema document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub(crate) fn new(diagnostics: Vec<Diagnostic>) -> Self {
+        Diagnostics(diagnostics)
+    }
+
+    /// Iterate over the diagnostics, in the order they were found.
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, diagnostic) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n\n")?;
+            }
+
+            Display::fmt(diagnostic, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostics {}
+
+impl From<Diagnostic> for Diagnostics {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Diagnostics(vec![diagnostic])
+    }
+}
+
+/// Parser errors from graphql-parser only expose their position in their message, as in
+/// `Parse error at 3:7`.
+pub(crate) fn parse_error_location(message: &str) -> Option<(usize, usize)> {
+    let location = message.split("Parse error at ").nth(1)?;
+    let location = location.split_whitespace().next()?;
+    let (line, column) = location.split_once(':')?;
+
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_display_includes_snippet() {
+        let source = "query Q {\n  hero { nope }\n}\n";
+        let diagnostic = Diagnostic::new("No field named nope on Character".to_owned())
+            .with_path(Some(Path::new("src/query.graphql")))
+            .with_location(2, 10, Some(source));
+
+        assert_eq!(
+            diagnostic.to_string(),
+            "No field named nope on Character\n  --> src/query.graphql:2:10\n  |\n2 |   hero { nope }\n  |          ^"
+        );
+    }
+
+    #[test]
+    fn parse_error_location_works() {
+        let error = graphql_parser::parse_query::<&str>("query Q {\n  hero {\n}")
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(parse_error_location(&error), Some((3, 1)));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     codegen_options::*,
     query::{BoundQuery, OperationId},
-    BoxError,
+    utf8_path, utf8_paths, BoxError,
 };
 use heck::*;
 use proc_macro2::{Ident, Span, TokenStream};
@@ -29,6 +29,8 @@ pub(crate) struct GeneratedModule<'a> {
     pub query_string: &'a str,
     pub resolved_query: &'a crate::query::Query,
     pub schema: &'a crate::schema::Schema,
+    pub schema_files: &'a [std::path::PathBuf],
+    pub imported_files: &'a [std::path::PathBuf],
     pub options: &'a crate::GraphQLClientCodegenOptions,
 }
 
@@ -64,16 +66,28 @@ impl GeneratedModule<'_> {
         let operation_name_ident = Ident::new(&operation_name_ident, Span::call_site());
 
         // Force cargo to refresh the generated code when the query file changes.
-        let query_include = self
-            .options
-            .query_file()
-            .map(|path| {
-                let path = path.to_str();
+        let query_include = match self.options.query_file() {
+            Some(path) => {
+                let path = utf8_path(path)?;
                 quote!(
                     const __QUERY_WORKAROUND: &str = include_str!(#path);
                 )
-            })
-            .unwrap_or_default();
+            }
+            None => TokenStream::new(),
+        };
+
+        // Same for the schema files and the files fragments are imported from, in derive mode.
+        let schema_include = match self.options.mode {
+            CodegenMode::Derive => {
+                let paths = utf8_paths(self.schema_files)?;
+                let imported_paths = utf8_paths(self.imported_files)?;
+                quote!(
+                    const __SCHEMA_WORKAROUND: &[&str] = &[#(include_str!(#paths)),*];
+                    const __IMPORTS_WORKAROUND: &[&str] = &[#(include_str!(#imported_paths)),*];
+                )
+            }
+            CodegenMode::Cli => TokenStream::new(),
+        };
 
         let query_string = &self.query_string;
         let impls = self.build_impls()?;
@@ -96,6 +110,7 @@ impl GeneratedModule<'_> {
                 pub const QUERY: &str = #query_string;
 
                 #query_include
+                #schema_include
 
                 #impls
             }
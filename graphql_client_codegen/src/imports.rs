@@ -0,0 +1,198 @@
+//! `#import "./fragments.graphql"` comments, which bring the fragments defined in other query
+//! documents into scope.
+
+use crate::operation_source::{collect_spreads, operation_selection_set};
+use crate::{query_document, read_file, BoxError, GeneralError, QueryDocument};
+use graphql_parser::query::{Definition, FragmentDefinition, OperationDefinition};
+use graphql_parser::Pos;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+type Fragment = FragmentDefinition<'static, String>;
+
+/// The imported fragments used by a query document.
+pub(crate) struct Imports {
+    /// Every file reached through `#import` comments, directly or not.
+    pub(crate) files: Vec<PathBuf>,
+    /// The imported fragments the document uses, directly or through other fragments, to be
+    /// appended to the document.
+    pub(crate) fragments: Vec<ImportedFragment>,
+}
+
+/// A fragment definition, as written in the file it is imported from.
+#[derive(Clone)]
+pub(crate) struct ImportedFragment {
+    pub(crate) path: PathBuf,
+    /// The contents of the whole file, for the snippets of diagnostics.
+    pub(crate) file_contents: String,
+    /// The text of the definition, indented like in the file, so that columns match.
+    pub(crate) source: String,
+    /// The line of the file the definition starts on.
+    pub(crate) line: usize,
+}
+
+/// Resolves the `#import` comments of a query document. Paths are relative to the importing
+/// file, or to the current directory when the document is not read from a file.
+pub(crate) fn resolve_imports(
+    query_string: &str,
+    document: &QueryDocument,
+    query_path: Option<&Path>,
+) -> Result<Imports, BoxError> {
+    let base_dir = query_path
+        .and_then(Path::parent)
+        .unwrap_or_else(|| Path::new("."));
+
+    let mut pending: Vec<PathBuf> = import_paths(query_string)
+        .map(|path| base_dir.join(path))
+        .collect();
+    let mut files = Vec::new();
+    let mut visited = BTreeSet::new();
+    let mut imported: BTreeMap<String, (Fragment, ImportedFragment)> = BTreeMap::new();
+
+    while let Some(path) = pending.pop() {
+        if !visited.insert(path.canonicalize().unwrap_or_else(|_| path.clone())) {
+            continue;
+        }
+
+        let source = read_file(&path)?;
+        let imported_document = query_document(&source, Some(&path))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        pending.extend(import_paths(&source).map(|import| dir.join(import)));
+
+        let mut starts: Vec<Pos> = imported_document
+            .definitions
+            .iter()
+            .map(definition_position)
+            .collect();
+        starts.sort();
+
+        for definition in imported_document.definitions {
+            if let Definition::Fragment(fragment) = definition {
+                if let Some((_, other)) = imported.get(&fragment.name) {
+                    return Err(GeneralError(format!(
+                        "The fragment `{}` is imported from both {} and {}.",
+                        fragment.name,
+                        other.path.display(),
+                        path.display(),
+                    ))
+                    .into());
+                }
+
+                // The definition runs until the next one starts.
+                let start = byte_offset(&source, fragment.position);
+                let end = starts
+                    .iter()
+                    .find(|start| **start > fragment.position)
+                    .map_or(source.len(), |end| byte_offset(&source, *end));
+                let imported_fragment = ImportedFragment {
+                    path: path.clone(),
+                    source: format!(
+                        "{}{}",
+                        " ".repeat(fragment.position.column - 1),
+                        source[start..end].trim_end()
+                    ),
+                    file_contents: source.clone(),
+                    line: fragment.position.line,
+                };
+
+                imported.insert(fragment.name.clone(), (fragment, imported_fragment));
+            }
+        }
+
+        files.push(path);
+    }
+
+    let mut used = BTreeSet::new();
+    let mut to_visit = Vec::new();
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation(operation) => {
+                collect_spreads(operation_selection_set(operation), &mut to_visit)
+            }
+            Definition::Fragment(fragment) => {
+                if let Some((_, imported_fragment)) = imported.get(&fragment.name) {
+                    return Err(GeneralError(format!(
+                        "The fragment `{}` is defined in the query document and imported from {}.",
+                        fragment.name,
+                        imported_fragment.path.display(),
+                    ))
+                    .into());
+                }
+
+                collect_spreads(&fragment.selection_set, &mut to_visit)
+            }
+        }
+    }
+
+    while let Some(name) = to_visit.pop() {
+        if let Some((fragment, _)) = imported.get(name.as_str()) {
+            if used.insert(name) {
+                collect_spreads(&fragment.selection_set, &mut to_visit);
+            }
+        }
+    }
+
+    let fragments = imported
+        .into_values()
+        .filter(|(fragment, _)| used.contains(&fragment.name))
+        .map(|(_, imported_fragment)| imported_fragment)
+        .collect();
+
+    Ok(Imports { files, fragments })
+}
+
+fn definition_position(definition: &Definition<'_, String>) -> Pos {
+    match definition {
+        Definition::Fragment(fragment) => fragment.position,
+        Definition::Operation(OperationDefinition::Query(query)) => query.position,
+        Definition::Operation(OperationDefinition::Mutation(mutation)) => mutation.position,
+        Definition::Operation(OperationDefinition::Subscription(subscription)) => {
+            subscription.position
+        }
+        Definition::Operation(OperationDefinition::SelectionSet(selection_set)) => {
+            selection_set.span.0
+        }
+    }
+}
+
+/// The offset in `source` of a parser position, whose columns count characters.
+fn byte_offset(source: &str, position: Pos) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(position.line - 1)
+        .map(str::len)
+        .sum();
+
+    source[line_start..]
+        .char_indices()
+        .nth(position.column - 1)
+        .map_or(source.len(), |(offset, _)| line_start + offset)
+}
+
+/// The paths of the `#import "path"` comments in a document.
+fn import_paths(source: &str) -> impl Iterator<Item = &str> {
+    source.lines().filter_map(|line| {
+        let path = line.trim().strip_prefix("#import")?.trim();
+
+        path.strip_prefix('"')
+            .and_then(|path| path.strip_suffix('"'))
+            .or_else(|| path.strip_prefix('\'')?.strip_suffix('\''))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_paths_are_read_from_comments() {
+        let source = "#import \"./user.graphql\"\n  #import './post.graphql'\n# import \"x\"\nquery Q { a }\n";
+
+        assert_eq!(
+            import_paths(source).collect::<Vec<_>>(),
+            ["./user.graphql", "./post.graphql"]
+        );
+    }
+}
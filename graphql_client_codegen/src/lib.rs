@@ -8,24 +8,37 @@ use proc_macro2::TokenStream;
 use quote::*;
 use schema::Schema;
 
+mod builder;
 mod codegen;
 mod codegen_options;
 /// Deprecation-related code
 pub mod deprecation;
+/// Fake data generation for mock servers.
+pub mod mock;
 /// Contains the [Schema] type and its implementation.
 pub mod schema;
 
 mod constants;
+mod diagnostics;
 mod generated_module;
+mod imports;
+/// Crate-wide defaults from `Cargo.toml` metadata.
+pub mod manifest;
 /// Normalization-related code
 pub mod normalization;
+mod operation_source;
 mod query;
+mod schema_source;
 mod type_qualifiers;
+/// Well-known scalars and the crates they map to.
+pub mod well_known_scalars;
 
 #[cfg(test)]
 mod tests;
 
+pub use crate::builder::Builder;
 pub use crate::codegen_options::{CodegenMode, GraphQLClientCodegenOptions};
+pub use crate::diagnostics::{Diagnostic, Diagnostics};
 
 use std::{collections::BTreeMap, fmt::Display, io};
 
@@ -41,67 +54,272 @@ impl Display for GeneralError {
 impl std::error::Error for GeneralError {}
 
 type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
-type CacheMap<T> = std::sync::Mutex<BTreeMap<std::path::PathBuf, T>>;
+type CacheMap<K, T> = std::sync::Mutex<BTreeMap<K, T>>;
 type QueryDocument = graphql_parser::query::Document<'static, String>;
 
-static SCHEMA_CACHE: CacheMap<Schema> = std::sync::Mutex::new(BTreeMap::new());
-static QUERY_CACHE: CacheMap<(String, QueryDocument)> = std::sync::Mutex::new(BTreeMap::new());
-
-fn get_set_cached<T: Clone>(
-    cache: &CacheMap<T>,
-    key: &std::path::Path,
-    value_func: impl FnOnce() -> T,
-) -> T {
+static SCHEMA_CACHE: CacheMap<Vec<std::path::PathBuf>, Schema> =
+    std::sync::Mutex::new(BTreeMap::new());
+static QUERY_CACHE: CacheMap<std::path::PathBuf, QuerySource> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+fn get_set_cached<K, T>(
+    cache: &CacheMap<K::Owned, T>,
+    key: &K,
+    value_func: impl FnOnce() -> Result<T, BoxError>,
+) -> Result<T, BoxError>
+where
+    K: Ord + ToOwned + ?Sized,
+    K::Owned: Ord,
+    T: Clone,
+{
     let mut lock = cache.lock().expect("cache is poisoned");
-    lock.entry(key.into()).or_insert_with(value_func).clone()
+
+    if let Some(value) = lock.get(key) {
+        return Ok(value.clone());
+    }
+
+    let value = value_func()?;
+    lock.insert(key.to_owned(), value.clone());
+    Ok(value)
 }
 
-fn query_document(query_string: &str) -> Result<QueryDocument, BoxError> {
+fn query_document(
+    query_string: &str,
+    query_path: Option<&std::path::Path>,
+) -> Result<QueryDocument, BoxError> {
     let document = graphql_parser::parse_query(query_string)
-        .map_err(|err| GeneralError(format!("Query parser error: {}", err)))?
+        .map_err(|err| {
+            parse_error_diagnostic(
+                format!("Query parser error: {}", err),
+                query_string,
+                query_path,
+            )
+        })?
         .into_static();
     Ok(document)
 }
 
-fn get_set_query_from_file(query_path: &std::path::Path) -> (String, QueryDocument) {
-    get_set_cached(&QUERY_CACHE, query_path, || {
-        let query_string = read_file(query_path).unwrap();
-        let query_document = query_document(&query_string).unwrap();
-        (query_string, query_document)
-    })
+fn parse_error_diagnostic(
+    message: String,
+    source: &str,
+    path: Option<&std::path::Path>,
+) -> Diagnostics {
+    let location = diagnostics::parse_error_location(&message);
+    let diagnostic = Diagnostic::new(message).with_path(path);
+
+    match location {
+        Some((line, column)) => diagnostic.with_location(line, column, Some(source)),
+        None => diagnostic,
+    }
+    .into()
 }
 
-fn get_set_schema_from_file(schema_path: &std::path::Path) -> Schema {
-    get_set_cached(&SCHEMA_CACHE, schema_path, || {
-        let schema_extension = schema_path
-            .extension()
-            .map(|ext| ext.to_str().expect("Path must be valid UTF-8"))
-            .unwrap_or("<no extension>");
-        let schema_string = read_file(schema_path).unwrap();
-        match schema_extension {
-            "graphql" | "graphqls"| "gql" => {
-                let s = graphql_parser::schema::parse_schema::<&str>(&schema_string).map_err(|parser_error| GeneralError(format!("Parser error: {}", parser_error))).unwrap();
-                Schema::from(s)
+/// A query document, with the fragments it imports appended.
+#[derive(Clone)]
+struct QuerySource {
+    string: String,
+    document: QueryDocument,
+    /// The files reached through `#import` comments.
+    imported_files: Vec<std::path::PathBuf>,
+    /// The appended fragments, with the line of `string` each starts on.
+    imported_fragments: Vec<(usize, imports::ImportedFragment)>,
+}
+
+impl QuerySource {
+    fn new(
+        query_string: String,
+        query_path: Option<&std::path::Path>,
+    ) -> Result<QuerySource, BoxError> {
+        let document = query_document(&query_string, query_path)?;
+        let imports = imports::resolve_imports(&query_string, &document, query_path)?;
+
+        if imports.fragments.is_empty() {
+            return Ok(QuerySource {
+                string: query_string,
+                document,
+                imported_files: imports.files,
+                imported_fragments: Vec::new(),
+            });
+        }
+
+        let mut string = query_string;
+        let mut imported_fragments = Vec::with_capacity(imports.fragments.len());
+
+        for fragment in imports.fragments {
+            if !string.ends_with('\n') {
+                string.push('\n');
             }
-            "json" => {
-                let parsed: graphql_introspection_query::introspection_response::IntrospectionResponse = serde_json::from_str(&schema_string).unwrap();
-                Schema::from(parsed)
+            let first_line = string.matches('\n').count() + 1;
+            string.push_str(&fragment.source);
+            imported_fragments.push((first_line, fragment));
+        }
+
+        let document = query_document(&string, query_path)?;
+
+        Ok(QuerySource {
+            string,
+            document,
+            imported_files: imports.files,
+            imported_fragments,
+        })
+    }
+
+    /// A diagnostic for a validation error, pointing at the file a fragment was imported from
+    /// when the error is in one.
+    fn diagnostic(
+        &self,
+        error: query::QueryValidationError,
+        query_path: Option<&std::path::Path>,
+    ) -> Diagnostic {
+        let imported_fragment = error.position().and_then(|position| {
+            self.imported_fragments
+                .iter()
+                .rev()
+                .find(|(first_line, _)| *first_line <= position.line)
+                .map(|(first_line, fragment)| (position, first_line, fragment))
+        });
+
+        match imported_fragment {
+            Some((mut position, first_line, fragment)) => {
+                position.line = position.line - first_line + fragment.line;
+                error
+                    .with_position(position)
+                    .into_diagnostic(&fragment.file_contents, Some(&fragment.path))
             }
-            extension => panic!("Unsupported extension for the GraphQL schema: {} (only .json, .graphql, .graphqls and .gql are supported)", extension)
+            None => error.into_diagnostic(&self.string, query_path),
         }
+    }
+}
+
+fn get_set_query_from_file(query_path: &std::path::Path) -> Result<QuerySource, BoxError> {
+    get_set_cached(&QUERY_CACHE, query_path, || {
+        QuerySource::new(read_file(query_path)?, Some(query_path))
     })
 }
 
+/// Resolves the schema paths to the files they stand for, and reads the schema from them.
+fn get_set_schema_from_paths(
+    schema_paths: &[std::path::PathBuf],
+) -> Result<(Vec<std::path::PathBuf>, Schema), BoxError> {
+    let schema_files = schema_source::resolve_schema_paths(schema_paths)?;
+    let schema = get_set_cached(&SCHEMA_CACHE, schema_files.as_slice(), || {
+        read_schema(&schema_files)
+    })?;
+
+    Ok((schema_files, schema))
+}
+
+/// The path as a string, for the `include_str!` calls that make cargo track the files read by the
+/// derive.
+pub(crate) fn utf8_path(path: &std::path::Path) -> Result<&str, GeneralError> {
+    path.to_str()
+        .ok_or_else(|| GeneralError(format!("Path {} is not valid UTF-8", path.display())))
+}
+
+pub(crate) fn utf8_paths(paths: &[std::path::PathBuf]) -> Result<Vec<&str>, GeneralError> {
+    paths.iter().map(|path| utf8_path(path)).collect()
+}
+
+fn schema_extension(schema_path: &std::path::Path) -> Result<&str, GeneralError> {
+    match schema_path.extension() {
+        Some(extension) => extension.to_str().ok_or_else(|| {
+            GeneralError(format!(
+                "Schema path {} is not valid UTF-8",
+                schema_path.display()
+            ))
+        }),
+        None => Ok("<no extension>"),
+    }
+}
+
+fn read_schema(schema_files: &[std::path::PathBuf]) -> Result<Schema, BoxError> {
+    if let [schema_path] = schema_files {
+        if schema_extension(schema_path)? == "json" {
+            let schema_string = read_file(schema_path)?;
+            let parsed: graphql_introspection_query::introspection_response::IntrospectionResponse =
+                serde_json::from_str(&schema_string).map_err(|err| {
+                    Diagnostics::from(
+                        Diagnostic::new(format!("Invalid introspection response: {}", err))
+                            .with_path(Some(schema_path))
+                            .with_location(err.line(), err.column(), Some(&schema_string)),
+                    )
+                })?;
+            return Ok(Schema::from(parsed));
+        }
+    }
+
+    for schema_path in schema_files {
+        match schema_extension(schema_path)? {
+            "graphql" | "graphqls" | "gql" => (),
+            "json" => return Err(GeneralError(format!("Introspection schemas cannot be combined with other schema files: {}", schema_path.display())).into()),
+            extension => return Err(GeneralError(format!("Unsupported extension for the GraphQL schema: {} (only .json, .graphql, .graphqls and .gql are supported)", extension)).into()),
+        }
+    }
+
+    let source = schema_source::SchemaSource::read(schema_files)?;
+    let document =
+        graphql_parser::schema::parse_schema::<&str>(source.text()).map_err(|parser_error| {
+            Diagnostics::from(
+                source.parse_error_diagnostic(format!("Parser error: {}", parser_error)),
+            )
+        })?;
+
+    let mut schema_errors = schema::undefined_type_references(&document);
+    schema_errors.extend(schema::type_extension_errors(&document));
+
+    if !schema_errors.is_empty() {
+        let diagnostics = schema_errors
+            .into_iter()
+            .map(|(position, message)| source.diagnostic(message, position.line, position.column))
+            .collect();
+
+        return Err(Diagnostics::new(diagnostics).into());
+    }
+
+    Ok(Schema::from(document))
+}
+
+/// Formats generated code as a Rust source file. This does not depend on rustfmt, so the output
+/// is the same on every machine.
+pub fn format_generated_code(tokens: TokenStream) -> Result<String, BoxError> {
+    let file: syn::File = syn::parse2(tokens).map_err(|err| {
+        GeneralError(format!(
+            "The generated code could not be parsed for formatting: {}",
+            err
+        ))
+    })?;
+
+    Ok(prettyplease::unparse(&file))
+}
+
 /// Generates Rust code given a path to a query file, a path to a schema file, and options.
+///
+/// The schema path can also be a directory or a glob pattern matching the files the schema is
+/// split across. In derive mode, only the files found are tracked for rebuilds: files added later
+/// are not picked up until the crate is rebuilt for another reason.
 pub fn generate_module_token_stream(
     query_path: std::path::PathBuf,
     schema_path: &std::path::Path,
     options: GraphQLClientCodegenOptions,
 ) -> Result<TokenStream, BoxError> {
-    let query = get_set_query_from_file(query_path.as_path());
-    let schema = get_set_schema_from_file(schema_path);
+    generate_module_token_stream_with_schema_paths(
+        query_path,
+        &[schema_path.to_path_buf()],
+        options,
+    )
+}
+
+/// Generates Rust code given a path to a query file, the paths of the files, directories or glob
+/// patterns making up the schema, and options.
+pub fn generate_module_token_stream_with_schema_paths(
+    query_path: std::path::PathBuf,
+    schema_paths: &[std::path::PathBuf],
+    options: GraphQLClientCodegenOptions,
+) -> Result<TokenStream, BoxError> {
+    let query = get_set_query_from_file(query_path.as_path())?;
+    let (schema_files, schema) = get_set_schema_from_paths(schema_paths)?;
 
-    generate_module_token_stream_inner(&query, &schema, options)
+    generate_module_token_stream_inner(&query, Some(&query_path), &schema, &schema_files, options)
 }
 
 /// Generates Rust code given a query string, a path to a schema file, and options.
@@ -110,22 +328,101 @@ pub fn generate_module_token_stream_from_string(
     schema_path: &std::path::Path,
     options: GraphQLClientCodegenOptions,
 ) -> Result<TokenStream, BoxError> {
-    let query = (query_string.to_string(), query_document(query_string)?);
-    let schema = get_set_schema_from_file(schema_path);
+    generate_module_token_stream_from_string_with_schema_paths(
+        query_string,
+        &[schema_path.to_path_buf()],
+        options,
+    )
+}
+
+/// Generates Rust code given a query string, the paths of the files, directories or glob
+/// patterns making up the schema, and options.
+pub fn generate_module_token_stream_from_string_with_schema_paths(
+    query_string: &str,
+    schema_paths: &[std::path::PathBuf],
+    options: GraphQLClientCodegenOptions,
+) -> Result<TokenStream, BoxError> {
+    let query_path = options.query_file().map(std::path::Path::to_path_buf);
+    let query = QuerySource::new(query_string.to_owned(), query_path.as_deref())?;
+    let (schema_files, schema) = get_set_schema_from_paths(schema_paths)?;
+
+    generate_module_token_stream_inner(
+        &query,
+        query_path.as_deref(),
+        &schema,
+        &schema_files,
+        options,
+    )
+}
+
+/// Generates the enums, input objects and custom scalars of a whole schema, given the paths of
+/// the files, directories or glob patterns making up the schema, and options.
+///
+/// The items are meant to fill the module that operations point to with `shared_types_module`.
+pub fn generate_schema_types_token_stream(
+    schema_paths: &[std::path::PathBuf],
+    options: GraphQLClientCodegenOptions,
+) -> Result<TokenStream, BoxError> {
+    let (schema_files, schema) = get_set_schema_from_paths(schema_paths)?;
+
+    let typed_id_errors = codegen::typed_ids::validate_typed_id_inputs(&options, &schema, None);
+    if !typed_id_errors.is_empty() {
+        return Err(Diagnostics::new(typed_id_errors).into());
+    }
 
-    generate_module_token_stream_inner(&query, &schema, options)
+    let types = codegen::schema_types(&options, &schema)?;
+
+    // Force cargo to refresh the generated code when the schema changes, in derive mode.
+    let schema_include = match options.mode {
+        CodegenMode::Derive => {
+            let paths = utf8_paths(&schema_files)?;
+            quote!(const __SCHEMA_WORKAROUND: &[&str] = &[#(include_str!(#paths)),*];)
+        }
+        CodegenMode::Cli => TokenStream::new(),
+    };
+
+    Ok(quote! {
+        #schema_include
+
+        #types
+    })
 }
 
 /// Generates Rust code given a query string and query document, a schema, and options.
 fn generate_module_token_stream_inner(
-    query: &(String, QueryDocument),
+    query: &QuerySource,
+    query_path: Option<&std::path::Path>,
     schema: &Schema,
+    schema_files: &[std::path::PathBuf],
     options: GraphQLClientCodegenOptions,
 ) -> Result<TokenStream, BoxError> {
-    let (query_string, query_document) = query;
+    let query_source = query;
+    let QuerySource {
+        document: query_document,
+        imported_files,
+        ..
+    } = query;
 
     // We need to qualify the query with the path to the crate it is part of
-    let query = crate::query::resolve(schema, query_document)?;
+    let query = crate::query::resolve(schema, query_document, *options.deny_unknown_directives())
+        .map_err(|errors| {
+        let diagnostics = errors
+            .into_iter()
+            .map(|error| query_source.diagnostic(error, query_path))
+            .collect();
+
+        Diagnostics::new(diagnostics)
+    })?;
+
+    let typed_id_errors =
+        codegen::typed_ids::validate_typed_id_inputs(&options, schema, Some(&query.variables));
+    if !typed_id_errors.is_empty() {
+        let diagnostics = typed_id_errors
+            .into_iter()
+            .map(|diagnostic| diagnostic.with_path(query_path))
+            .collect();
+        return Err(Diagnostics::new(diagnostics).into());
+    }
 
     // Determine which operation we are generating code for. This will be used in operationName.
     let operations = options
@@ -150,9 +447,18 @@ fn generate_module_token_stream_inner(
     let mut modules = Vec::with_capacity(operations.len());
 
     for operation in &operations {
+        // Each module only sends its own operation, and the fragments it uses.
+        let operation_query_string = operation_source::operation_source(
+            query_document,
+            &operation.1.name,
+            *options.minify_query(),
+        );
+
         let generated = generated_module::GeneratedModule {
-            query_string: query_string.as_str(),
+            query_string: operation_query_string.as_str(),
             schema,
+            schema_files,
+            imported_files,
             resolved_query: &query,
             operation: &operation.1.name,
             options: &options,
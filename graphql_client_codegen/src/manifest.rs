@@ -0,0 +1,301 @@
+//! Crate-wide codegen defaults, read from the `[package.metadata.graphql-client]` table of a
+//! crate's `Cargo.toml`.
+
+use crate::{
+    deprecation::DeprecationStrategy, normalization::Normalization, read_file,
+    well_known_scalars::parse_scalar_crates, BoxError, GeneralError, GraphQLClientCodegenOptions,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The defaults set in `[package.metadata.graphql-client]`. They use the names of the
+/// `#[graphql(...)]` attribute options, and are overridden by the attributes of each struct, or
+/// by the flags of the CLI.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestDefaults {
+    /// The directory of the `Cargo.toml`, which schema paths are relative to.
+    #[serde(skip)]
+    manifest_dir: PathBuf,
+    schema_path: Option<SchemaPaths>,
+    variables_derives: Option<String>,
+    response_derives: Option<String>,
+    custom_scalars_module: Option<String>,
+    scalars: Option<BTreeMap<String, String>>,
+    scalar_crates: Option<String>,
+    shared_types_module: Option<String>,
+    extern_enums: Option<Vec<String>>,
+    deprecated: Option<String>,
+    normalization: Option<String>,
+    fragments_other_variant: Option<bool>,
+    skip_serializing_none: Option<bool>,
+    skip_descriptions: Option<bool>,
+    builders: Option<bool>,
+    maybe_undefined: Option<bool>,
+    typed_ids: Option<bool>,
+    typed_id_inputs: Option<BTreeMap<String, String>>,
+    minify_query: Option<bool>,
+    deny_unknown_directives: Option<bool>,
+}
+
+/// Either `schema_path = "..."` or `schema_path = ["...", "..."]`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SchemaPaths {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    metadata: Option<Metadata>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    #[serde(rename = "graphql-client")]
+    graphql_client: Option<ManifestDefaults>,
+}
+
+impl ManifestDefaults {
+    /// Reads the defaults from the `Cargo.toml` at `manifest_path`. A manifest without a
+    /// `[package.metadata.graphql-client]` table has no defaults.
+    pub fn read(manifest_path: &Path) -> Result<ManifestDefaults, BoxError> {
+        let manifest: Manifest = toml::from_str(&read_file(manifest_path)?).map_err(|err| {
+            GeneralError(format!(
+                "Invalid [package.metadata.graphql-client] in {}: {}",
+                manifest_path.display(),
+                err
+            ))
+        })?;
+
+        let mut defaults = manifest
+            .package
+            .and_then(|package| package.metadata)
+            .and_then(|metadata| metadata.graphql_client)
+            .unwrap_or_default();
+        defaults.manifest_dir = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Ok(defaults)
+    }
+
+    /// The default schema paths, relative to the directory of the `Cargo.toml`.
+    pub fn schema_paths(&self) -> Vec<PathBuf> {
+        let paths = match &self.schema_path {
+            Some(SchemaPaths::One(path)) => std::slice::from_ref(path),
+            Some(SchemaPaths::Many(paths)) => paths.as_slice(),
+            None => &[],
+        };
+
+        paths
+            .iter()
+            .map(|path| self.manifest_dir.join(path))
+            .collect()
+    }
+
+    /// Sets the defaults on the codegen options. Options set afterwards override them.
+    pub fn apply(&self, options: &mut GraphQLClientCodegenOptions) -> Result<(), BoxError> {
+        if let Some(variables_derives) = &self.variables_derives {
+            options.set_variables_derives(variables_derives.clone());
+        }
+
+        if let Some(response_derives) = &self.response_derives {
+            options.set_response_derives(response_derives.clone());
+        }
+
+        if let Some(custom_scalars_module) = &self.custom_scalars_module {
+            let custom_scalars_module = syn::parse_str(custom_scalars_module)
+                .map_err(|_| self.invalid("custom_scalars_module", "must be a path to a module"))?;
+            options.set_custom_scalars_module(custom_scalars_module);
+        }
+
+        for (scalar, rust_type) in self.scalars.iter().flatten() {
+            let rust_type = syn::parse_str(rust_type)
+                .map_err(|_| self.invalid("scalars", "must map scalars to Rust types"))?;
+            options.set_scalar_type(scalar.clone(), rust_type);
+        }
+
+        if let Some(scalar_crates) = &self.scalar_crates {
+            let scalar_crates = parse_scalar_crates(scalar_crates).map_err(|err| {
+                self.invalid(
+                    "scalar_crates",
+                    &format!("must list crates of well-known scalars: {}", err),
+                )
+            })?;
+            options.set_scalar_crates(scalar_crates);
+        }
+
+        if let Some(shared_types_module) = &self.shared_types_module {
+            let shared_types_module = syn::parse_str(shared_types_module)
+                .map_err(|_| self.invalid("shared_types_module", "must be a path to a module"))?;
+            options.set_shared_types_module(shared_types_module);
+        }
+
+        if let Some(extern_enums) = &self.extern_enums {
+            options.set_extern_enums(extern_enums.clone());
+        }
+
+        if let Some(deprecated) = &self.deprecated {
+            let strategy: DeprecationStrategy =
+                deprecated.to_lowercase().parse().map_err(|_| {
+                    self.invalid("deprecated", "must be one of 'allow', 'deny', or 'warn'")
+                })?;
+            options.set_deprecation_strategy(strategy);
+        }
+
+        if let Some(normalization) = &self.normalization {
+            let normalization: Normalization = normalization
+                .to_lowercase()
+                .parse()
+                .map_err(|_| self.invalid("normalization", "must be one of 'none' or 'rust'"))?;
+            options.set_normalization(normalization);
+        }
+
+        if let Some(fragments_other_variant) = self.fragments_other_variant {
+            options.set_fragments_other_variant(fragments_other_variant);
+        }
+
+        if let Some(skip_serializing_none) = self.skip_serializing_none {
+            options.set_skip_serializing_none(skip_serializing_none);
+        }
+
+        if let Some(skip_descriptions) = self.skip_descriptions {
+            options.set_skip_descriptions(skip_descriptions);
+        }
+
+        if let Some(builders) = self.builders {
+            options.set_builders(builders);
+        }
+
+        if let Some(maybe_undefined) = self.maybe_undefined {
+            options.set_maybe_undefined(maybe_undefined);
+        }
+
+        if let Some(typed_ids) = self.typed_ids {
+            options.set_typed_ids(typed_ids);
+        }
+
+        for (name, owner) in self.typed_id_inputs.iter().flatten() {
+            options.set_crate_wide_typed_id_input(name.clone(), owner.clone());
+        }
+
+        if let Some(minify_query) = self.minify_query {
+            options.set_minify_query(minify_query);
+        }
+
+        if let Some(deny_unknown_directives) = self.deny_unknown_directives {
+            options.set_deny_unknown_directives(deny_unknown_directives);
+        }
+
+        Ok(())
+    }
+
+    fn invalid(&self, key: &str, expected: &str) -> GeneralError {
+        GeneralError(format!(
+            "Invalid `{}` in [package.metadata.graphql-client] of {}: it {}.",
+            key,
+            self.manifest_dir.join("Cargo.toml").display(),
+            expected
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::well_known_scalars::ScalarCrate;
+
+    #[test]
+    fn defaults_are_read_from_the_package_metadata() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [package]
+            name = "app"
+
+            [package.metadata.graphql-client]
+            schema_path = ["schema/core.graphqls", "schema/billing.graphqls"]
+            response_derives = "Debug, PartialEq"
+            normalization = "rust"
+            skip_serializing_none = true
+            scalars = { Int = "i32", ID = "crate::Id" }
+            scalar_crates = "chrono, uuid"
+            typed_ids = true
+            deny_unknown_directives = true
+            typed_id_inputs = { userId = "User", "StarInput.repositoryId" = "Repository" }
+            "#,
+        )
+        .unwrap();
+        let mut defaults = manifest
+            .package
+            .unwrap()
+            .metadata
+            .unwrap()
+            .graphql_client
+            .unwrap();
+        defaults.manifest_dir = PathBuf::from("app");
+
+        assert_eq!(
+            defaults.schema_paths(),
+            [
+                Path::new("app/schema/core.graphqls"),
+                Path::new("app/schema/billing.graphqls")
+            ]
+        );
+
+        let mut options = GraphQLClientCodegenOptions::new(crate::CodegenMode::Derive);
+        defaults.apply(&mut options).unwrap();
+
+        assert_eq!(
+            options.additional_response_derives().collect::<Vec<_>>(),
+            ["Debug", "PartialEq"]
+        );
+        assert_eq!(*options.normalization(), Normalization::Rust);
+        assert!(*options.skip_serializing_none());
+
+        let id_type = options.scalar_types().get("ID").unwrap();
+        assert_eq!(quote::quote!(#id_type).to_string(), "crate :: Id");
+        assert_eq!(
+            options.scalar_crates(),
+            [ScalarCrate::Chrono, ScalarCrate::Uuid]
+        );
+
+        assert!(*options.typed_ids());
+        assert!(*options.deny_unknown_directives());
+        assert_eq!(
+            options.typed_id_inputs().get("userId").map(String::as_str),
+            Some("User")
+        );
+        assert_eq!(
+            options
+                .typed_id_inputs()
+                .get("StarInput.repositoryId")
+                .map(String::as_str),
+            Some("Repository")
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let result = toml::from_str::<Manifest>(
+            r#"
+            [package.metadata.graphql-client]
+            respone_derives = "Debug"
+            "#,
+        );
+
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("unknown field `respone_derives`"));
+    }
+}
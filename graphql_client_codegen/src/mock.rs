@@ -0,0 +1,500 @@
+//! Execution of GraphQL operations against a schema, returning deterministic
+//! fake data. This is what powers the `graphql-client mock-server` command.
+//!
+//! Leaf values are derived from the position of the field in the response, so
+//! the same operation always gets the same response. They can be overridden
+//! per type (`"DateTime"`) or per field (`"User.login"`). For interfaces and
+//! unions, an override with the name of an object type selects the concrete
+//! type that gets returned.
+
+use crate::{
+    constants::TYPENAME_FIELD,
+    schema::{ObjectId, ObjectLike, Schema, TypeId},
+    type_qualifiers::GraphqlTypeQualifier,
+    BoxError, QueryDocument,
+};
+use graphql_parser::query::{
+    Definition, Directive, Field, OperationDefinition, Selection, SelectionSet, TypeCondition,
+    Value,
+};
+use serde_json::{json, Map, Value as JsonValue};
+use std::path::Path;
+
+/// The number of items returned for list fields, when not configured.
+const DEFAULT_LIST_LENGTH: usize = 2;
+
+/// A schema ready to execute operations with fake data.
+pub struct MockSchema {
+    schema: Schema,
+    overrides: Map<String, JsonValue>,
+    list_length: usize,
+}
+
+impl MockSchema {
+    /// Load the schema at the given path (.json or .graphql).
+    pub fn from_path(schema_path: &Path) -> Result<MockSchema, BoxError> {
+        Ok(MockSchema {
+            schema: crate::get_set_schema_from_paths(&[schema_path.to_path_buf()])?.1,
+            overrides: Map::new(),
+            list_length: DEFAULT_LIST_LENGTH,
+        })
+    }
+
+    /// Set the values to use instead of the generated ones. Keys are either type names
+    /// (`"DateTime"`) or field coordinates (`"User.login"`).
+    ///
+    /// This is the Rust counterpart of the JSON config file of the `mock-server` command.
+    pub fn set_overrides(&mut self, overrides: Map<String, JsonValue>) {
+        self.overrides = overrides;
+    }
+
+    /// Set the number of items returned for list fields.
+    pub fn set_list_length(&mut self, list_length: usize) {
+        self.list_length = list_length;
+    }
+
+    /// Execute an operation, returning a complete GraphQL response body (with either `data` or
+    /// `errors`).
+    pub fn execute(
+        &self,
+        query: &str,
+        operation_name: Option<&str>,
+        variables: &Map<String, JsonValue>,
+    ) -> JsonValue {
+        let result = crate::query_document(query, None).and_then(|document| {
+            Execution {
+                mock: self,
+                document: &document,
+                variables,
+            }
+            .run(operation_name)
+        });
+
+        match result {
+            Ok(data) => json!({ "data": data }),
+            Err(err) => json!({ "errors": [{ "message": err.to_string() }] }),
+        }
+    }
+}
+
+struct Execution<'a> {
+    mock: &'a MockSchema,
+    document: &'a QueryDocument,
+    variables: &'a Map<String, JsonValue>,
+}
+
+type Fields<'a> = Vec<(&'a str, Vec<&'a Field<'static, String>>)>;
+
+impl<'a> Execution<'a> {
+    fn schema(&self) -> &'a Schema {
+        &self.mock.schema
+    }
+
+    fn run(&self, operation_name: Option<&str>) -> Result<JsonValue, BoxError> {
+        let mut operations =
+            self.document
+                .definitions
+                .iter()
+                .filter_map(|definition| match definition {
+                    Definition::Operation(operation) => Some(operation),
+                    Definition::Fragment(_) => None,
+                });
+
+        let operation = match operation_name {
+            Some(name) => operations
+                .find(|operation| operation_name_of(operation) == Some(name))
+                .ok_or_else(|| format!("Unknown operation named \"{}\".", name))?,
+            None => {
+                let operation = operations.next().ok_or("The document has no operation.")?;
+
+                if operations.next().is_some() {
+                    return Err("Must provide an operation name when the document contains multiple operations.".into());
+                }
+
+                operation
+            }
+        };
+
+        let (root, selection_set) = match operation {
+            OperationDefinition::Query(q) => (Some(self.schema().query_type()), &q.selection_set),
+            OperationDefinition::SelectionSet(s) => (Some(self.schema().query_type()), s),
+            OperationDefinition::Mutation(m) => (self.schema().mutation_type(), &m.selection_set),
+            OperationDefinition::Subscription(s) => {
+                (self.schema().subscription_type(), &s.selection_set)
+            }
+        };
+        let root = root.ok_or("The schema does not support this operation type.")?;
+
+        self.resolve_object(root, &[selection_set], "")
+    }
+
+    fn resolve_object(
+        &self,
+        object_id: ObjectId,
+        selection_sets: &[&'a SelectionSet<'static, String>],
+        path: &str,
+    ) -> Result<JsonValue, BoxError> {
+        let object = self.schema().get_object(object_id);
+        let mut fields: Fields<'a> = Vec::new();
+
+        for selection_set in selection_sets {
+            self.collect_fields(object_id, selection_set, &mut fields)?;
+        }
+
+        let mut result = Map::new();
+
+        for (response_key, fields) in fields {
+            let field_path = format!("{}.{}", path, response_key);
+            let name = fields[0].name.as_str();
+
+            if name == TYPENAME_FIELD {
+                result.insert(response_key.to_owned(), object.name.clone().into());
+                continue;
+            }
+
+            let (_, schema_field) =
+                object
+                    .get_field_by_name(name, self.schema())
+                    .ok_or_else(|| {
+                        format!(
+                            "Cannot query field \"{}\" on type \"{}\".",
+                            name, object.name
+                        )
+                    })?;
+
+            let sub_selection_sets: Vec<_> = fields.iter().map(|f| &f.selection_set).collect();
+            let coordinate = format!("{}.{}", object.name, name);
+
+            let value = self.resolve_value(
+                schema_field.r#type.id,
+                &schema_field.r#type.qualifiers,
+                &coordinate,
+                &sub_selection_sets,
+                &field_path,
+            )?;
+
+            result.insert(response_key.to_owned(), value);
+        }
+
+        Ok(JsonValue::Object(result))
+    }
+
+    fn resolve_value(
+        &self,
+        type_id: TypeId,
+        qualifiers: &[GraphqlTypeQualifier],
+        coordinate: &str,
+        selection_sets: &[&'a SelectionSet<'static, String>],
+        path: &str,
+    ) -> Result<JsonValue, BoxError> {
+        match qualifiers.split_first() {
+            Some((GraphqlTypeQualifier::Required, rest)) => {
+                return self.resolve_value(type_id, rest, coordinate, selection_sets, path)
+            }
+            Some((GraphqlTypeQualifier::List, rest)) => {
+                return (0..self.mock.list_length)
+                    .map(|idx| {
+                        let item_path = format!("{}[{}]", path, idx);
+                        self.resolve_value(type_id, rest, coordinate, selection_sets, &item_path)
+                    })
+                    .collect();
+            }
+            None => (),
+        }
+
+        let type_name = type_id.name(self.schema());
+
+        match type_id {
+            TypeId::Object(object_id) => self.resolve_object(object_id, selection_sets, path),
+            TypeId::Interface(_) | TypeId::Union(_) => {
+                let object_id = self.pick_concrete_type(type_id, coordinate, path)?;
+                self.resolve_object(object_id, selection_sets, path)
+            }
+            TypeId::Scalar(_) | TypeId::Enum(_) => {
+                if let Some(value) = self
+                    .mock
+                    .overrides
+                    .get(coordinate)
+                    .or_else(|| self.mock.overrides.get(type_name))
+                {
+                    return Ok(value.clone());
+                }
+
+                Ok(self.fake_leaf(type_id, coordinate, path))
+            }
+            TypeId::Input(_) => Err(format!("Input type {} used as output.", type_name).into()),
+        }
+    }
+
+    fn fake_leaf(&self, type_id: TypeId, coordinate: &str, path: &str) -> JsonValue {
+        let seed = fnv1a(path);
+
+        match type_id {
+            TypeId::Enum(enum_id) => {
+                let variants = &self.schema().get_enum(enum_id).variants;
+                variants
+                    .get(seed as usize % variants.len().max(1))
+                    .map(|variant| JsonValue::from(variant.as_str()))
+                    .unwrap_or(JsonValue::Null)
+            }
+            _ => match type_id.name(self.schema()) {
+                "Int" => JsonValue::from(seed % 1000),
+                "Float" => JsonValue::from((seed % 100_000) as f64 / 100.0),
+                "Boolean" => JsonValue::from(seed & 1 == 0),
+                "ID" => JsonValue::from(format!("{:016x}", seed)),
+                _ => JsonValue::from(coordinate),
+            },
+        }
+    }
+
+    fn pick_concrete_type(
+        &self,
+        type_id: TypeId,
+        coordinate: &str,
+        path: &str,
+    ) -> Result<ObjectId, BoxError> {
+        let possible_types = self.schema().possible_types(type_id);
+
+        let overridden = self
+            .mock
+            .overrides
+            .get(coordinate)
+            .or_else(|| self.mock.overrides.get(type_id.name(self.schema())))
+            .and_then(JsonValue::as_str);
+
+        if let Some(name) = overridden {
+            return possible_types
+                .iter()
+                .find(|id| self.schema().get_object(**id).name == name)
+                .copied()
+                .ok_or_else(|| {
+                    format!(
+                        "Mock override for {} names {}, which is not one of its possible types.",
+                        coordinate, name
+                    )
+                    .into()
+                });
+        }
+
+        possible_types
+            .get(fnv1a(path) as usize % possible_types.len().max(1))
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "{} has no possible object types.",
+                    type_id.name(self.schema())
+                )
+                .into()
+            })
+    }
+
+    /// CollectFields, from the spec: flatten fragments, group fields by response key.
+    fn collect_fields(
+        &self,
+        object_id: ObjectId,
+        selection_set: &'a SelectionSet<'static, String>,
+        fields: &mut Fields<'a>,
+    ) -> Result<(), BoxError> {
+        for item in &selection_set.items {
+            match item {
+                Selection::Field(field) => {
+                    if !self.is_included(&field.directives)? {
+                        continue;
+                    }
+
+                    let response_key = field.alias.as_deref().unwrap_or(&field.name);
+
+                    match fields.iter_mut().find(|(key, _)| *key == response_key) {
+                        Some((_, same_key)) => same_key.push(field),
+                        None => fields.push((response_key, vec![field])),
+                    }
+                }
+                Selection::InlineFragment(inline) => {
+                    if !self.is_included(&inline.directives)? {
+                        continue;
+                    }
+
+                    let applies = match &inline.type_condition {
+                        Some(TypeCondition::On(on)) => {
+                            self.type_condition_applies(object_id, on)?
+                        }
+                        None => true,
+                    };
+
+                    if applies {
+                        self.collect_fields(object_id, &inline.selection_set, fields)?;
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    if !self.is_included(&spread.directives)? {
+                        continue;
+                    }
+
+                    let fragment = self
+                        .document
+                        .definitions
+                        .iter()
+                        .find_map(|definition| match definition {
+                            Definition::Fragment(f) if f.name == spread.fragment_name => Some(f),
+                            _ => None,
+                        })
+                        .ok_or_else(|| format!("Unknown fragment \"{}\".", spread.fragment_name))?;
+
+                    let TypeCondition::On(on) = &fragment.type_condition;
+
+                    if self.type_condition_applies(object_id, on)? {
+                        self.collect_fields(object_id, &fragment.selection_set, fields)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn type_condition_applies(&self, object_id: ObjectId, on: &str) -> Result<bool, BoxError> {
+        let type_id = self
+            .schema()
+            .find_type(on)
+            .ok_or_else(|| format!("Unknown type \"{}\".", on))?;
+
+        Ok(self.schema().possible_types(type_id).contains(&object_id))
+    }
+
+    /// Evaluate `@skip` and `@include`.
+    fn is_included(&self, directives: &[Directive<'static, String>]) -> Result<bool, BoxError> {
+        for directive in directives {
+            let expected = match directive.name.as_str() {
+                "skip" => false,
+                "include" => true,
+                _ => continue,
+            };
+
+            let condition = directive
+                .arguments
+                .iter()
+                .find(|(name, _)| name == "if")
+                .map(|(_, value)| value);
+
+            let condition = match condition {
+                Some(Value::Boolean(b)) => *b,
+                Some(Value::Variable(name)) => self
+                    .variables
+                    .get(name)
+                    .and_then(JsonValue::as_bool)
+                    .ok_or_else(|| format!("Variable \"${}\" must be a Boolean.", name))?,
+                _ => {
+                    return Err(format!(
+                        "Directive \"@{}\" requires a Boolean \"if\" argument.",
+                        directive.name
+                    )
+                    .into())
+                }
+            };
+
+            if condition != expected {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn operation_name_of<'a>(operation: &'a OperationDefinition<'static, String>) -> Option<&'a str> {
+    match operation {
+        OperationDefinition::Query(q) => q.name.as_deref(),
+        OperationDefinition::Mutation(m) => m.name.as_deref(),
+        OperationDefinition::Subscription(s) => s.name.as_deref(),
+        OperationDefinition::SelectionSet(_) => None,
+    }
+}
+
+/// A small, stable hash, so the fake values do not change between runs or platforms.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn star_wars() -> MockSchema {
+        let schema_path = std::env::current_dir()
+            .unwrap()
+            .join("src/tests/star_wars_schema.graphql");
+
+        MockSchema::from_path(&schema_path).unwrap()
+    }
+
+    const QUERY: &str = r#"
+        query Heroes($withFriends: Boolean!) {
+            hero(episode: NEWHOPE) {
+                __typename
+                id
+                name
+                appearsIn
+                friends @include(if: $withFriends) { name }
+                ... on Human { homePlanet }
+                ... on Droid { primaryFunction }
+            }
+        }
+    "#;
+
+    #[test]
+    fn mock_responses_are_deterministic_and_conform_to_the_selection() {
+        let mock = star_wars();
+        let variables = json!({ "withFriends": false });
+        let variables = variables.as_object().unwrap();
+
+        let first = mock.execute(QUERY, None, variables);
+        let second = mock.execute(QUERY, Some("Heroes"), variables);
+        assert_eq!(first, second);
+
+        let hero = &first["data"]["hero"];
+        let typename = hero["__typename"].as_str().unwrap();
+        assert!(typename == "Human" || typename == "Droid");
+        assert!(hero["id"].is_string());
+        assert_eq!(
+            hero["name"],
+            "Character.name".replace("Character", typename)
+        );
+        assert_eq!(hero["appearsIn"].as_array().unwrap().len(), 2);
+        assert!(hero.get("friends").is_none());
+    }
+
+    #[test]
+    fn mock_overrides_apply_to_types_and_fields() {
+        let mut mock = star_wars();
+        let overrides = json!({
+            "Character": "Droid",
+            "Droid.name": "R2-D2",
+            "Episode": "JEDI",
+        });
+        mock.set_overrides(overrides.as_object().unwrap().clone());
+        mock.set_list_length(1);
+
+        let variables = json!({ "withFriends": true });
+        let response = mock.execute(QUERY, None, variables.as_object().unwrap());
+        let hero = &response["data"]["hero"];
+
+        assert_eq!(hero["__typename"], "Droid");
+        assert_eq!(hero["name"], "R2-D2");
+        assert_eq!(hero["appearsIn"], json!(["JEDI"]));
+        assert_eq!(hero["friends"].as_array().unwrap().len(), 1);
+        assert!(hero["primaryFunction"].is_string());
+        assert!(hero.get("homePlanet").is_none());
+    }
+
+    #[test]
+    fn mock_reports_unknown_fields() {
+        let response = star_wars().execute("query Q { hero { nope } }", None, &Map::new());
+
+        assert!(response["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("nope"));
+    }
+}
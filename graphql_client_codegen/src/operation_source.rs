@@ -0,0 +1,236 @@
+//! Printing the part of a query document that an operation needs: the operation itself, and the
+//! fragments it uses.
+
+use crate::QueryDocument;
+use graphql_parser::query::{Definition, OperationDefinition, Selection, SelectionSet};
+use std::collections::BTreeSet;
+
+/// The source of the named operation and of the fragments it uses, directly or through other
+/// fragments, in the order they are defined in the document.
+pub(crate) fn operation_source(
+    document: &QueryDocument,
+    operation_name: &str,
+    minify: bool,
+) -> String {
+    let operation = document
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Operation(operation)
+                if operation_name_of(operation) == Some(operation_name) =>
+            {
+                Some(operation)
+            }
+            _ => None,
+        });
+
+    let operation = match operation {
+        Some(operation) => operation,
+        None => return String::new(),
+    };
+
+    let mut used = BTreeSet::new();
+    let mut to_visit = Vec::new();
+    collect_spreads(operation_selection_set(operation), &mut to_visit);
+
+    while let Some(name) = to_visit.pop() {
+        if !used.insert(name.clone()) {
+            continue;
+        }
+
+        let fragment = document
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Fragment(fragment) if fragment.name == name => Some(fragment),
+                _ => None,
+            });
+
+        if let Some(fragment) = fragment {
+            collect_spreads(&fragment.selection_set, &mut to_visit);
+        }
+    }
+
+    let mut source = operation.to_string();
+
+    for definition in &document.definitions {
+        if let Definition::Fragment(fragment) = definition {
+            if used.contains(&fragment.name) {
+                source.push('\n');
+                source.push_str(&fragment.to_string());
+            }
+        }
+    }
+
+    if minify {
+        minify_source(&source)
+    } else {
+        source
+    }
+}
+
+fn operation_name_of<'a>(operation: &'a OperationDefinition<'static, String>) -> Option<&'a str> {
+    match operation {
+        OperationDefinition::Query(query) => query.name.as_deref(),
+        OperationDefinition::Mutation(mutation) => mutation.name.as_deref(),
+        OperationDefinition::Subscription(subscription) => subscription.name.as_deref(),
+        OperationDefinition::SelectionSet(_) => None,
+    }
+}
+
+pub(crate) fn operation_selection_set<'a>(
+    operation: &'a OperationDefinition<'static, String>,
+) -> &'a SelectionSet<'static, String> {
+    match operation {
+        OperationDefinition::SelectionSet(selection_set) => selection_set,
+        OperationDefinition::Query(query) => &query.selection_set,
+        OperationDefinition::Mutation(mutation) => &mutation.selection_set,
+        OperationDefinition::Subscription(subscription) => &subscription.selection_set,
+    }
+}
+
+/// Pushes the names of the fragments spread anywhere in the selection set.
+pub(crate) fn collect_spreads(
+    selection_set: &SelectionSet<'static, String>,
+    names: &mut Vec<String>,
+) {
+    for selection in &selection_set.items {
+        match selection {
+            Selection::Field(field) => collect_spreads(&field.selection_set, names),
+            Selection::FragmentSpread(spread) => names.push(spread.fragment_name.clone()),
+            Selection::InlineFragment(inline) => collect_spreads(&inline.selection_set, names),
+        }
+    }
+}
+
+/// Removes the whitespace and commas that are not needed to separate two tokens. String
+/// literals are kept as they are.
+fn minify_source(source: &str) -> String {
+    let is_punctuator = |c: char| "!$&()...:=@[]{|}\"".contains(c);
+    let mut minified = String::with_capacity(source.len());
+    let mut chars = source.chars();
+    let mut pending_separator = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ',' => pending_separator = true,
+            '"' => {
+                if pending_separator && !minified.is_empty() && !minified.ends_with(is_punctuator) {
+                    minified.push(' ');
+                }
+                pending_separator = false;
+
+                // Block strings are printed as `"""`, other strings are escaped.
+                let block = source_starts_with(&mut chars, "\"\"");
+                minified.push('"');
+
+                if block {
+                    minified.push_str("\"\"");
+                    let mut quotes = 0;
+
+                    for c in chars.by_ref() {
+                        minified.push(c);
+                        quotes = if c == '"' { quotes + 1 } else { 0 };
+
+                        if quotes == 3 && !minified.ends_with("\\\"\"\"") {
+                            break;
+                        }
+                    }
+                } else {
+                    let mut escaped = false;
+
+                    for c in chars.by_ref() {
+                        minified.push(c);
+
+                        match c {
+                            '\\' if !escaped => escaped = true,
+                            '"' if !escaped => break,
+                            _ => escaped = false,
+                        }
+                    }
+                }
+            }
+            c => {
+                if pending_separator
+                    && !minified.is_empty()
+                    && !is_punctuator(c)
+                    && !minified.ends_with(is_punctuator)
+                {
+                    minified.push(' ');
+                }
+                pending_separator = false;
+                minified.push(c);
+            }
+        }
+    }
+
+    minified
+}
+
+/// Consumes `expected` if the remaining characters start with it.
+fn source_starts_with(chars: &mut std::str::Chars<'_>, expected: &str) -> bool {
+    let lookahead = chars.clone();
+
+    if lookahead.take(expected.len()).eq(expected.chars()) {
+        for _ in 0..expected.len() {
+            chars.next();
+        }
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"
+fragment Unused on User { id }
+
+query First($id: ID!) {
+  user(id: $id) { ...UserFields }
+}
+
+fragment UserFields on User {
+  name
+  bio(format: "plain, short")
+  ...AvatarFields
+}
+
+query Second { viewer { id } }
+
+fragment AvatarFields on User { avatarUrl(size: 64) }
+"#;
+
+    #[test]
+    fn operation_source_only_contains_used_fragments() {
+        let document = graphql_parser::parse_query::<String>(DOCUMENT)
+            .unwrap()
+            .into_static();
+
+        let source = operation_source(&document, "First", false);
+        assert!(source.starts_with("query First($id: ID!) {"));
+        assert!(source.contains("fragment UserFields on User"));
+        assert!(source.contains("fragment AvatarFields on User"));
+        assert!(!source.contains("Unused"));
+        assert!(!source.contains("Second"));
+
+        assert_eq!(
+            operation_source(&document, "Second", false),
+            "query Second {\n  viewer {\n    id\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn operation_source_can_be_minified() {
+        let document = graphql_parser::parse_query::<String>(DOCUMENT)
+            .unwrap()
+            .into_static();
+
+        assert_eq!(
+            operation_source(&document, "First", true),
+            "query First($id:ID!){user(id:$id){...UserFields}}fragment UserFields on User{name bio(format:\"plain, short\")...AvatarFields}fragment AvatarFields on User{avatarUrl(size:64)}"
+        );
+    }
+}
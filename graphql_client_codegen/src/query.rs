@@ -12,20 +12,24 @@ pub(crate) use selection::*;
 
 use crate::{
     constants::TYPENAME_FIELD,
+    diagnostics::Diagnostic,
     normalization::Normalization,
     schema::{
-        resolve_field_type, EnumId, InputId, ScalarId, Schema, StoredEnum, StoredFieldType,
-        StoredInputType, StoredScalar, TypeId, UnionId,
+        resolve_field_type, EnumId, InputId, ScalarId, Schema, StoredEnum, StoredFieldId,
+        StoredFieldType, StoredInputType, StoredScalar, TypeId, UnionId,
     },
 };
+use graphql_parser::Pos;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Display,
+    path::Path,
 };
 
 #[derive(Debug)]
 pub(crate) struct QueryValidationError {
     message: String,
+    position: Option<Pos>,
 }
 
 impl Display for QueryValidationError {
@@ -38,7 +42,30 @@ impl std::error::Error for QueryValidationError {}
 
 impl QueryValidationError {
     pub(crate) fn new(message: String) -> Self {
-        QueryValidationError { message }
+        QueryValidationError {
+            message,
+            position: None,
+        }
+    }
+
+    pub(crate) fn with_position(mut self, position: Pos) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub(crate) fn position(&self) -> Option<Pos> {
+        self.position
+    }
+
+    pub(crate) fn into_diagnostic(self, source: &str, path: Option<&Path>) -> Diagnostic {
+        let diagnostic = Diagnostic::new(self.message).with_path(path);
+
+        match self.position {
+            Some(position) => {
+                diagnostic.with_location(position.line, position.column, Some(source))
+            }
+            None => diagnostic,
+        }
     }
 }
 
@@ -63,173 +90,199 @@ pub(crate) struct VariableId(pub u32);
 pub(crate) fn resolve<'doc, T>(
     schema: &Schema,
     query: &graphql_parser::query::Document<'doc, T>,
-) -> Result<Query, QueryValidationError>
+    deny_unknown_directives: bool,
+) -> Result<Query, Vec<QueryValidationError>>
 where
     T: graphql_parser::query::Text<'doc>,
 {
+    let mut errors = validation::validate_document(query, schema, deny_unknown_directives);
     let mut resolved_query: Query = Default::default();
 
-    create_roots(&mut resolved_query, query, schema)?;
+    create_roots(&mut resolved_query, query, schema, &mut errors);
 
     // Then resolve the selections.
     for definition in &query.definitions {
         match definition {
             graphql_parser::query::Definition::Fragment(fragment) => {
-                resolve_fragment(&mut resolved_query, schema, fragment)?
+                resolve_fragment(&mut resolved_query, schema, fragment, &mut errors)
             }
             graphql_parser::query::Definition::Operation(operation) => {
-                resolve_operation(&mut resolved_query, schema, operation)?
+                resolve_operation(&mut resolved_query, schema, operation, &mut errors)
             }
         }
     }
 
     // Validation: to be expanded and factored out.
-    validation::validate_typename_presence(&BoundQuery {
+    let bound_query = BoundQuery {
         query: &resolved_query,
         schema,
-    })?;
+    };
+
+    validation::validate_typename_presence(&bound_query, &mut errors);
 
     for (selection_id, _) in resolved_query.selections() {
-        selection::validate_type_conditions(
-            selection_id,
-            &BoundQuery {
-                query: &resolved_query,
-                schema,
-            },
-        )?
+        if let Err(err) = selection::validate_type_conditions(selection_id, &bound_query) {
+            errors.push(err);
+        }
     }
 
-    Ok(resolved_query)
+    if errors.is_empty() {
+        Ok(resolved_query)
+    } else {
+        Err(errors)
+    }
 }
 
 fn create_roots<'doc, T>(
     resolved_query: &mut Query,
     query: &graphql_parser::query::Document<'doc, T>,
     schema: &Schema,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
     // First, give ids to all fragments and operations.
     for definition in &query.definitions {
-        match definition {
-            graphql_parser::query::Definition::Fragment(fragment) => {
-                let graphql_parser::query::TypeCondition::On(on) = &fragment.type_condition;
-                resolved_query.fragments.push(ResolvedFragment {
-                    name: fragment.name.as_ref().into(),
-                    on: schema.find_type(on.as_ref()).ok_or_else(|| {
-                        QueryValidationError::new(format!(
-                            "Could not find type {} for fragment {} in schema.",
-                            on.as_ref(),
-                            fragment.name.as_ref(),
-                        ))
-                    })?,
-                    selection_set: Vec::new(),
-                });
-            }
-            graphql_parser::query::Definition::Operation(
-                graphql_parser::query::OperationDefinition::Mutation(m),
-            ) => {
-                let on = schema.mutation_type().ok_or_else(|| {
-                    QueryValidationError::new(
-                        "Query contains a mutation operation, but the schema has no mutation type."
-                            .to_owned(),
-                    )
-                })?;
-                let resolved_operation: ResolvedOperation = ResolvedOperation {
-                    object_id: on,
-                    name: m
-                        .name
-                        .as_ref()
-                        .expect("mutation without name")
-                        .as_ref()
-                        .into(),
-                    _operation_type: operations::OperationType::Mutation,
-                    selection_set: Vec::with_capacity(m.selection_set.items.len()),
-                };
-
-                resolved_query.operations.push(resolved_operation);
-            }
-            graphql_parser::query::Definition::Operation(
-                graphql_parser::query::OperationDefinition::Query(q),
-            ) => {
-                let on = schema.query_type();
-                let resolved_operation: ResolvedOperation = ResolvedOperation {
-                    name: q.name.as_ref().expect("query without name. Instead of `query (...)`, write `query SomeName(...)` in your .graphql file").as_ref().into(),
-                    _operation_type: operations::OperationType::Query,
-                    object_id: on,
-                    selection_set: Vec::with_capacity(q.selection_set.items.len()),
-                };
-
-                resolved_query.operations.push(resolved_operation);
-            }
-            graphql_parser::query::Definition::Operation(
-                graphql_parser::query::OperationDefinition::Subscription(s),
-            ) => {
-                let on = schema.subscription_type().ok_or_else(|| {
-                    QueryValidationError::new(
-                        "Query contains a subscription operation, but the schema has no subscription type.".to_owned()
-                    )
-                })?;
-
-                if s.selection_set.items.len() != 1 {
-                    return Err(QueryValidationError::new(
-                        crate::constants::MULTIPLE_SUBSCRIPTION_FIELDS_ERROR.to_owned(),
-                    ));
-                }
+        if let Err(err) = create_root(resolved_query, definition, schema) {
+            errors.push(err);
+        }
+    }
+}
 
-                let resolved_operation: ResolvedOperation = ResolvedOperation {
-                    name: s
-                        .name
-                        .as_ref()
-                        .expect("subscription without name")
-                        .as_ref()
-                        .into(),
-                    _operation_type: operations::OperationType::Subscription,
-                    object_id: on,
-                    selection_set: Vec::with_capacity(s.selection_set.items.len()),
-                };
-
-                resolved_query.operations.push(resolved_operation);
-            }
-            graphql_parser::query::Definition::Operation(
-                graphql_parser::query::OperationDefinition::SelectionSet(_),
-            ) => {
+fn create_root<'doc, T>(
+    resolved_query: &mut Query,
+    definition: &graphql_parser::query::Definition<'doc, T>,
+    schema: &Schema,
+) -> Result<(), QueryValidationError>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    match definition {
+        graphql_parser::query::Definition::Fragment(fragment) => {
+            let graphql_parser::query::TypeCondition::On(on) = &fragment.type_condition;
+            resolved_query.fragments.push(ResolvedFragment {
+                name: fragment.name.as_ref().into(),
+                on: schema.find_type(on.as_ref()).ok_or_else(|| {
+                    QueryValidationError::new(format!(
+                        "Could not find type {} for fragment {} in schema.",
+                        on.as_ref(),
+                        fragment.name.as_ref(),
+                    ))
+                    .with_position(fragment.position)
+                })?,
+                position: fragment.position,
+                selection_set: Vec::new(),
+            });
+        }
+        graphql_parser::query::Definition::Operation(
+            graphql_parser::query::OperationDefinition::Mutation(m),
+        ) => {
+            let on = schema.mutation_type().ok_or_else(|| {
+                QueryValidationError::new(
+                    "Query contains a mutation operation, but the schema has no mutation type."
+                        .to_owned(),
+                )
+                .with_position(m.position)
+            })?;
+            let resolved_operation: ResolvedOperation = ResolvedOperation {
+                object_id: on,
+                name: operation_name(m.name.as_ref().map(AsRef::as_ref), "mutation", m.position)?,
+                _operation_type: operations::OperationType::Mutation,
+                selection_set: Vec::with_capacity(m.selection_set.items.len()),
+            };
+
+            resolved_query.operations.push(resolved_operation);
+        }
+        graphql_parser::query::Definition::Operation(
+            graphql_parser::query::OperationDefinition::Query(q),
+        ) => {
+            let on = schema.query_type();
+            let resolved_operation: ResolvedOperation = ResolvedOperation {
+                name: operation_name(q.name.as_ref().map(AsRef::as_ref), "query", q.position)?,
+                _operation_type: operations::OperationType::Query,
+                object_id: on,
+                selection_set: Vec::with_capacity(q.selection_set.items.len()),
+            };
+
+            resolved_query.operations.push(resolved_operation);
+        }
+        graphql_parser::query::Definition::Operation(
+            graphql_parser::query::OperationDefinition::Subscription(s),
+        ) => {
+            let on = schema.subscription_type().ok_or_else(|| {
+                QueryValidationError::new(
+                    "Query contains a subscription operation, but the schema has no subscription type.".to_owned()
+                )
+                .with_position(s.position)
+            })?;
+
+            if s.selection_set.items.len() != 1 {
                 return Err(QueryValidationError::new(
-                    crate::constants::SELECTION_SET_AT_ROOT.to_owned(),
-                ))
+                    crate::constants::MULTIPLE_SUBSCRIPTION_FIELDS_ERROR.to_owned(),
+                )
+                .with_position(s.position));
             }
+
+            let resolved_operation: ResolvedOperation = ResolvedOperation {
+                name: operation_name(
+                    s.name.as_ref().map(AsRef::as_ref),
+                    "subscription",
+                    s.position,
+                )?,
+                _operation_type: operations::OperationType::Subscription,
+                object_id: on,
+                selection_set: Vec::with_capacity(s.selection_set.items.len()),
+            };
+
+            resolved_query.operations.push(resolved_operation);
+        }
+        graphql_parser::query::Definition::Operation(
+            graphql_parser::query::OperationDefinition::SelectionSet(selection_set),
+        ) => {
+            return Err(QueryValidationError::new(
+                crate::constants::SELECTION_SET_AT_ROOT.to_owned(),
+            )
+            .with_position(selection_set.span.0))
         }
     }
 
     Ok(())
 }
 
+fn operation_name(
+    name: Option<&str>,
+    kind: &str,
+    position: Pos,
+) -> Result<String, QueryValidationError> {
+    name.map(Into::into).ok_or_else(|| {
+        QueryValidationError::new(format!(
+            "{kind} without name. Instead of `{kind} (...)`, write `{kind} SomeName(...)` in your .graphql file.",
+            kind = kind
+        ))
+        .with_position(position)
+    })
+}
+
 fn resolve_fragment<'doc, T>(
     query: &mut Query,
     schema: &Schema,
     fragment_definition: &graphql_parser::query::FragmentDefinition<'doc, T>,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
     let graphql_parser::query::TypeCondition::On(on) = &fragment_definition.type_condition;
-    let on = schema.find_type(on.as_ref()).ok_or_else(|| {
-        QueryValidationError::new(format!(
-            "Could not find type `{}` referenced by fragment `{}`",
-            on.as_ref(),
-            fragment_definition.name.as_ref(),
-        ))
-    })?;
 
-    let (id, _) = query
-        .find_fragment(fragment_definition.name.as_ref())
-        .ok_or_else(|| {
-            QueryValidationError::new(format!(
-                "Could not find fragment `{}`.",
-                fragment_definition.name.as_ref()
-            ))
-        })?;
+    // Unknown type conditions were reported when creating the roots.
+    let on = match schema.find_type(on.as_ref()) {
+        Some(on) => on,
+        None => return,
+    };
+
+    let id = match query.find_fragment(fragment_definition.name.as_ref()) {
+        Some((id, _)) => id,
+        None => return,
+    };
 
     resolve_selection(
         query,
@@ -237,9 +290,8 @@ where
         &fragment_definition.selection_set,
         SelectionParent::Fragment(id),
         schema,
-    )?;
-
-    Ok(())
+        errors,
+    );
 }
 
 fn resolve_union_selection<'doc, T>(
@@ -248,45 +300,70 @@ fn resolve_union_selection<'doc, T>(
     selection_set: &graphql_parser::query::SelectionSet<'doc, T>,
     parent: SelectionParent,
     schema: &Schema,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
     for item in selection_set.items.iter() {
         match item {
             graphql_parser::query::Selection::Field(field) => {
                 if field.name.as_ref() == TYPENAME_FIELD {
-                    let id = query.push_selection(Selection::Typename, parent);
+                    let id = query.push_selection(Selection::Typename, parent, field.position);
                     parent.add_to_selection_set(query, id);
                 } else {
-                    return Err(QueryValidationError::new(format!(
-                        "Invalid field selection on union field ({:?})",
-                        parent
-                    )));
+                    errors.push(
+                        QueryValidationError::new(format!(
+                            "Invalid field selection on union field ({:?})",
+                            parent
+                        ))
+                        .with_position(field.position),
+                    );
                 }
             }
             graphql_parser::query::Selection::InlineFragment(inline_fragment) => {
-                let selection_id = resolve_inline_fragment(query, schema, inline_fragment, parent)?;
-                parent.add_to_selection_set(query, selection_id);
+                if let Some(selection_id) =
+                    resolve_inline_fragment(query, schema, inline_fragment, parent, errors)
+                {
+                    parent.add_to_selection_set(query, selection_id);
+                }
             }
             graphql_parser::query::Selection::FragmentSpread(fragment_spread) => {
-                let (fragment_id, _fragment) = query
-                    .find_fragment(fragment_spread.fragment_name.as_ref())
-                    .ok_or_else(|| {
-                        QueryValidationError::new(format!(
-                            "Could not find fragment `{}` referenced by fragment spread.",
-                            fragment_spread.fragment_name.as_ref()
-                        ))
-                    })?;
-
-                let id = query.push_selection(Selection::FragmentSpread(fragment_id), parent);
-
-                parent.add_to_selection_set(query, id);
+                resolve_fragment_spread(query, fragment_spread, parent, errors)
             }
         }
     }
+}
 
-    Ok(())
+fn resolve_fragment_spread<'doc, T>(
+    query: &mut Query,
+    fragment_spread: &graphql_parser::query::FragmentSpread<'doc, T>,
+    parent: SelectionParent,
+    errors: &mut Vec<QueryValidationError>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let fragment_id = match query.find_fragment(fragment_spread.fragment_name.as_ref()) {
+        Some((fragment_id, _fragment)) => fragment_id,
+        None => {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "Could not find fragment `{}` referenced by fragment spread.",
+                    fragment_spread.fragment_name.as_ref()
+                ))
+                .with_position(fragment_spread.position),
+            );
+            return;
+        }
+    };
+
+    let id = query.push_selection(
+        Selection::FragmentSpread(fragment_id),
+        parent,
+        fragment_spread.position,
+    );
+    query.mark_conditional(id, &fragment_spread.directives);
+
+    parent.add_to_selection_set(query, id);
 }
 
 fn resolve_object_selection<'a, 'doc, T>(
@@ -295,28 +372,40 @@ fn resolve_object_selection<'a, 'doc, T>(
     selection_set: &graphql_parser::query::SelectionSet<'doc, T>,
     parent: SelectionParent,
     schema: &'a Schema,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
     for item in selection_set.items.iter() {
         match item {
             graphql_parser::query::Selection::Field(field) => {
                 if field.name.as_ref() == TYPENAME_FIELD {
-                    let id = query.push_selection(Selection::Typename, parent);
+                    let id = query.push_selection(Selection::Typename, parent, field.position);
                     parent.add_to_selection_set(query, id);
                     continue;
                 }
 
-                let (field_id, schema_field) = object
-                    .get_field_by_name(field.name.as_ref(), schema)
-                    .ok_or_else(|| {
-                        QueryValidationError::new(format!(
-                            "No field named {} on {}",
-                            field.name.as_ref(),
-                            object.name()
-                        ))
-                    })?;
+                let (field_id, schema_field) =
+                    match object.get_field_by_name(field.name.as_ref(), schema) {
+                        Some(field) => field,
+                        None => {
+                            errors.push(
+                                QueryValidationError::new(format!(
+                                    "No field named {} on {}",
+                                    field.name.as_ref(),
+                                    object.name()
+                                ))
+                                .with_position(field.position),
+                            );
+                            continue;
+                        }
+                    };
+
+                if let Err(err) =
+                    validation::validate_field_arguments(field, schema_field, object.name(), schema)
+                {
+                    errors.push(err);
+                }
 
                 let id = query.push_selection(
                     Selection::Field(SelectedField {
@@ -325,7 +414,9 @@ where
                         selection_set: Vec::with_capacity(selection_set.items.len()),
                     }),
                     parent,
+                    field.position,
                 );
+                query.mark_conditional(id, &field.directives);
 
                 resolve_selection(
                     query,
@@ -333,33 +424,23 @@ where
                     &field.selection_set,
                     SelectionParent::Field(id),
                     schema,
-                )?;
+                    errors,
+                );
 
                 parent.add_to_selection_set(query, id);
             }
             graphql_parser::query::Selection::InlineFragment(inline) => {
-                let selection_id = resolve_inline_fragment(query, schema, inline, parent)?;
-
-                parent.add_to_selection_set(query, selection_id);
+                if let Some(selection_id) =
+                    resolve_inline_fragment(query, schema, inline, parent, errors)
+                {
+                    parent.add_to_selection_set(query, selection_id);
+                }
             }
             graphql_parser::query::Selection::FragmentSpread(fragment_spread) => {
-                let (fragment_id, _fragment) = query
-                    .find_fragment(fragment_spread.fragment_name.as_ref())
-                    .ok_or_else(|| {
-                        QueryValidationError::new(format!(
-                            "Could not find fragment `{}` referenced by fragment spread.",
-                            fragment_spread.fragment_name.as_ref()
-                        ))
-                    })?;
-
-                let id = query.push_selection(Selection::FragmentSpread(fragment_id), parent);
-
-                parent.add_to_selection_set(query, id);
+                resolve_fragment_spread(query, fragment_spread, parent, errors)
             }
         }
     }
-
-    Ok(())
 }
 
 fn resolve_selection<'doc, T>(
@@ -368,33 +449,34 @@ fn resolve_selection<'doc, T>(
     selection_set: &graphql_parser::query::SelectionSet<'doc, T>,
     parent: SelectionParent,
     schema: &Schema,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
     match on {
         TypeId::Object(oid) => {
             let object = schema.get_object(oid);
-            resolve_object_selection(ctx, object, selection_set, parent, schema)?;
+            resolve_object_selection(ctx, object, selection_set, parent, schema, errors);
         }
         TypeId::Interface(interface_id) => {
             let interface = schema.get_interface(interface_id);
-            resolve_object_selection(ctx, interface, selection_set, parent, schema)?;
+            resolve_object_selection(ctx, interface, selection_set, parent, schema, errors);
         }
         TypeId::Union(union_id) => {
-            resolve_union_selection(ctx, union_id, selection_set, parent, schema)?;
+            resolve_union_selection(ctx, union_id, selection_set, parent, schema, errors);
         }
         other => {
             if !selection_set.items.is_empty() {
-                return Err(QueryValidationError::new(format!(
-                    "Selection set on non-object, non-interface type. ({:?})",
-                    other
-                )));
+                errors.push(
+                    QueryValidationError::new(format!(
+                        "Selection set on non-object, non-interface type. ({:?})",
+                        other
+                    ))
+                    .with_position(selection_set.span.0),
+                );
             }
         }
     };
-
-    Ok(())
 }
 
 fn resolve_inline_fragment<'doc, T>(
@@ -402,20 +484,37 @@ fn resolve_inline_fragment<'doc, T>(
     schema: &Schema,
     inline_fragment: &graphql_parser::query::InlineFragment<'doc, T>,
     parent: SelectionParent,
-) -> Result<SelectionId, QueryValidationError>
+    errors: &mut Vec<QueryValidationError>,
+) -> Option<SelectionId>
 where
     T: graphql_parser::query::Text<'doc>,
 {
-    let graphql_parser::query::TypeCondition::On(on) = inline_fragment
-        .type_condition
-        .as_ref()
-        .expect("missing type condition on inline fragment");
-    let type_id = schema.find_type(on.as_ref()).ok_or_else(|| {
-        QueryValidationError::new(format!(
-            "Could not find type `{}` referenced by inline fragment.",
-            on.as_ref()
-        ))
-    })?;
+    let on = match inline_fragment.type_condition.as_ref() {
+        Some(graphql_parser::query::TypeCondition::On(on)) => on,
+        None => {
+            errors.push(
+                QueryValidationError::new(
+                    "Inline fragments without a type condition are not supported.".to_owned(),
+                )
+                .with_position(inline_fragment.position),
+            );
+            return None;
+        }
+    };
+
+    let type_id = match schema.find_type(on.as_ref()) {
+        Some(type_id) => type_id,
+        None => {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "Could not find type `{}` referenced by inline fragment.",
+                    on.as_ref()
+                ))
+                .with_position(inline_fragment.position),
+            );
+            return None;
+        }
+    };
 
     let id = query.push_selection(
         Selection::InlineFragment(InlineFragment {
@@ -423,7 +522,9 @@ where
             selection_set: Vec::with_capacity(inline_fragment.selection_set.items.len()),
         }),
         parent,
+        inline_fragment.position,
     );
+    query.mark_conditional(id, &inline_fragment.directives);
 
     resolve_selection(
         query,
@@ -431,79 +532,85 @@ where
         &inline_fragment.selection_set,
         SelectionParent::InlineFragment(id),
         schema,
-    )?;
+        errors,
+    );
+
+    Some(id)
+}
+
+/// `@include(if: true)` and `@skip(if: false)` are the only conditions known to always select
+/// their field.
+fn directive_is_conditional<'doc, T>(directive: &graphql_parser::query::Directive<'doc, T>) -> bool
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let always_selected_when = match directive.name.as_ref() {
+        "include" => true,
+        "skip" => false,
+        _ => return false,
+    };
+
+    let condition = directive
+        .arguments
+        .iter()
+        .find(|(name, _)| name.as_ref() == "if")
+        .map(|(_, value)| value);
 
-    Ok(id)
+    !matches!(condition, Some(graphql_parser::query::Value::Boolean(value)) if *value == always_selected_when)
 }
 
 fn resolve_operation<'doc, T>(
     query: &mut Query,
     schema: &Schema,
     operation: &graphql_parser::query::OperationDefinition<'doc, T>,
-) -> Result<(), QueryValidationError>
-where
+    errors: &mut Vec<QueryValidationError>,
+) where
     T: graphql_parser::query::Text<'doc>,
 {
-    match operation {
-        graphql_parser::query::OperationDefinition::Mutation(m) => {
-            let on = schema.mutation_type().ok_or_else(|| {
-                QueryValidationError::new(
-                    "Query contains a mutation operation, but the schema has no mutation type."
-                        .to_owned(),
-                )
-            })?;
-            let on = schema.get_object(on);
-
-            let (id, _) = query
-                .find_operation(m.name.as_ref().map(|name| name.as_ref()).unwrap())
-                .unwrap();
-
-            resolve_variables(query, &m.variable_definitions, schema, id);
-            resolve_object_selection(
-                query,
-                on,
-                &m.selection_set,
-                SelectionParent::Operation(id),
-                schema,
-            )?;
-        }
-        graphql_parser::query::OperationDefinition::Query(q) => {
-            let on = schema.get_object(schema.query_type());
-            let (id, _) = query
-                .find_operation(q.name.as_ref().map(|name| name.as_ref()).unwrap())
-                .unwrap();
-
-            resolve_variables(query, &q.variable_definitions, schema, id);
-            resolve_object_selection(
-                query,
-                on,
-                &q.selection_set,
-                SelectionParent::Operation(id),
-                schema,
-            )?;
-        }
-        graphql_parser::query::OperationDefinition::Subscription(s) => {
-            let on = schema.subscription_type().ok_or_else(|| QueryValidationError::new("Query contains a subscription operation, but the schema has no subscription type.".into()))?;
-            let on = schema.get_object(on);
-            let (id, _) = query
-                .find_operation(s.name.as_ref().map(|name| name.as_ref()).unwrap())
-                .unwrap();
-
-            resolve_variables(query, &s.variable_definitions, schema, id);
-            resolve_object_selection(
-                query,
-                on,
-                &s.selection_set,
-                SelectionParent::Operation(id),
-                schema,
-            )?;
-        }
-        graphql_parser::query::OperationDefinition::SelectionSet(_) => {
-            unreachable!("unnamed queries are not supported")
-        }
-    }
+    use graphql_parser::query::OperationDefinition;
+
+    let (name, variable_definitions, selection_set, on) = match operation {
+        OperationDefinition::Mutation(m) => (
+            &m.name,
+            &m.variable_definitions,
+            &m.selection_set,
+            schema.mutation_type(),
+        ),
+        OperationDefinition::Query(q) => (
+            &q.name,
+            &q.variable_definitions,
+            &q.selection_set,
+            Some(schema.query_type()),
+        ),
+        OperationDefinition::Subscription(s) => (
+            &s.name,
+            &s.variable_definitions,
+            &s.selection_set,
+            schema.subscription_type(),
+        ),
+        OperationDefinition::SelectionSet(_) => return,
+    };
 
-    Ok(())
+    // Operations that could not be created as roots were already reported.
+    let (on, name) = match (on, name) {
+        (Some(on), Some(name)) => (schema.get_object(on), name),
+        _ => return,
+    };
+
+    let id = match query.find_operation(name.as_ref()) {
+        Some((id, _)) => id,
+        None => return,
+    };
+
+    resolve_variables(query, variable_definitions, schema, id);
+    resolve_object_selection(
+        query,
+        on,
+        selection_set,
+        SelectionParent::Operation(id),
+        schema,
+        errors,
+    );
 }
 
 #[derive(Default)]
@@ -511,16 +618,24 @@ pub(crate) struct Query {
     fragments: Vec<ResolvedFragment>,
     operations: Vec<ResolvedOperation>,
     selection_parent_idx: BTreeMap<SelectionId, SelectionParent>,
+    selection_positions: BTreeMap<SelectionId, Pos>,
+    conditional_selections: BTreeSet<SelectionId>,
     selections: Vec<Selection>,
     pub(crate) variables: Vec<ResolvedVariable>,
 }
 
 impl Query {
-    fn push_selection(&mut self, node: Selection, parent: SelectionParent) -> SelectionId {
+    fn push_selection(
+        &mut self,
+        node: Selection,
+        parent: SelectionParent,
+        position: Pos,
+    ) -> SelectionId {
         let id = SelectionId(self.selections.len() as u32);
         self.selections.push(node);
 
         self.selection_parent_idx.insert(id, parent);
+        self.selection_positions.insert(id, position);
 
         id
     }
@@ -572,6 +687,29 @@ impl Query {
             .map(|(id, op)| (OperationId::new(id), op))
     }
 
+    /// Whether the selection is subject to a `@skip` or `@include` condition, in which case it
+    /// may be missing from the response.
+    pub(crate) fn is_conditional(&self, id: SelectionId) -> bool {
+        self.conditional_selections.contains(&id)
+    }
+
+    fn mark_conditional<'doc, T>(
+        &mut self,
+        id: SelectionId,
+        directives: &[graphql_parser::query::Directive<'doc, T>],
+    ) where
+        T: graphql_parser::query::Text<'doc>,
+    {
+        if directives.iter().any(directive_is_conditional) {
+            self.conditional_selections.insert(id);
+        }
+    }
+
+    /// Where the selection appears in the query document.
+    fn selection_position(&self, id: SelectionId) -> Option<Pos> {
+        self.selection_positions.get(&id).copied()
+    }
+
     fn selections(&self) -> impl Iterator<Item = (SelectionId, &Selection)> {
         self.selections
             .iter()
@@ -623,9 +761,29 @@ impl ResolvedVariable {
 pub(crate) struct UsedTypes {
     pub(crate) types: BTreeSet<TypeId>,
     fragments: BTreeSet<ResolvedFragmentId>,
+    /// The selected fields of type `ID`, whose parents name the typed IDs.
+    pub(crate) id_fields: BTreeSet<StoredFieldId>,
 }
 
 impl UsedTypes {
+    /// Every enum, input object and scalar of the schema.
+    pub(crate) fn schema_types(schema: &Schema) -> UsedTypes {
+        let enums = schema.enums().map(|(id, _)| TypeId::Enum(id));
+        let inputs = schema.inputs().map(|(id, _)| TypeId::Input(id));
+        let scalars = schema.scalars().map(|(id, _)| TypeId::Scalar(id));
+
+        let id_fields = schema
+            .fields()
+            .filter(|(_, field)| field.r#type.id.is_id(schema))
+            .map(|(id, _)| id);
+
+        UsedTypes {
+            types: enums.chain(inputs).chain(scalars).collect(),
+            fragments: BTreeSet::new(),
+            id_fields: id_fields.collect(),
+        }
+    }
+
     pub(crate) fn inputs<'s, 'a: 's>(
         &'s self,
         schema: &'a Schema,
@@ -670,6 +828,11 @@ fn resolve_variables<'doc, T>(
     T: graphql_parser::query::Text<'doc>,
 {
     for var in variables {
+        // Variables with unknown types were reported when validating the document.
+        if !variable_type_is_known(schema, &var.var_type) {
+            continue;
+        }
+
         query.variables.push(ResolvedVariable {
             operation_id,
             name: var.name.as_ref().into(),
@@ -679,6 +842,21 @@ fn resolve_variables<'doc, T>(
     }
 }
 
+fn variable_type_is_known<'doc, T>(
+    schema: &Schema,
+    r#type: &graphql_parser::query::Type<'doc, T>,
+) -> bool
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    use graphql_parser::query::Type;
+
+    match r#type {
+        Type::NamedType(name) => schema.find_type(name.as_ref()).is_some(),
+        Type::ListType(inner) | Type::NonNullType(inner) => variable_type_is_known(schema, inner),
+    }
+}
+
 pub(crate) fn walk_operations(
     query: &Query,
 ) -> impl Iterator<Item = (OperationId, &ResolvedOperation)> {
@@ -6,6 +6,7 @@ use heck::*;
 pub(crate) struct ResolvedFragment {
     pub(crate) name: String,
     pub(crate) on: TypeId,
+    pub(crate) position: graphql_parser::Pos,
     pub(crate) selection_set: Vec<SelectionId>,
 }
 
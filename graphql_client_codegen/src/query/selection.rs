@@ -30,26 +30,26 @@ pub(super) fn validate_type_conditions(
         return Ok(());
     }
 
-    match parent_schema_type_id {
-        TypeId::Union(union_id) => {
-            let union = query.schema.get_union(union_id);
+    let invalid_spread = |message: String| {
+        let error = QueryValidationError::new(message);
 
-            if !union.variants.contains(&selected_type) {
-                return Err(QueryValidationError::new(format!(
-                    "The spread {}... on {} is not valid.",
-                    union.name,
-                    selected_type.name(query.schema)
-                )));
-            }
+        match query.query.selection_position(selection_id) {
+            Some(position) => error.with_position(position),
+            None => error,
         }
-        TypeId::Interface(interface_id) => {
-            let mut variants = query
+    };
+
+    match parent_schema_type_id {
+        TypeId::Object(_) | TypeId::Union(_) | TypeId::Interface(_) => {
+            let parent_possible_types = query.schema.possible_types(parent_schema_type_id);
+            let overlaps = query
                 .schema
-                .objects()
-                .filter(|(_, obj)| obj.implements_interfaces.contains(&interface_id));
+                .possible_types(selected_type)
+                .iter()
+                .any(|id| parent_possible_types.contains(id));
 
-            if !variants.any(|(id, _)| TypeId::Object(id) == selected_type) {
-                return Err(QueryValidationError::new(format!(
+            if !overlaps {
+                return Err(invalid_spread(format!(
                     "The spread {}... on {} is not valid.",
                     parent_schema_type_id.name(query.schema),
                     selected_type.name(query.schema),
@@ -166,6 +166,9 @@ impl Selection {
             Selection::Field(field) => {
                 let stored_field = query.schema.get_field(field.field_id);
                 used_types.types.insert(stored_field.r#type.id);
+                if stored_field.r#type.id.is_id(query.schema) {
+                    used_types.id_fields.insert(field.field_id);
+                }
 
                 for selection_id in self.subselection() {
                     let selection = query.query.get_selection(*selection_id);
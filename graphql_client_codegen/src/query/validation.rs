@@ -1,21 +1,28 @@
-use super::{full_path_prefix, BoundQuery, Query, QueryValidationError, Selection, SelectionId};
-use crate::schema::TypeId;
+mod document;
+
+pub(super) use document::validate_document;
+
+use super::{full_path_prefix, BoundQuery, QueryValidationError, Selection, SelectionId};
+use crate::schema::{Schema, StoredField, TypeId};
+use crate::type_qualifiers::{graphql_type_string, GraphqlTypeQualifier};
+use graphql_parser::query::Value;
 
 pub(super) fn validate_typename_presence(
     query: &BoundQuery<'_>,
-) -> Result<(), QueryValidationError> {
+    errors: &mut Vec<QueryValidationError>,
+) {
     for fragment in query.query.fragments.iter() {
         let type_id = match fragment.on {
             id @ TypeId::Interface(_) | id @ TypeId::Union(_) => id,
             _ => continue,
         };
 
-        if !selection_set_contains_type_name(fragment.on, &fragment.selection_set, query.query) {
-            return Err(QueryValidationError::new(format!(
+        if !selection_set_contains_type_name(fragment.on, &fragment.selection_set, query) {
+            errors.push(QueryValidationError::new(format!(
                 "The `{}` fragment uses `{}` but does not select `__typename` on it. graphql-client cannot generate code for it. Please add `__typename` to the selection.",
                 &fragment.name,
                 type_id.name(query.schema),
-            )));
+            )).with_position(fragment.position));
         }
     }
 
@@ -34,31 +41,36 @@ pub(super) fn validate_typename_presence(
             });
 
     for selection in union_and_interface_field_selections {
-        if !selection_set_contains_type_name(selection.1, selection.2, query.query) {
-            return Err(QueryValidationError::new(format!(
+        if !selection_set_contains_type_name(selection.1, selection.2, query) {
+            let error = QueryValidationError::new(format!(
                 "The query uses `{path}` at `{selected_type}` but does not select `__typename` on it. graphql-client cannot generate code for it. Please add `__typename` to the selection.",
                 path = full_path_prefix(selection.0, query),
                 selected_type = selection.1.name(query.schema)
-            )));
+            ));
+
+            errors.push(match query.query.selection_position(selection.0) {
+                Some(position) => error.with_position(position),
+                None => error,
+            });
         }
     }
-
-    Ok(())
 }
 
 fn selection_set_contains_type_name(
     parent_type_id: TypeId,
     selection_set: &[SelectionId],
-    query: &Query,
+    query: &BoundQuery<'_>,
 ) -> bool {
     for id in selection_set {
-        let selection = query.get_selection(*id);
+        let selection = query.query.get_selection(*id);
 
         match selection {
             Selection::Typename => return true,
             Selection::FragmentSpread(fragment_id) => {
-                let fragment = query.get_fragment(*fragment_id);
-                if fragment.on == parent_type_id
+                let fragment = query.query.get_fragment(*fragment_id);
+                if query
+                    .schema
+                    .type_condition_applies(fragment.on, parent_type_id)
                     && selection_set_contains_type_name(fragment.on, &fragment.selection_set, query)
                 {
                     return true;
@@ -70,3 +82,246 @@ fn selection_set_contains_type_name(
 
     false
 }
+
+/// Checks the arguments passed to a field against the argument definitions in the schema:
+/// unknown arguments, missing required arguments, and literals of the wrong type.
+pub(super) fn validate_field_arguments<'doc, T>(
+    field: &graphql_parser::query::Field<'doc, T>,
+    schema_field: &StoredField,
+    parent_type_name: &str,
+    schema: &Schema,
+) -> Result<(), QueryValidationError>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    for (name, value) in &field.arguments {
+        let argument = schema_field
+            .get_argument_by_name(name.as_ref())
+            .ok_or_else(|| {
+                QueryValidationError::new(format!(
+                    "Unknown argument `{}` on field `{}.{}`.",
+                    name.as_ref(),
+                    parent_type_name,
+                    schema_field.name,
+                ))
+                .with_position(field.position)
+            })?;
+
+        if !literal_matches_type(
+            value,
+            argument.r#type.id,
+            &argument.r#type.qualifiers,
+            schema,
+        ) {
+            return Err(QueryValidationError::new(format!(
+                "Argument `{}` on field `{}.{}` has an invalid value ({}). Expected type `{}`.",
+                argument.name,
+                parent_type_name,
+                schema_field.name,
+                value,
+                graphql_type_string(argument.r#type.id.name(schema), &argument.r#type.qualifiers),
+            ))
+            .with_position(field.position));
+        }
+    }
+
+    for (name, value) in &field.arguments {
+        let argument = match schema_field.get_argument_by_name(name.as_ref()) {
+            Some(argument) => argument,
+            None => continue,
+        };
+        let missing = missing_input_fields(
+            value,
+            argument.r#type.id,
+            &argument.r#type.qualifiers,
+            schema,
+        );
+
+        if !missing.is_empty() {
+            return Err(QueryValidationError::new(format!(
+                "Argument `{}` on field `{}.{}` is missing {}.",
+                argument.name,
+                parent_type_name,
+                schema_field.name,
+                missing_input_fields_list(&missing),
+            ))
+            .with_position(field.position));
+        }
+    }
+
+    let missing_argument = schema_field.arguments.iter().find(|argument| {
+        argument.is_required()
+            && !field
+                .arguments
+                .iter()
+                .any(|(name, _)| name.as_ref() == argument.name)
+    });
+
+    if let Some(argument) = missing_argument {
+        return Err(QueryValidationError::new(format!(
+            "Field `{}.{}` is missing the required argument `{}` of type `{}`.",
+            parent_type_name,
+            schema_field.name,
+            argument.name,
+            graphql_type_string(argument.r#type.id.name(schema), &argument.r#type.qualifiers),
+        ))
+        .with_position(field.position));
+    }
+
+    Ok(())
+}
+
+/// The non-null fields of the input objects in a literal that it leaves out, and that have no
+/// default in the schema, as `Input.field` with their type. Fields of the wrong type are left to
+/// `literal_matches_type`.
+pub(super) fn missing_input_fields<'doc, T>(
+    value: &Value<'doc, T>,
+    type_id: TypeId,
+    qualifiers: &[GraphqlTypeQualifier],
+    schema: &Schema,
+) -> Vec<String>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let mut missing = Vec::new();
+    collect_missing_input_fields(value, type_id, qualifiers, schema, &mut missing);
+    missing
+}
+
+fn collect_missing_input_fields<'doc, T>(
+    value: &Value<'doc, T>,
+    type_id: TypeId,
+    qualifiers: &[GraphqlTypeQualifier],
+    schema: &Schema,
+    missing: &mut Vec<String>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    match (value, qualifiers.split_first()) {
+        (Value::Variable(_), _) | (Value::Null, _) => (),
+        (value, Some((GraphqlTypeQualifier::Required, inner))) => {
+            collect_missing_input_fields(value, type_id, inner, schema, missing)
+        }
+        (Value::List(items), Some((GraphqlTypeQualifier::List, inner))) => {
+            for item in items {
+                collect_missing_input_fields(item, type_id, inner, schema, missing);
+            }
+        }
+        (value, Some((GraphqlTypeQualifier::List, inner))) => {
+            collect_missing_input_fields(value, type_id, inner, schema, missing)
+        }
+        (Value::Object(fields), None) => {
+            let input = match type_id {
+                TypeId::Input(input_id) => schema.get_input(input_id),
+                _ => return,
+            };
+
+            for ((field_name, field_type), default) in
+                input.fields.iter().zip(&input.field_defaults)
+            {
+                match fields.iter().find(|(name, _)| name.as_ref() == field_name) {
+                    Some((_, value)) => collect_missing_input_fields(
+                        value,
+                        field_type.id,
+                        &field_type.qualifiers,
+                        schema,
+                        missing,
+                    ),
+                    None if !field_type.is_optional() && default.is_none() => {
+                        missing.push(format!(
+                            "`{}.{}` of type `{}`",
+                            input.name,
+                            field_name,
+                            graphql_type_string(field_type.id.name(schema), &field_type.qualifiers),
+                        ))
+                    }
+                    None => (),
+                }
+            }
+        }
+        (_, None) => (),
+    }
+}
+
+/// "the required input field `A.b` of type `Int!`", or a list of them.
+pub(super) fn missing_input_fields_list(missing: &[String]) -> String {
+    match missing.split_last() {
+        Some((last, [])) => format!("the required input field {}", last),
+        Some((last, rest)) => format!("the required input fields {} and {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// Whether a literal value can be coerced to the given input type. Variables are accepted here,
+/// since their types are declared separately.
+fn literal_matches_type<'doc, T>(
+    value: &Value<'doc, T>,
+    type_id: TypeId,
+    qualifiers: &[GraphqlTypeQualifier],
+    schema: &Schema,
+) -> bool
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    match (value, qualifiers.split_first()) {
+        (Value::Variable(_), _) => true,
+        (Value::Null, qualifiers) => {
+            !matches!(qualifiers, Some((GraphqlTypeQualifier::Required, _)))
+        }
+        (value, Some((GraphqlTypeQualifier::Required, inner))) => {
+            literal_matches_type(value, type_id, inner, schema)
+        }
+        (Value::List(items), Some((GraphqlTypeQualifier::List, inner))) => items
+            .iter()
+            .all(|item| literal_matches_type(item, type_id, inner, schema)),
+        // A single item is coerced to a list of one.
+        (value, Some((GraphqlTypeQualifier::List, inner))) => {
+            literal_matches_type(value, type_id, inner, schema)
+        }
+        (value, None) => match type_id {
+            TypeId::Scalar(_) => match (type_id.name(schema), value) {
+                ("Int", Value::Int(_))
+                | ("Float", Value::Int(_))
+                | ("Float", Value::Float(_))
+                | ("String", Value::String(_))
+                | ("Boolean", Value::Boolean(_))
+                | ("ID", Value::String(_))
+                | ("ID", Value::Int(_)) => true,
+                ("Int", _) | ("Float", _) | ("String", _) | ("Boolean", _) | ("ID", _) => false,
+                // We don't know how custom scalars are represented.
+                _ => true,
+            },
+            TypeId::Enum(enum_id) => match value {
+                Value::Enum(variant) => schema
+                    .get_enum(enum_id)
+                    .variants
+                    .iter()
+                    .any(|known| known == variant.as_ref()),
+                _ => false,
+            },
+            TypeId::Input(input_id) => match value {
+                Value::Object(fields) => {
+                    let input = schema.get_input(input_id);
+
+                    fields.iter().all(|(name, value)| {
+                        input
+                            .fields
+                            .iter()
+                            .find(|(field_name, _)| field_name == name.as_ref())
+                            .map(|(_, field_type)| {
+                                literal_matches_type(
+                                    value,
+                                    field_type.id,
+                                    &field_type.qualifiers,
+                                    schema,
+                                )
+                            })
+                            .unwrap_or(false)
+                    })
+                }
+                _ => false,
+            },
+            TypeId::Object(_) | TypeId::Interface(_) | TypeId::Union(_) => false,
+        },
+    }
+}
@@ -0,0 +1,1108 @@
+//! Validation rules that apply to the query document as a whole: names, variables, fragments,
+//! directives and field merging. These run before the document is resolved against the schema,
+//! so fields and types that do not exist in the schema are skipped here and reported during
+//! resolution.
+
+use super::{literal_matches_type, missing_input_fields, missing_input_fields_list};
+use crate::query::QueryValidationError;
+use crate::schema::{
+    resolve_field_type, ObjectLike, Schema, StoredField, StoredFieldArgument, StoredFieldType,
+    TypeId,
+};
+use crate::type_qualifiers::{graphql_type_string, GraphqlTypeQualifier};
+use graphql_parser::query::{
+    Definition, Directive, Document, Field, FragmentDefinition, OperationDefinition, Selection,
+    SelectionSet, Text, Type, TypeCondition, Value, VariableDefinition,
+};
+use graphql_parser::schema::DirectiveLocation;
+use graphql_parser::Pos;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub(in crate::query) fn validate_document<'doc, T>(
+    document: &Document<'doc, T>,
+    schema: &Schema,
+    deny_unknown_directives: bool,
+) -> Vec<QueryValidationError>
+where
+    T: Text<'doc>,
+{
+    let context = DocumentContext::new(document, schema, deny_unknown_directives);
+    let mut errors = Vec::new();
+
+    validate_unique_names(
+        "operation",
+        context
+            .operations
+            .iter()
+            .filter_map(|operation| Some((operation.name?, operation.position))),
+        &mut errors,
+    );
+    validate_unique_names(
+        "fragment",
+        context
+            .fragment_definitions()
+            .map(|fragment| (fragment.name.as_ref(), fragment.position)),
+        &mut errors,
+    );
+
+    context.validate_fragment_cycles(&mut errors);
+    context.validate_fragments_are_used(&mut errors);
+
+    for operation in &context.operations {
+        context.validate_variables(operation, &mut errors);
+        context.validate_directives(operation.directives, &operation.location, &mut errors);
+        context.validate_selection_set(operation.root, operation.selection_set, &[], &mut errors);
+    }
+
+    for fragment in context.fragment_definitions() {
+        context.validate_directives(
+            &fragment.directives,
+            &DirectiveLocation::FragmentDefinition,
+            &mut errors,
+        );
+        context.validate_selection_set(
+            context.fragment_type(fragment),
+            &fragment.selection_set,
+            &[fragment.name.as_ref()],
+            &mut errors,
+        );
+    }
+
+    // The same field conflict can be found from several selection sets.
+    let mut seen = BTreeSet::new();
+    errors.retain(|error| {
+        seen.insert((
+            error.message.clone(),
+            error
+                .position
+                .map(|position| (position.line, position.column)),
+        ))
+    });
+
+    errors
+}
+
+struct DocumentContext<'a, 'doc, T: Text<'doc>> {
+    schema: &'a Schema,
+    document: &'a Document<'doc, T>,
+    fragments: BTreeMap<&'a str, &'a FragmentDefinition<'doc, T>>,
+    operations: Vec<Operation<'a, 'doc, T>>,
+    /// Whether directives the schema does not declare are errors, or passed through.
+    deny_unknown_directives: bool,
+}
+
+struct Operation<'a, 'doc, T: Text<'doc>> {
+    name: Option<&'a str>,
+    position: Pos,
+    location: DirectiveLocation,
+    root: Option<TypeId>,
+    variable_definitions: &'a [VariableDefinition<'doc, T>],
+    directives: &'a [Directive<'doc, T>],
+    selection_set: &'a SelectionSet<'doc, T>,
+}
+
+impl<'a, 'doc, T: Text<'doc>> Operation<'a, 'doc, T> {
+    fn display_name(&self) -> &'a str {
+        self.name.unwrap_or("<anonymous>")
+    }
+}
+
+/// A variable used as an argument value, with the type expected at that position when it is
+/// known.
+struct VariableUsage<'a> {
+    name: &'a str,
+    position: Pos,
+    expected: Option<(TypeId, Vec<GraphqlTypeQualifier>)>,
+    location_has_default: bool,
+}
+
+/// A field collected from a selection set, with the fragments it was reached through.
+struct CollectedField<'a, 'doc, T: Text<'doc>> {
+    response_name: &'a str,
+    parent: Option<TypeId>,
+    field: &'a Field<'doc, T>,
+    definition: Option<&'a StoredField>,
+    fragment_path: Vec<&'a str>,
+}
+
+impl<'a, 'doc, T> DocumentContext<'a, 'doc, T>
+where
+    T: Text<'doc>,
+{
+    fn new(
+        document: &'a Document<'doc, T>,
+        schema: &'a Schema,
+        deny_unknown_directives: bool,
+    ) -> Self {
+        let mut fragments = BTreeMap::new();
+        let mut operations = Vec::new();
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::Fragment(fragment) => {
+                    fragments.entry(fragment.name.as_ref()).or_insert(fragment);
+                }
+                Definition::Operation(operation) => {
+                    operations.push(Operation::from_definition(operation, schema))
+                }
+            }
+        }
+
+        DocumentContext {
+            schema,
+            document,
+            fragments,
+            operations,
+            deny_unknown_directives,
+        }
+    }
+
+    fn fragment_definitions(&self) -> impl Iterator<Item = &'a FragmentDefinition<'doc, T>> {
+        self.document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Fragment(fragment) => Some(fragment),
+                Definition::Operation(_) => None,
+            })
+    }
+
+    fn fragment_type(&self, fragment: &FragmentDefinition<'doc, T>) -> Option<TypeId> {
+        let TypeCondition::On(on) = &fragment.type_condition;
+        self.schema.find_type(on.as_ref())
+    }
+
+    /// Fragments may not spread themselves, directly or through other fragments. The one
+    /// exception is a fragment that spreads itself inside one of its fields: the generated type
+    /// is boxed, so the recursion is finite.
+    fn validate_fragment_cycles(&self, errors: &mut Vec<QueryValidationError>) {
+        for fragment in self.fragment_definitions() {
+            let start = fragment.name.as_ref();
+            let mut path = Vec::new();
+            let mut visited = BTreeSet::new();
+
+            if let Err(err) = self.find_fragment_cycle(start, fragment, &mut path, &mut visited) {
+                errors.push(err.with_position(fragment.position));
+            }
+        }
+    }
+
+    fn find_fragment_cycle(
+        &self,
+        start: &str,
+        fragment: &'a FragmentDefinition<'doc, T>,
+        path: &mut Vec<&'a str>,
+        visited: &mut BTreeSet<&'a str>,
+    ) -> Result<(), QueryValidationError> {
+        let mut spreads = Vec::new();
+        collect_fragment_spreads(&fragment.selection_set, false, &mut spreads);
+
+        for (target, nested_in_field) in spreads {
+            if target == start {
+                if path.is_empty() && nested_in_field {
+                    continue;
+                }
+
+                let via = if path.is_empty() {
+                    String::new()
+                } else {
+                    let path: Vec<String> = path.iter().map(|name| format!("`{}`", name)).collect();
+                    format!(" via {}", path.join(", "))
+                };
+
+                return Err(QueryValidationError::new(format!(
+                    "Cannot spread fragment `{}` within itself{}.",
+                    start, via
+                )));
+            }
+
+            if !visited.insert(target) {
+                continue;
+            }
+
+            if let Some(next) = self.fragments.get(target) {
+                path.push(target);
+                self.find_fragment_cycle(start, next, path, visited)?;
+                path.pop();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_fragments_are_used(&self, errors: &mut Vec<QueryValidationError>) {
+        let mut pending = Vec::new();
+        let mut used = BTreeSet::new();
+
+        for operation in &self.operations {
+            collect_fragment_spreads(operation.selection_set, false, &mut pending);
+        }
+
+        while let Some((name, _)) = pending.pop() {
+            if used.insert(name) {
+                if let Some(fragment) = self.fragments.get(name) {
+                    collect_fragment_spreads(&fragment.selection_set, false, &mut pending);
+                }
+            }
+        }
+
+        for fragment in self
+            .fragment_definitions()
+            .filter(|fragment| !used.contains(fragment.name.as_ref()))
+        {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "Fragment `{}` is never used.",
+                    fragment.name.as_ref()
+                ))
+                .with_position(fragment.position),
+            );
+        }
+    }
+
+    /// Variables must be defined once, with an input type, and every variable must be used.
+    /// Variables used inside fragments count as used by the operations spreading them.
+    fn validate_variables(
+        &self,
+        operation: &Operation<'a, 'doc, T>,
+        errors: &mut Vec<QueryValidationError>,
+    ) {
+        validate_unique_names(
+            "variable",
+            operation
+                .variable_definitions
+                .iter()
+                .map(|definition| (definition.name.as_ref(), definition.position)),
+            errors,
+        );
+
+        for definition in operation.variable_definitions {
+            let name = definition.name.as_ref();
+            let type_name = named_type(&definition.var_type);
+
+            let message = match self.schema.find_type(type_name) {
+                Some(TypeId::Scalar(_)) | Some(TypeId::Enum(_)) | Some(TypeId::Input(_)) => {
+                    self.validate_variable_default(definition, errors);
+                    continue;
+                }
+                Some(_) => format!(
+                    "Variable `${}` cannot be of non-input type `{}`.",
+                    name, type_name
+                ),
+                None => format!("Unknown type `{}` for variable `${}`.", type_name, name),
+            };
+
+            errors.push(QueryValidationError::new(message).with_position(definition.position));
+        }
+
+        let mut usages = Vec::new();
+        let mut visited_fragments = BTreeSet::new();
+        self.collect_directive_usages(operation.directives, &mut usages);
+        self.collect_selection_set_usages(
+            operation.root,
+            operation.selection_set,
+            &mut visited_fragments,
+            &mut usages,
+        );
+
+        for usage in &usages {
+            let definition = operation
+                .variable_definitions
+                .iter()
+                .find(|definition| definition.name.as_ref() == usage.name);
+
+            let definition = match definition {
+                Some(definition) => definition,
+                None => {
+                    errors.push(
+                        QueryValidationError::new(format!(
+                            "Variable `${}` is not defined by operation `{}`.",
+                            usage.name,
+                            operation.display_name(),
+                        ))
+                        .with_position(usage.position),
+                    );
+                    continue;
+                }
+            };
+
+            let (expected_id, expected_qualifiers) = match &usage.expected {
+                Some(expected) => expected,
+                None => continue,
+            };
+
+            // Unknown variable types were reported above.
+            if self
+                .schema
+                .find_type(named_type(&definition.var_type))
+                .is_none()
+            {
+                continue;
+            }
+
+            let variable_type = resolve_field_type(self.schema, &definition.var_type);
+            let has_default = matches!(
+                definition.default_value,
+                Some(ref value) if !matches!(value, Value::Null)
+            );
+
+            if !variable_usage_is_allowed(
+                &variable_type,
+                has_default || usage.location_has_default,
+                *expected_id,
+                expected_qualifiers,
+            ) {
+                errors.push(
+                    QueryValidationError::new(format!(
+                        "Variable `${}` of type `{}` is used in a position expecting type `{}`.",
+                        usage.name,
+                        definition.var_type,
+                        graphql_type_string(expected_id.name(self.schema), expected_qualifiers),
+                    ))
+                    .with_position(usage.position),
+                );
+            }
+        }
+
+        for unused in operation.variable_definitions.iter().filter(|definition| {
+            !usages
+                .iter()
+                .any(|usage| usage.name == definition.name.as_ref())
+        }) {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "Variable `${}` is never used in operation `{}`.",
+                    unused.name.as_ref(),
+                    operation.display_name(),
+                ))
+                .with_position(unused.position),
+            );
+        }
+    }
+
+    fn collect_selection_set_usages(
+        &self,
+        parent: Option<TypeId>,
+        selection_set: &'a SelectionSet<'doc, T>,
+        visited_fragments: &mut BTreeSet<&'a str>,
+        usages: &mut Vec<VariableUsage<'a>>,
+    ) {
+        for item in &selection_set.items {
+            match item {
+                Selection::Field(field) => {
+                    let definition =
+                        parent.and_then(|parent| field_definition(self.schema, parent, field));
+
+                    for (name, value) in &field.arguments {
+                        let argument = definition
+                            .and_then(|definition| definition.get_argument_by_name(name.as_ref()));
+                        self.collect_argument_usages(value, argument, field.position, usages);
+                    }
+
+                    self.collect_directive_usages(&field.directives, usages);
+                    self.collect_selection_set_usages(
+                        definition.map(|definition| definition.r#type.id),
+                        &field.selection_set,
+                        visited_fragments,
+                        usages,
+                    );
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    self.collect_directive_usages(&inline_fragment.directives, usages);
+                    self.collect_selection_set_usages(
+                        self.type_condition(parent, inline_fragment.type_condition.as_ref()),
+                        &inline_fragment.selection_set,
+                        visited_fragments,
+                        usages,
+                    );
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    self.collect_directive_usages(&fragment_spread.directives, usages);
+
+                    let name = fragment_spread.fragment_name.as_ref();
+
+                    if !visited_fragments.insert(name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = self.fragments.get(name) {
+                        self.collect_selection_set_usages(
+                            self.fragment_type(fragment),
+                            &fragment.selection_set,
+                            visited_fragments,
+                            usages,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_directive_usages(
+        &self,
+        directives: &'a [Directive<'doc, T>],
+        usages: &mut Vec<VariableUsage<'a>>,
+    ) {
+        for directive in directives {
+            let definition = self.schema.get_directive_by_name(directive.name.as_ref());
+
+            for (name, value) in &directive.arguments {
+                let argument = definition.and_then(|definition| {
+                    definition
+                        .arguments
+                        .iter()
+                        .find(|argument| argument.name == name.as_ref())
+                });
+                self.collect_argument_usages(value, argument, directive.position, usages);
+            }
+        }
+    }
+
+    fn collect_argument_usages(
+        &self,
+        value: &'a Value<'doc, T>,
+        argument: Option<&StoredFieldArgument>,
+        position: Pos,
+        usages: &mut Vec<VariableUsage<'a>>,
+    ) {
+        let expected =
+            argument.map(|argument| (argument.r#type.id, &argument.r#type.qualifiers[..]));
+        let location_has_default = argument
+            .map(|argument| argument.default_value.is_some())
+            .unwrap_or(false);
+
+        self.collect_value_usages(value, expected, location_has_default, position, usages);
+    }
+
+    fn collect_value_usages(
+        &self,
+        value: &'a Value<'doc, T>,
+        expected: Option<(TypeId, &[GraphqlTypeQualifier])>,
+        location_has_default: bool,
+        position: Pos,
+        usages: &mut Vec<VariableUsage<'a>>,
+    ) {
+        match value {
+            Value::Variable(name) => usages.push(VariableUsage {
+                name: name.as_ref(),
+                position,
+                expected: expected.map(|(id, qualifiers)| (id, qualifiers.to_vec())),
+                location_has_default,
+            }),
+            Value::List(items) => {
+                let item_type = expected.and_then(|(id, qualifiers)| {
+                    match strip_required(qualifiers).split_first() {
+                        Some((GraphqlTypeQualifier::List, inner)) => Some((id, inner)),
+                        _ => None,
+                    }
+                });
+
+                for item in items {
+                    self.collect_value_usages(item, item_type, false, position, usages);
+                }
+            }
+            Value::Object(fields) => {
+                let input = expected.and_then(|(id, qualifiers)| {
+                    if strip_required(qualifiers).is_empty() {
+                        id.as_input_id()
+                            .map(|input_id| self.schema.get_input(input_id))
+                    } else {
+                        None
+                    }
+                });
+
+                for (name, value) in fields {
+                    let field_type = input.and_then(|input| {
+                        input
+                            .fields
+                            .iter()
+                            .find(|(field_name, _)| field_name == name.as_ref())
+                            .map(|(_, field_type)| (field_type.id, &field_type.qualifiers[..]))
+                    });
+
+                    self.collect_value_usages(value, field_type, false, position, usages);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn type_condition(
+        &self,
+        parent: Option<TypeId>,
+        type_condition: Option<&TypeCondition<'doc, T>>,
+    ) -> Option<TypeId> {
+        match type_condition {
+            Some(TypeCondition::On(on)) => self.schema.find_type(on.as_ref()),
+            None => parent,
+        }
+    }
+
+    /// Defaults are literals, so the input objects in them must give every required field.
+    fn validate_variable_default(
+        &self,
+        definition: &VariableDefinition<'doc, T>,
+        errors: &mut Vec<QueryValidationError>,
+    ) {
+        let default_value = match &definition.default_value {
+            Some(default_value) => default_value,
+            None => return,
+        };
+        let variable_type = resolve_field_type(self.schema, &definition.var_type);
+        let missing = missing_input_fields(
+            default_value,
+            variable_type.id,
+            &variable_type.qualifiers,
+            self.schema,
+        );
+
+        if !missing.is_empty() {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "The default value of variable `${}` is missing {}.",
+                    definition.name.as_ref(),
+                    missing_input_fields_list(&missing),
+                ))
+                .with_position(definition.position),
+            );
+        }
+    }
+
+    fn validate_directives(
+        &self,
+        directives: &[Directive<'doc, T>],
+        location: &DirectiveLocation,
+        errors: &mut Vec<QueryValidationError>,
+    ) {
+        for directive in directives {
+            if let Err(err) = self.validate_directive(directive, location, errors) {
+                errors.push(err.with_position(directive.position));
+            }
+        }
+    }
+
+    fn validate_directive(
+        &self,
+        directive: &Directive<'doc, T>,
+        location: &DirectiveLocation,
+        errors: &mut Vec<QueryValidationError>,
+    ) -> Result<(), QueryValidationError> {
+        let name = directive.name.as_ref();
+        let definition = match self.schema.get_directive_by_name(name) {
+            Some(definition) => definition,
+            None if self.deny_unknown_directives => {
+                return Err(QueryValidationError::new(format!(
+                    "Unknown directive `@{}`.",
+                    name
+                )))
+            }
+            // Client-only directives, like `@client`, are often not declared in the schema.
+            None => return Ok(()),
+        };
+
+        if !definition.locations.contains(location) {
+            return Err(QueryValidationError::new(format!(
+                "Directive `@{}` may not be used on {}.",
+                name,
+                location.as_str()
+            )));
+        }
+
+        validate_unique_names(
+            "argument",
+            directive
+                .arguments
+                .iter()
+                .map(|(argument_name, _)| (argument_name.as_ref(), directive.position)),
+            errors,
+        );
+
+        for (argument_name, value) in &directive.arguments {
+            let argument = definition
+                .arguments
+                .iter()
+                .find(|argument| argument.name == argument_name.as_ref())
+                .ok_or_else(|| {
+                    QueryValidationError::new(format!(
+                        "Unknown argument `{}` on directive `@{}`.",
+                        argument_name.as_ref(),
+                        name
+                    ))
+                })?;
+
+            if !literal_matches_type(
+                value,
+                argument.r#type.id,
+                &argument.r#type.qualifiers,
+                self.schema,
+            ) {
+                return Err(QueryValidationError::new(format!(
+                    "Argument `{}` on directive `@{}` has an invalid value ({}). Expected type `{}`.",
+                    argument.name,
+                    name,
+                    value,
+                    graphql_type_string(
+                        argument.r#type.id.name(self.schema),
+                        &argument.r#type.qualifiers
+                    ),
+                )));
+            }
+
+            let missing = missing_input_fields(
+                value,
+                argument.r#type.id,
+                &argument.r#type.qualifiers,
+                self.schema,
+            );
+            if !missing.is_empty() {
+                return Err(QueryValidationError::new(format!(
+                    "Argument `{}` on directive `@{}` is missing {}.",
+                    argument.name,
+                    name,
+                    missing_input_fields_list(&missing),
+                )));
+            }
+        }
+
+        let missing_argument = definition.arguments.iter().find(|argument| {
+            argument.is_required()
+                && !directive
+                    .arguments
+                    .iter()
+                    .any(|(argument_name, _)| argument_name.as_ref() == argument.name)
+        });
+
+        if let Some(argument) = missing_argument {
+            return Err(QueryValidationError::new(format!(
+                "Directive `@{}` is missing the required argument `{}` of type `{}`.",
+                name,
+                argument.name,
+                graphql_type_string(
+                    argument.r#type.id.name(self.schema),
+                    &argument.r#type.qualifiers
+                ),
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks argument names, directives and field merging in a selection set and, recursively,
+    /// in the selection sets it contains.
+    fn validate_selection_set(
+        &self,
+        parent: Option<TypeId>,
+        selection_set: &'a SelectionSet<'doc, T>,
+        fragment_path: &[&'a str],
+        errors: &mut Vec<QueryValidationError>,
+    ) {
+        let mut fields = Vec::new();
+        self.collect_fields(parent, selection_set, fragment_path, &mut fields);
+        self.validate_fields_can_merge(&fields, false, errors);
+
+        for item in &selection_set.items {
+            match item {
+                Selection::Field(field) => {
+                    validate_unique_names(
+                        "argument",
+                        field
+                            .arguments
+                            .iter()
+                            .map(|(name, _)| (name.as_ref(), field.position)),
+                        errors,
+                    );
+                    self.validate_directives(&field.directives, &DirectiveLocation::Field, errors);
+
+                    let field_type = parent
+                        .and_then(|parent| field_definition(self.schema, parent, field))
+                        .map(|definition| definition.r#type.id);
+                    self.validate_selection_set(
+                        field_type,
+                        &field.selection_set,
+                        fragment_path,
+                        errors,
+                    );
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    self.validate_directives(
+                        &inline_fragment.directives,
+                        &DirectiveLocation::InlineFragment,
+                        errors,
+                    );
+                    self.validate_selection_set(
+                        self.type_condition(parent, inline_fragment.type_condition.as_ref()),
+                        &inline_fragment.selection_set,
+                        fragment_path,
+                        errors,
+                    );
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    self.validate_directives(
+                        &fragment_spread.directives,
+                        &DirectiveLocation::FragmentSpread,
+                        errors,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Collects the fields of a selection set, expanding fragments. Fragments that are already
+    /// being expanded are skipped, so recursive fragments terminate.
+    fn collect_fields(
+        &self,
+        parent: Option<TypeId>,
+        selection_set: &'a SelectionSet<'doc, T>,
+        fragment_path: &[&'a str],
+        fields: &mut Vec<CollectedField<'a, 'doc, T>>,
+    ) {
+        for item in &selection_set.items {
+            match item {
+                Selection::Field(field) => fields.push(CollectedField {
+                    response_name: field.alias.as_ref().unwrap_or(&field.name).as_ref(),
+                    parent,
+                    field,
+                    definition: parent
+                        .and_then(|parent| field_definition(self.schema, parent, field)),
+                    fragment_path: fragment_path.to_vec(),
+                }),
+                Selection::InlineFragment(inline_fragment) => self.collect_fields(
+                    self.type_condition(parent, inline_fragment.type_condition.as_ref()),
+                    &inline_fragment.selection_set,
+                    fragment_path,
+                    fields,
+                ),
+                Selection::FragmentSpread(fragment_spread) => {
+                    let name = fragment_spread.fragment_name.as_ref();
+
+                    if fragment_path.contains(&name) {
+                        continue;
+                    }
+
+                    if let Some(fragment) = self.fragments.get(name) {
+                        let mut fragment_path = fragment_path.to_vec();
+                        fragment_path.push(name);
+
+                        self.collect_fields(
+                            self.fragment_type(fragment),
+                            &fragment.selection_set,
+                            &fragment_path,
+                            fields,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fields with the same response name must be mergeable: same field and arguments (unless
+    /// they can never apply to the same object), and the same response shape.
+    fn validate_fields_can_merge(
+        &self,
+        fields: &[CollectedField<'a, 'doc, T>],
+        parents_are_exclusive: bool,
+        errors: &mut Vec<QueryValidationError>,
+    ) {
+        for (idx, first) in fields.iter().enumerate() {
+            for second in fields[idx + 1..]
+                .iter()
+                .filter(|second| second.response_name == first.response_name)
+            {
+                if let Err(err) =
+                    self.validate_field_pair(first, second, parents_are_exclusive, errors)
+                {
+                    errors.push(err.with_position(second.field.position));
+                }
+            }
+        }
+    }
+
+    fn validate_field_pair(
+        &self,
+        first: &CollectedField<'a, 'doc, T>,
+        second: &CollectedField<'a, 'doc, T>,
+        parents_are_exclusive: bool,
+        errors: &mut Vec<QueryValidationError>,
+    ) -> Result<(), QueryValidationError> {
+        let response_name = first.response_name;
+        let parents_are_exclusive = parents_are_exclusive
+            || (first.parent != second.parent
+                && matches!(first.parent, Some(TypeId::Object(_)))
+                && matches!(second.parent, Some(TypeId::Object(_))));
+
+        if !parents_are_exclusive {
+            if first.field.name != second.field.name {
+                return Err(QueryValidationError::new(format!(
+                    "Fields `{}` conflict because `{}` and `{}` are different fields. Use different aliases on the fields to fetch both if this was intentional.",
+                    response_name,
+                    first.field.name.as_ref(),
+                    second.field.name.as_ref(),
+                )));
+            }
+
+            if !same_arguments(first.field, second.field) {
+                return Err(QueryValidationError::new(format!(
+                    "Fields `{}` conflict because they have differing arguments. Use different aliases on the fields to fetch both if this was intentional.",
+                    response_name,
+                )));
+            }
+        }
+
+        if let (Some(first_definition), Some(second_definition)) =
+            (first.definition, second.definition)
+        {
+            if !same_response_shape(&first_definition.r#type, &second_definition.r#type) {
+                return Err(QueryValidationError::new(format!(
+                    "Fields `{}` conflict because they return conflicting types `{}` and `{}`. Use different aliases on the fields to fetch both if this was intentional.",
+                    response_name,
+                    stored_type_string(&first_definition.r#type, self.schema),
+                    stored_type_string(&second_definition.r#type, self.schema),
+                )));
+            }
+        }
+
+        if first.field.selection_set.items.is_empty() || second.field.selection_set.items.is_empty()
+        {
+            return Ok(());
+        }
+
+        let mut subfields = Vec::new();
+
+        for field in &[first, second] {
+            self.collect_fields(
+                field.definition.map(|definition| definition.r#type.id),
+                &field.field.selection_set,
+                &field.fragment_path,
+                &mut subfields,
+            );
+        }
+
+        self.validate_fields_can_merge(&subfields, parents_are_exclusive, errors);
+
+        Ok(())
+    }
+}
+
+impl<'a, 'doc, T> Operation<'a, 'doc, T>
+where
+    T: Text<'doc>,
+{
+    fn from_definition(definition: &'a OperationDefinition<'doc, T>, schema: &Schema) -> Self {
+        let (name, position, location, root, variable_definitions, directives, selection_set) =
+            match definition {
+                OperationDefinition::Query(query) => (
+                    query.name.as_ref(),
+                    query.position,
+                    DirectiveLocation::Query,
+                    schema.query_type,
+                    &query.variable_definitions[..],
+                    &query.directives[..],
+                    &query.selection_set,
+                ),
+                OperationDefinition::Mutation(mutation) => (
+                    mutation.name.as_ref(),
+                    mutation.position,
+                    DirectiveLocation::Mutation,
+                    schema.mutation_type(),
+                    &mutation.variable_definitions[..],
+                    &mutation.directives[..],
+                    &mutation.selection_set,
+                ),
+                OperationDefinition::Subscription(subscription) => (
+                    subscription.name.as_ref(),
+                    subscription.position,
+                    DirectiveLocation::Subscription,
+                    schema.subscription_type(),
+                    &subscription.variable_definitions[..],
+                    &subscription.directives[..],
+                    &subscription.selection_set,
+                ),
+                OperationDefinition::SelectionSet(selection_set) => (
+                    None,
+                    selection_set.span.0,
+                    DirectiveLocation::Query,
+                    schema.query_type,
+                    &[][..],
+                    &[][..],
+                    selection_set,
+                ),
+            };
+
+        Operation {
+            name: name.map(|name| name.as_ref()),
+            position,
+            location,
+            root: root.map(TypeId::Object),
+            variable_definitions,
+            directives,
+            selection_set,
+        }
+    }
+}
+
+fn validate_unique_names<'a>(
+    kind: &str,
+    names: impl Iterator<Item = (&'a str, Pos)>,
+    errors: &mut Vec<QueryValidationError>,
+) {
+    let mut seen = BTreeSet::new();
+
+    for (name, position) in names {
+        if !seen.insert(name) {
+            errors.push(
+                QueryValidationError::new(format!(
+                    "There can be only one {} named `{}`.",
+                    kind, name
+                ))
+                .with_position(position),
+            );
+        }
+    }
+}
+
+/// Collects the names of the fragments spread in a selection set, and whether each spread is
+/// nested inside a field.
+fn collect_fragment_spreads<'a, 'doc, T>(
+    selection_set: &'a SelectionSet<'doc, T>,
+    nested_in_field: bool,
+    spreads: &mut Vec<(&'a str, bool)>,
+) where
+    T: Text<'doc>,
+{
+    for item in &selection_set.items {
+        match item {
+            Selection::Field(field) => {
+                collect_fragment_spreads(&field.selection_set, true, spreads)
+            }
+            Selection::InlineFragment(inline_fragment) => {
+                collect_fragment_spreads(&inline_fragment.selection_set, nested_in_field, spreads)
+            }
+            Selection::FragmentSpread(fragment_spread) => {
+                spreads.push((fragment_spread.fragment_name.as_ref(), nested_in_field))
+            }
+        }
+    }
+}
+
+fn field_definition<'a, 'doc, T>(
+    schema: &'a Schema,
+    parent: TypeId,
+    field: &Field<'doc, T>,
+) -> Option<&'a StoredField>
+where
+    T: Text<'doc>,
+{
+    let name = field.name.as_ref();
+
+    let field = match parent {
+        TypeId::Object(object_id) => schema.get_object(object_id).get_field_by_name(name, schema),
+        TypeId::Interface(interface_id) => schema
+            .get_interface(interface_id)
+            .get_field_by_name(name, schema),
+        _ => None,
+    };
+
+    field.map(|(_, field)| field)
+}
+
+fn named_type<'a, 'doc, T>(r#type: &'a Type<'doc, T>) -> &'a str
+where
+    T: Text<'doc>,
+{
+    match r#type {
+        Type::NamedType(name) => name.as_ref(),
+        Type::ListType(inner) | Type::NonNullType(inner) => named_type(inner),
+    }
+}
+
+fn same_arguments<'doc, T>(first: &Field<'doc, T>, second: &Field<'doc, T>) -> bool
+where
+    T: Text<'doc>,
+{
+    first.arguments.len() == second.arguments.len()
+        && first.arguments.iter().all(|(name, value)| {
+            second.arguments.iter().any(|(other_name, other_value)| {
+                other_name == name && other_value.to_string() == value.to_string()
+            })
+        })
+}
+
+/// Lists and nullability must match exactly, and leaf types must be the same.
+fn same_response_shape(first: &StoredFieldType, second: &StoredFieldType) -> bool {
+    let is_leaf = |id: TypeId| matches!(id, TypeId::Scalar(_) | TypeId::Enum(_));
+
+    first.qualifiers == second.qualifiers
+        && (!(is_leaf(first.id) || is_leaf(second.id)) || first.id == second.id)
+}
+
+fn stored_type_string(r#type: &StoredFieldType, schema: &Schema) -> String {
+    graphql_type_string(r#type.id.name(schema), &r#type.qualifiers)
+}
+
+fn strip_required(qualifiers: &[GraphqlTypeQualifier]) -> &[GraphqlTypeQualifier] {
+    match qualifiers.split_first() {
+        Some((GraphqlTypeQualifier::Required, inner)) => inner,
+        _ => qualifiers,
+    }
+}
+
+/// A nullable variable may only be used in a non-null position when a default value applies.
+fn variable_usage_is_allowed(
+    variable_type: &StoredFieldType,
+    has_default: bool,
+    expected_id: TypeId,
+    expected_qualifiers: &[GraphqlTypeQualifier],
+) -> bool {
+    let variable_is_nullable = !matches!(
+        variable_type.qualifiers.first(),
+        Some(GraphqlTypeQualifier::Required)
+    );
+
+    let expected_qualifiers = match expected_qualifiers.split_first() {
+        Some((GraphqlTypeQualifier::Required, inner)) if variable_is_nullable => {
+            if !has_default {
+                return false;
+            }
+
+            inner
+        }
+        _ => expected_qualifiers,
+    };
+
+    types_are_compatible(
+        &variable_type.qualifiers,
+        variable_type.id,
+        expected_qualifiers,
+        expected_id,
+    )
+}
+
+fn types_are_compatible(
+    variable_qualifiers: &[GraphqlTypeQualifier],
+    variable_id: TypeId,
+    expected_qualifiers: &[GraphqlTypeQualifier],
+    expected_id: TypeId,
+) -> bool {
+    use GraphqlTypeQualifier::{List, Required};
+
+    match (
+        variable_qualifiers.split_first(),
+        expected_qualifiers.split_first(),
+    ) {
+        (Some((Required, variable_inner)), Some((Required, expected_inner))) => {
+            types_are_compatible(variable_inner, variable_id, expected_inner, expected_id)
+        }
+        (_, Some((Required, _))) => false,
+        (Some((Required, variable_inner)), _) => types_are_compatible(
+            variable_inner,
+            variable_id,
+            expected_qualifiers,
+            expected_id,
+        ),
+        (Some((List, variable_inner)), Some((List, expected_inner))) => {
+            types_are_compatible(variable_inner, variable_id, expected_inner, expected_id)
+        }
+        (Some((List, _)), _) | (_, Some((List, _))) => false,
+        (None, None) => variable_id == expected_id,
+    }
+}
@@ -4,8 +4,11 @@ mod json_conversion;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use graphql_parser_conversion::{type_extension_errors, undefined_type_references};
+
 use crate::query::UsedTypes;
 use crate::type_qualifiers::GraphqlTypeQualifier;
+use graphql_parser::schema::DirectiveLocation;
 use std::collections::{BTreeMap, BTreeSet};
 
 pub(crate) const DEFAULT_SCALARS: &[&str] = &["ID", "String", "Int", "Float", "Boolean"];
@@ -15,6 +18,7 @@ pub(crate) struct StoredObject {
     pub(crate) name: String,
     pub(crate) fields: Vec<StoredFieldId>,
     pub(crate) implements_interfaces: Vec<InterfaceId>,
+    pub(crate) description: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -24,12 +28,39 @@ pub(crate) struct StoredField {
     pub(crate) parent: StoredFieldParent,
     /// `Some(None)` should be interpreted as "deprecated, without reason"
     pub(crate) deprecation: Option<Option<String>>,
+    pub(crate) arguments: Vec<StoredFieldArgument>,
+    pub(crate) description: Option<String>,
 }
 
 impl StoredField {
     pub(crate) fn deprecation(&self) -> Option<Option<&str>> {
         self.deprecation.as_ref().map(|inner| inner.as_deref())
     }
+
+    pub(crate) fn get_argument_by_name(&self, name: &str) -> Option<&StoredFieldArgument> {
+        self.arguments.iter().find(|argument| argument.name == name)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct StoredFieldArgument {
+    pub(crate) name: String,
+    pub(crate) r#type: StoredInputFieldType,
+    pub(crate) default_value: Option<graphql_parser::query::Value<'static, String>>,
+}
+
+impl StoredFieldArgument {
+    /// Non-null arguments without a default value must be provided.
+    pub(crate) fn is_required(&self) -> bool {
+        !self.r#type.is_optional() && self.default_value.is_none()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct StoredDirective {
+    pub(crate) name: String,
+    pub(crate) locations: Vec<DirectiveLocation>,
+    pub(crate) arguments: Vec<StoredFieldArgument>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -38,6 +69,15 @@ pub(crate) enum StoredFieldParent {
     Interface(InterfaceId),
 }
 
+impl StoredFieldParent {
+    pub(crate) fn name<'a>(&self, schema: &'a Schema) -> &'a str {
+        match self {
+            StoredFieldParent::Object(id) => TypeId::Object(*id).name(schema),
+            StoredFieldParent::Interface(id) => TypeId::Interface(*id).name(schema),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, PartialOrd, Ord)]
 pub(crate) struct ObjectId(u32);
 
@@ -56,13 +96,15 @@ pub(crate) struct EnumId(usize);
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, PartialOrd, Ord)]
 pub(crate) struct InputId(u32);
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) struct StoredFieldId(usize);
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct StoredInterface {
     name: String,
     fields: Vec<StoredFieldId>,
+    implements_interfaces: Vec<InterfaceId>,
+    description: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -79,11 +121,14 @@ pub(crate) struct StoredFieldType {
 pub(crate) struct StoredUnion {
     pub(crate) name: String,
     pub(crate) variants: Vec<TypeId>,
+    pub(crate) description: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct StoredScalar {
     pub(crate) name: String,
+    /// The `@specifiedBy` URL of the scalar, or its `specifiedByURL` in introspection.
+    pub(crate) specified_by_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, PartialOrd, Ord)]
@@ -131,6 +176,13 @@ impl TypeId {
         }
     }
 
+    fn as_union_id(&self) -> Option<UnionId> {
+        match self {
+            TypeId::Union(id) => Some(*id),
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_input_id(&self) -> Option<InputId> {
         match self {
             TypeId::Input(id) => Some(*id),
@@ -162,12 +214,34 @@ impl TypeId {
             TypeId::Input(s) => schema.get_input(*s).name.as_str(),
         }
     }
+
+    /// Whether this is the built-in `ID` scalar.
+    pub(crate) fn is_id(&self, schema: &Schema) -> bool {
+        match self {
+            TypeId::Scalar(id) => schema.get_scalar(*id).name == "ID",
+            _ => false,
+        }
+    }
+
+    pub(crate) fn description<'a>(&self, schema: &'a Schema) -> Option<&'a str> {
+        match self {
+            TypeId::Object(obj) => schema.get_object(*obj).description.as_deref(),
+            TypeId::Scalar(_) => None,
+            TypeId::Interface(s) => schema.get_interface(*s).description.as_deref(),
+            TypeId::Union(s) => schema.get_union(*s).description.as_deref(),
+            TypeId::Enum(s) => schema.get_enum(*s).description.as_deref(),
+            TypeId::Input(s) => schema.get_input(*s).description.as_deref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct StoredEnum {
     pub(crate) name: String,
     pub(crate) variants: Vec<String>,
+    pub(crate) description: Option<String>,
+    /// The description of each variant, in the same order as `variants`.
+    pub(crate) variant_descriptions: Vec<Option<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -199,6 +273,11 @@ pub(crate) struct StoredInputType {
     pub(crate) name: String,
     pub(crate) fields: Vec<(String, StoredInputFieldType)>,
     pub(crate) is_one_of: bool,
+    pub(crate) description: Option<String>,
+    /// The description of each field, in the same order as `fields`.
+    pub(crate) field_descriptions: Vec<Option<String>>,
+    /// The default value of each field declared in the schema, in the same order as `fields`.
+    pub(crate) field_defaults: Vec<Option<graphql_parser::query::Value<'static, String>>>,
 }
 
 /// Intermediate representation for a parsed GraphQL schema used during code generation.
@@ -211,6 +290,7 @@ pub(crate) struct Schema {
     stored_scalars: Vec<StoredScalar>,
     stored_enums: Vec<StoredEnum>,
     stored_inputs: Vec<StoredInputType>,
+    stored_directives: Vec<StoredDirective>,
     names: BTreeMap<String, TypeId>,
 
     pub(crate) query_type: Option<ObjectId>,
@@ -228,6 +308,7 @@ impl Schema {
             stored_scalars: Vec::with_capacity(DEFAULT_SCALARS.len()),
             stored_enums: Vec::new(),
             stored_inputs: Vec::new(),
+            stored_directives: Vec::new(),
             names: BTreeMap::new(),
             query_type: None,
             mutation_type: None,
@@ -235,6 +316,7 @@ impl Schema {
         };
 
         schema.push_default_scalars();
+        schema.push_default_directives();
 
         schema
     }
@@ -243,12 +325,44 @@ impl Schema {
         for scalar in DEFAULT_SCALARS {
             let id = self.push_scalar(StoredScalar {
                 name: (*scalar).to_owned(),
+                specified_by_url: None,
             });
 
             self.names.insert((*scalar).to_owned(), TypeId::Scalar(id));
         }
     }
 
+    /// `@skip` and `@include` are always available in executable documents.
+    fn push_default_directives(&mut self) {
+        let boolean_id = self.find_type_id("Boolean");
+
+        for name in &["skip", "include"] {
+            self.push_directive(StoredDirective {
+                name: (*name).to_owned(),
+                locations: vec![
+                    DirectiveLocation::Field,
+                    DirectiveLocation::FragmentSpread,
+                    DirectiveLocation::InlineFragment,
+                ],
+                arguments: vec![StoredFieldArgument {
+                    name: "if".to_owned(),
+                    r#type: StoredInputFieldType {
+                        id: boolean_id,
+                        qualifiers: vec![GraphqlTypeQualifier::Required],
+                    },
+                    default_value: None,
+                }],
+            });
+        }
+    }
+
+    /// Directives defined in the schema replace the built-in definitions with the same name.
+    fn push_directive(&mut self, directive: StoredDirective) {
+        self.stored_directives
+            .retain(|existing| existing.name != directive.name);
+        self.stored_directives.push(directive);
+    }
+
     fn push_object(&mut self, object: StoredObject) -> ObjectId {
         let id = ObjectId(self.stored_objects.len() as u32);
         self.stored_objects.push(object);
@@ -339,6 +453,12 @@ impl Schema {
             .expect("Schema::get_union")
     }
 
+    pub(crate) fn get_directive_by_name(&self, name: &str) -> Option<&StoredDirective> {
+        self.stored_directives
+            .iter()
+            .find(|directive| directive.name == name)
+    }
+
     fn find_interface(&self, interface_name: &str) -> InterfaceId {
         self.find_type_id(interface_name).as_interface_id().unwrap()
     }
@@ -354,6 +474,28 @@ impl Schema {
             .map(|(idx, obj)| (ObjectId(idx as u32), obj))
     }
 
+    /// The fields of every object and interface.
+    pub(crate) fn fields(&self) -> impl Iterator<Item = (StoredFieldId, &StoredField)> {
+        self.stored_fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| (StoredFieldId(idx), field))
+    }
+
+    pub(crate) fn enums(&self) -> impl Iterator<Item = (EnumId, &StoredEnum)> {
+        self.stored_enums
+            .iter()
+            .enumerate()
+            .map(|(idx, enm)| (EnumId(idx), enm))
+    }
+
+    pub(crate) fn scalars(&self) -> impl Iterator<Item = (ScalarId, &StoredScalar)> {
+        self.stored_scalars
+            .iter()
+            .enumerate()
+            .map(|(idx, scalar)| (ScalarId(idx), scalar))
+    }
+
     pub(crate) fn inputs(&self) -> impl Iterator<Item = (InputId, &StoredInputType)> {
         self.stored_inputs
             .iter()
@@ -361,6 +503,62 @@ impl Schema {
             .map(|(idx, obj)| (InputId(idx as u32), obj))
     }
 
+    /// Whether `type_id` implements `interface_id`, either directly or through the interfaces it
+    /// implements.
+    pub(crate) fn implements_interface(&self, type_id: TypeId, interface_id: InterfaceId) -> bool {
+        let mut pending: Vec<InterfaceId> = match type_id {
+            TypeId::Object(object_id) => self.get_object(object_id).implements_interfaces.clone(),
+            TypeId::Interface(id) => self.get_interface(id).implements_interfaces.clone(),
+            _ => return false,
+        };
+        let mut visited = BTreeSet::new();
+
+        while let Some(id) = pending.pop() {
+            if id == interface_id {
+                return true;
+            }
+
+            if visited.insert(id) {
+                pending.extend(self.get_interface(id).implements_interfaces.iter().copied());
+            }
+        }
+
+        false
+    }
+
+    /// The object types a value of type `type_id` can have at runtime.
+    pub(crate) fn possible_types(&self, type_id: TypeId) -> Vec<ObjectId> {
+        match type_id {
+            TypeId::Object(object_id) => vec![object_id],
+            TypeId::Interface(interface_id) => self
+                .objects()
+                .filter(|(id, _)| self.implements_interface(TypeId::Object(*id), interface_id))
+                .map(|(id, _)| id)
+                .collect(),
+            TypeId::Union(union_id) => self
+                .get_union(union_id)
+                .variants
+                .iter()
+                .filter_map(|variant| variant.as_object_id())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a fragment with the type condition `condition` applies to values of type
+    /// `type_id`.
+    pub(crate) fn type_condition_applies(&self, condition: TypeId, type_id: TypeId) -> bool {
+        if condition == type_id {
+            return true;
+        }
+
+        match condition {
+            TypeId::Interface(interface_id) => self.implements_interface(type_id, interface_id),
+            TypeId::Union(union_id) => self.get_union(union_id).variants.contains(&type_id),
+            _ => false,
+        }
+    }
+
     fn find_type_id(&self, type_name: &str) -> TypeId {
         match self.names.get(type_name) {
             Some(id) => *id,
@@ -1,8 +1,10 @@
-use super::{Schema, StoredInputFieldType, TypeId};
+use super::{Schema, StoredDirective, StoredFieldArgument, StoredInputFieldType, TypeId};
 use crate::schema::resolve_field_type;
 use graphql_parser::schema::{
     self as parser, Definition, Document, TypeDefinition, TypeExtension, UnionType,
 };
+use graphql_parser::Pos;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub(super) fn build_schema<'doc, T>(
     mut src: graphql_parser::schema::Document<'doc, T>,
@@ -38,11 +40,19 @@ where
     interfaces_mut(src).for_each(|iface| ingest_interface(schema, iface));
 
     objects_mut(src).for_each(|obj| ingest_object(schema, obj));
-    extend_object_type_extensions_mut(src)
-        .for_each(|ext| ingest_object_type_extension(schema, ext));
 
     inputs_mut(src).for_each(|input| ingest_input(schema, input));
 
+    type_extensions_mut(src).for_each(|ext| ingest_type_extension(schema, ext));
+
+    src.definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::DirectiveDefinition(directive) => Some(directive),
+            _ => None,
+        })
+        .for_each(|directive| ingest_directive(schema, directive));
+
     let schema_definition = src.definitions.iter_mut().find_map(|def| match def {
         Definition::SchemaDefinition(definition) => Some(definition),
         _ => None,
@@ -162,6 +172,7 @@ where
             .iter()
             .map(|name| schema.find_type_id(name.as_ref()))
             .collect(),
+        description: union.description.clone(),
     };
 
     schema.stored_unions.push(stored_union);
@@ -185,6 +196,8 @@ fn ingest_object<'doc, T>(
             r#type: resolve_field_type(schema, &field.field_type),
             parent: super::StoredFieldParent::Object(object_id),
             deprecation: find_deprecation(&field.directives),
+            arguments: ingest_arguments(schema, &field.arguments),
+            description: field.description.clone(),
         };
 
         field_ids.push(schema.push_field(field));
@@ -199,6 +212,7 @@ fn ingest_object<'doc, T>(
             .iter()
             .map(|iface_name| schema.find_interface(iface_name.as_ref()))
             .collect(),
+        description: obj.description.clone(),
     };
 
     schema.push_object(object);
@@ -222,6 +236,8 @@ fn ingest_object_type_extension<'doc, T>(
             r#type: resolve_field_type(schema, &field.field_type),
             parent: super::StoredFieldParent::Object(object_id),
             deprecation: find_deprecation(&field.directives),
+            arguments: ingest_arguments(schema, &field.arguments),
+            description: field.description.clone(),
         };
 
         field_ids.push(schema.push_field(field));
@@ -238,6 +254,133 @@ fn ingest_object_type_extension<'doc, T>(
     object.fields.extend(field_ids);
 }
 
+fn ingest_interface_type_extension<'doc, T>(
+    schema: &mut Schema,
+    ext: &mut parser::InterfaceTypeExtension<'doc, T>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let interface_id = schema
+        .find_type_id(ext.name.as_ref())
+        .as_interface_id()
+        .unwrap();
+    let mut field_ids = Vec::with_capacity(ext.fields.len());
+
+    for field in ext.fields.iter_mut() {
+        let field = super::StoredField {
+            name: field.name.as_ref().into(),
+            r#type: resolve_field_type(schema, &field.field_type),
+            parent: super::StoredFieldParent::Interface(interface_id),
+            deprecation: find_deprecation(&field.directives),
+            arguments: ingest_arguments(schema, &field.arguments),
+            description: field.description.clone(),
+        };
+
+        field_ids.push(schema.push_field(field));
+    }
+
+    let iface_ids = ext
+        .implements_interfaces
+        .iter()
+        .map(|iface_name| schema.find_interface(iface_name.as_ref()))
+        .collect::<Vec<_>>();
+
+    let interface = &mut schema.stored_interfaces[interface_id.0];
+    interface.implements_interfaces.extend(iface_ids);
+    interface.fields.extend(field_ids);
+}
+
+fn ingest_union_type_extension<'doc, T>(
+    schema: &mut Schema,
+    ext: &mut parser::UnionTypeExtension<'doc, T>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let union_id = schema
+        .find_type_id(ext.name.as_ref())
+        .as_union_id()
+        .unwrap();
+    let variants: Vec<TypeId> = ext
+        .types
+        .iter()
+        .map(|name| schema.find_type_id(name.as_ref()))
+        .collect();
+
+    schema.stored_unions[union_id.0].variants.extend(variants);
+}
+
+fn ingest_enum_type_extension<'doc, T>(
+    schema: &mut Schema,
+    ext: &mut parser::EnumTypeExtension<'doc, T>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let enum_id = schema.find_type_id(ext.name.as_ref()).as_enum_id().unwrap();
+    let enm = &mut schema.stored_enums[enum_id.0];
+
+    for value in &ext.values {
+        enm.variants.push(value.name.as_ref().into());
+        enm.variant_descriptions.push(value.description.clone());
+    }
+}
+
+fn ingest_input_type_extension<'doc, T>(
+    schema: &mut Schema,
+    ext: &mut parser::InputObjectTypeExtension<'doc, T>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let input_id = schema
+        .find_type_id(ext.name.as_ref())
+        .as_input_id()
+        .unwrap();
+    let fields: Vec<_> = ext
+        .fields
+        .iter()
+        .map(|field| {
+            let field_type = super::resolve_field_type(schema, &field.value_type);
+            (
+                field.name.as_ref().into(),
+                StoredInputFieldType {
+                    qualifiers: field_type.qualifiers,
+                    id: field_type.id,
+                },
+            )
+        })
+        .collect();
+    let is_one_of = ext
+        .directives
+        .iter()
+        .any(|directive| directive.name.as_ref() == "oneOf");
+
+    let input = &mut schema.stored_inputs[input_id.0 as usize];
+    input.fields.extend(fields);
+    input
+        .field_descriptions
+        .extend(ext.fields.iter().map(|field| field.description.clone()));
+    input.field_defaults.extend(ext.fields.iter().map(|field| {
+        field
+            .default_value
+            .as_ref()
+            .map(|value| value.into_static())
+    }));
+    input.is_one_of |= is_one_of;
+}
+
+fn ingest_type_extension<'doc, T>(schema: &mut Schema, ext: &mut TypeExtension<'doc, T>)
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    match ext {
+        TypeExtension::Object(ext) => ingest_object_type_extension(schema, ext),
+        TypeExtension::Interface(ext) => ingest_interface_type_extension(schema, ext),
+        TypeExtension::Union(ext) => ingest_union_type_extension(schema, ext),
+        TypeExtension::Enum(ext) => ingest_enum_type_extension(schema, ext),
+        TypeExtension::InputObject(ext) => ingest_input_type_extension(schema, ext),
+        TypeExtension::Scalar(ext) => ingest_scalar_type_extension(schema, ext),
+    }
+}
+
 fn ingest_scalar<'doc, T>(
     schema: &mut Schema,
     scalar: &mut graphql_parser::schema::ScalarType<'doc, T>,
@@ -247,7 +390,10 @@ fn ingest_scalar<'doc, T>(
     let name: String = scalar.name.as_ref().into();
     let name_for_names = name.clone();
 
-    let scalar = super::StoredScalar { name };
+    let scalar = super::StoredScalar {
+        name,
+        specified_by_url: find_specified_by_url(&scalar.directives),
+    };
 
     let scalar_id = schema.push_scalar(scalar);
 
@@ -267,6 +413,12 @@ where
             .iter_mut()
             .map(|value| value.name.as_ref().into())
             .collect(),
+        description: enm.description.clone(),
+        variant_descriptions: enm
+            .values
+            .iter()
+            .map(|value| value.description.clone())
+            .collect(),
     };
 
     schema.push_enum(enm);
@@ -291,6 +443,8 @@ fn ingest_interface<'doc, T>(
             r#type: resolve_field_type(schema, &field.field_type),
             parent: super::StoredFieldParent::Interface(interface_id),
             deprecation: find_deprecation(&field.directives),
+            arguments: ingest_arguments(schema, &field.arguments),
+            description: field.description.clone(),
         };
 
         field_ids.push(schema.push_field(field));
@@ -299,11 +453,53 @@ fn ingest_interface<'doc, T>(
     let new_interface = super::StoredInterface {
         name: interface.name.as_ref().into(),
         fields: field_ids,
+        implements_interfaces: interface
+            .implements_interfaces
+            .iter()
+            .map(|iface_name| schema.find_interface(iface_name.as_ref()))
+            .collect(),
+        description: interface.description.clone(),
     };
 
     schema.push_interface(new_interface);
 }
 
+/// Scalar extensions can only add directives, of which only `@specifiedBy` matters here.
+fn ingest_scalar_type_extension<'doc, T>(
+    schema: &mut Schema,
+    ext: &mut parser::ScalarTypeExtension<'doc, T>,
+) where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let scalar_id = schema
+        .find_type_id(ext.name.as_ref())
+        .as_scalar_id()
+        .unwrap();
+
+    if let Some(url) = find_specified_by_url(&ext.directives) {
+        schema.stored_scalars[scalar_id.0].specified_by_url = Some(url);
+    }
+}
+
+fn find_specified_by_url<'doc, T>(directives: &[parser::Directive<'doc, T>]) -> Option<String>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    directives
+        .iter()
+        .find(|directive| directive.name.as_ref() == "specifiedBy")
+        .and_then(|directive| {
+            directive
+                .arguments
+                .iter()
+                .find(|(name, _)| name.as_ref() == "url")
+        })
+        .and_then(|(_, value)| match value {
+            graphql_parser::query::Value::String(url) => Some(url.clone()),
+            _ => None,
+        })
+}
+
 fn find_deprecation<'doc, T>(directives: &[parser::Directive<'doc, T>]) -> Option<Option<String>>
 where
     T: graphql_parser::query::Text<'doc>,
@@ -323,6 +519,46 @@ where
         })
 }
 
+fn ingest_arguments<'doc, T>(
+    schema: &Schema,
+    arguments: &[parser::InputValue<'doc, T>],
+) -> Vec<StoredFieldArgument>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    arguments
+        .iter()
+        .map(|argument| {
+            let field_type = resolve_field_type(schema, &argument.value_type);
+
+            StoredFieldArgument {
+                name: argument.name.as_ref().into(),
+                r#type: StoredInputFieldType {
+                    id: field_type.id,
+                    qualifiers: field_type.qualifiers,
+                },
+                default_value: argument
+                    .default_value
+                    .as_ref()
+                    .map(|value| value.into_static()),
+            }
+        })
+        .collect()
+}
+
+fn ingest_directive<'doc, T>(schema: &mut Schema, directive: &parser::DirectiveDefinition<'doc, T>)
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let arguments = ingest_arguments(schema, &directive.arguments);
+
+    schema.push_directive(StoredDirective {
+        name: directive.name.as_ref().into(),
+        locations: directive.locations.clone(),
+        arguments,
+    });
+}
+
 fn ingest_input<'doc, T>(schema: &mut Schema, input: &mut parser::InputObjectType<'doc, T>)
 where
     T: graphql_parser::query::Text<'doc>,
@@ -349,6 +585,22 @@ where
             })
             .collect(),
         is_one_of,
+        description: input.description.clone(),
+        field_descriptions: input
+            .fields
+            .iter()
+            .map(|field| field.description.clone())
+            .collect(),
+        field_defaults: input
+            .fields
+            .iter()
+            .map(|field| {
+                field
+                    .default_value
+                    .as_ref()
+                    .map(|value| value.into_static())
+            })
+            .collect(),
     };
 
     schema.stored_inputs.push(input);
@@ -366,14 +618,14 @@ where
     })
 }
 
-fn extend_object_type_extensions_mut<'a, 'doc: 'a, T>(
+fn type_extensions_mut<'a, 'doc: 'a, T>(
     doc: &'a mut Document<'doc, T>,
-) -> impl Iterator<Item = &'a mut parser::ObjectTypeExtension<'doc, T>>
+) -> impl Iterator<Item = &'a mut TypeExtension<'doc, T>>
 where
     T: graphql_parser::query::Text<'doc>,
 {
     doc.definitions.iter_mut().filter_map(|def| match def {
-        Definition::TypeExtension(TypeExtension::Object(obj)) => Some(obj),
+        Definition::TypeExtension(ext) => Some(ext),
         _ => None,
     })
 }
@@ -425,3 +677,327 @@ where
         _ => None,
     })
 }
+
+/// Finds the references to types that are not defined in the document, with their position.
+/// Converting a document with such references would fail, so they are reported beforehand.
+pub(crate) fn undefined_type_references<'doc, T>(
+    document: &Document<'doc, T>,
+) -> Vec<(graphql_parser::Pos, String)>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    let mut defined: BTreeSet<&str> = super::DEFAULT_SCALARS.iter().copied().collect();
+
+    for definition in &document.definitions {
+        if let Definition::TypeDefinition(type_definition) = definition {
+            defined.insert(match type_definition {
+                TypeDefinition::Scalar(scalar) => scalar.name.as_ref(),
+                TypeDefinition::Object(object) => object.name.as_ref(),
+                TypeDefinition::Interface(interface) => interface.name.as_ref(),
+                TypeDefinition::Union(union) => union.name.as_ref(),
+                TypeDefinition::Enum(enm) => enm.name.as_ref(),
+                TypeDefinition::InputObject(input) => input.name.as_ref(),
+            });
+        }
+    }
+
+    let mut references = Vec::new();
+
+    let mut check_fields = |parent: &str, fields: &[parser::Field<'doc, T>]| {
+        for field in fields {
+            let coordinate = format!("{}.{}", parent, field.name.as_ref());
+            references.push((
+                named_type(&field.field_type).to_owned(),
+                field.position,
+                coordinate.clone(),
+            ));
+
+            for argument in &field.arguments {
+                references.push((
+                    named_type(&argument.value_type).to_owned(),
+                    argument.position,
+                    format!("{}({}:)", coordinate, argument.name.as_ref()),
+                ));
+            }
+        }
+    };
+
+    let mut other_references = Vec::new();
+
+    for definition in &document.definitions {
+        match definition {
+            Definition::TypeDefinition(TypeDefinition::Object(object)) => {
+                check_fields(object.name.as_ref(), &object.fields);
+
+                for interface in &object.implements_interfaces {
+                    other_references.push((
+                        interface.as_ref().to_owned(),
+                        object.position,
+                        object.name.as_ref().to_owned(),
+                    ));
+                }
+            }
+            Definition::TypeExtension(TypeExtension::Object(object)) => {
+                check_fields(object.name.as_ref(), &object.fields);
+
+                for interface in &object.implements_interfaces {
+                    other_references.push((
+                        interface.as_ref().to_owned(),
+                        object.position,
+                        object.name.as_ref().to_owned(),
+                    ));
+                }
+            }
+            Definition::TypeDefinition(TypeDefinition::Interface(parser::InterfaceType {
+                name,
+                fields,
+                implements_interfaces,
+                position,
+                ..
+            }))
+            | Definition::TypeExtension(TypeExtension::Interface(
+                parser::InterfaceTypeExtension {
+                    name,
+                    fields,
+                    implements_interfaces,
+                    position,
+                    ..
+                },
+            )) => {
+                check_fields(name.as_ref(), fields);
+
+                for interface in implements_interfaces {
+                    other_references.push((
+                        interface.as_ref().to_owned(),
+                        *position,
+                        name.as_ref().to_owned(),
+                    ));
+                }
+            }
+            Definition::TypeDefinition(TypeDefinition::Union(parser::UnionType {
+                name,
+                types,
+                position,
+                ..
+            }))
+            | Definition::TypeExtension(TypeExtension::Union(parser::UnionTypeExtension {
+                name,
+                types,
+                position,
+                ..
+            })) => {
+                for member in types {
+                    other_references.push((
+                        member.as_ref().to_owned(),
+                        *position,
+                        name.as_ref().to_owned(),
+                    ));
+                }
+            }
+            Definition::TypeDefinition(TypeDefinition::InputObject(parser::InputObjectType {
+                name,
+                fields,
+                ..
+            }))
+            | Definition::TypeExtension(TypeExtension::InputObject(
+                parser::InputObjectTypeExtension { name, fields, .. },
+            )) => {
+                for field in fields {
+                    other_references.push((
+                        named_type(&field.value_type).to_owned(),
+                        field.position,
+                        format!("{}.{}", name.as_ref(), field.name.as_ref()),
+                    ));
+                }
+            }
+            Definition::DirectiveDefinition(directive) => {
+                for argument in &directive.arguments {
+                    other_references.push((
+                        named_type(&argument.value_type).to_owned(),
+                        argument.position,
+                        format!("@{}({}:)", directive.name.as_ref(), argument.name.as_ref()),
+                    ));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    references
+        .into_iter()
+        .chain(other_references)
+        .filter(|(name, _, _)| !defined.contains(name.as_str()))
+        .map(|(name, position, coordinate)| {
+            (
+                position,
+                format!("Unknown type `{}` referenced by `{}`.", name, coordinate),
+            )
+        })
+        .collect()
+}
+
+/// Finds the type extensions that cannot be merged into the type they extend, with their
+/// position: extensions of unknown types or of types of another kind, and extensions redefining
+/// a member of the type.
+pub(crate) fn type_extension_errors<'doc, T>(
+    document: &Document<'doc, T>,
+) -> Vec<(graphql_parser::Pos, String)>
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    // The kind of each type, as its SDL keyword, and the names of its members.
+    let mut types: BTreeMap<&str, (&str, BTreeSet<&str>)> = super::DEFAULT_SCALARS
+        .iter()
+        .map(|name| (*name, ("scalar", BTreeSet::new())))
+        .collect();
+
+    for definition in &document.definitions {
+        let (name, kind, members): (&str, &str, Vec<&str>) = match definition {
+            Definition::TypeDefinition(TypeDefinition::Scalar(scalar)) => {
+                (scalar.name.as_ref(), "scalar", Vec::new())
+            }
+            Definition::TypeDefinition(TypeDefinition::Object(object)) => (
+                object.name.as_ref(),
+                "type",
+                object
+                    .fields
+                    .iter()
+                    .map(|field| field.name.as_ref())
+                    .collect(),
+            ),
+            Definition::TypeDefinition(TypeDefinition::Interface(interface)) => (
+                interface.name.as_ref(),
+                "interface",
+                interface
+                    .fields
+                    .iter()
+                    .map(|field| field.name.as_ref())
+                    .collect(),
+            ),
+            Definition::TypeDefinition(TypeDefinition::Union(union)) => (
+                union.name.as_ref(),
+                "union",
+                union.types.iter().map(AsRef::as_ref).collect(),
+            ),
+            Definition::TypeDefinition(TypeDefinition::Enum(enm)) => (
+                enm.name.as_ref(),
+                "enum",
+                enm.values.iter().map(|value| value.name.as_ref()).collect(),
+            ),
+            Definition::TypeDefinition(TypeDefinition::InputObject(input)) => (
+                input.name.as_ref(),
+                "input",
+                input
+                    .fields
+                    .iter()
+                    .map(|field| field.name.as_ref())
+                    .collect(),
+            ),
+            _ => continue,
+        };
+
+        types.insert(name, (kind, members.into_iter().collect()));
+    }
+
+    let mut errors = Vec::new();
+
+    for definition in &document.definitions {
+        let (name, kind, position, members): (&str, &str, _, Vec<(&str, Pos)>) = match definition {
+            Definition::TypeExtension(TypeExtension::Scalar(scalar)) => {
+                (scalar.name.as_ref(), "scalar", scalar.position, Vec::new())
+            }
+            Definition::TypeExtension(TypeExtension::Object(object)) => (
+                object.name.as_ref(),
+                "type",
+                object.position,
+                object
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.as_ref(), field.position))
+                    .collect(),
+            ),
+            Definition::TypeExtension(TypeExtension::Interface(interface)) => (
+                interface.name.as_ref(),
+                "interface",
+                interface.position,
+                interface
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.as_ref(), field.position))
+                    .collect(),
+            ),
+            Definition::TypeExtension(TypeExtension::Union(union)) => (
+                union.name.as_ref(),
+                "union",
+                union.position,
+                union
+                    .types
+                    .iter()
+                    .map(|member| (member.as_ref(), union.position))
+                    .collect(),
+            ),
+            Definition::TypeExtension(TypeExtension::Enum(enm)) => (
+                enm.name.as_ref(),
+                "enum",
+                enm.position,
+                enm.values
+                    .iter()
+                    .map(|value| (value.name.as_ref(), value.position))
+                    .collect(),
+            ),
+            Definition::TypeExtension(TypeExtension::InputObject(input)) => (
+                input.name.as_ref(),
+                "input",
+                input.position,
+                input
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.as_ref(), field.position))
+                    .collect(),
+            ),
+            _ => continue,
+        };
+
+        let existing_members = match types.get_mut(name) {
+            Some((existing_kind, _)) if *existing_kind != kind => {
+                errors.push((
+                    position,
+                    format!(
+                        "Cannot extend `{}` with `extend {}`, because it is defined as `{}`.",
+                        name, kind, existing_kind
+                    ),
+                ));
+                continue;
+            }
+            Some((_, existing_members)) => existing_members,
+            None => {
+                errors.push((position, format!("Cannot extend unknown type `{}`.", name)));
+                continue;
+            }
+        };
+
+        for (member, member_position) in members {
+            if !existing_members.insert(member) {
+                let message = match kind {
+                    "union" => format!("`{}` is already a member of union `{}`.", member, name),
+                    "enum" => format!("Enum value `{}.{}` is already defined.", name, member),
+                    _ => format!("Field `{}.{}` is already defined.", name, member),
+                };
+
+                errors.push((member_position, message));
+            }
+        }
+    }
+
+    errors
+}
+
+fn named_type<'a, 'doc, T>(r#type: &'a parser::Type<'doc, T>) -> &'a str
+where
+    T: graphql_parser::query::Text<'doc>,
+{
+    match r#type {
+        parser::Type::NamedType(name) => name.as_ref(),
+        parser::Type::ListType(inner) | parser::Type::NonNullType(inner) => named_type(inner),
+    }
+}
@@ -1,6 +1,7 @@
-use super::{Schema, TypeId};
+use super::{InterfaceId, Schema, StoredDirective, StoredFieldArgument, TypeId};
 use graphql_introspection_query::introspection_response::{
-    FullType, IntrospectionResponse, Schema as JsonSchema, TypeRef, __TypeKind,
+    FullType, FullTypeFields, InputValue, IntrospectionResponse, Schema as JsonSchema,
+    SchemaDirectives, TypeRef, __TypeKind,
 };
 
 pub(super) fn build_schema(src: IntrospectionResponse) -> Schema {
@@ -69,6 +70,10 @@ fn convert(src: &mut JsonSchema, schema: &mut Schema) {
         ingest_input(schema, input);
     }
 
+    for directive in src.directives.iter_mut().flatten().flatten() {
+        ingest_directive(schema, directive);
+    }
+
     // Define the root operations.
     {
         schema.query_type = src
@@ -132,7 +137,10 @@ fn ingest_scalar(schema: &mut Schema, scalar: &mut FullType) {
     let name: String = scalar.name.take().expect("scalar.name");
     let names_name = name.clone();
 
-    let id = schema.push_scalar(super::StoredScalar { name });
+    let id = schema.push_scalar(super::StoredScalar {
+        name,
+        specified_by_url: scalar.specified_by_url.take(),
+    });
 
     schema.names.insert(names_name, TypeId::Scalar(id));
 }
@@ -141,15 +149,22 @@ fn ingest_enum(schema: &mut Schema, enm: &mut FullType) {
     let name = enm.name.take().expect("enm.name");
     let names_name = name.clone();
 
-    let variants = enm
-        .enum_values
-        .as_mut()
-        .expect("enm.enum_values.as_mut()")
+    let enum_values = enm.enum_values.as_mut().expect("enm.enum_values.as_mut()");
+    let variant_descriptions = enum_values
+        .iter_mut()
+        .map(|v| v.description.take())
+        .collect();
+    let variants = enum_values
         .iter_mut()
         .map(|v| std::mem::take(v.name.as_mut().expect("variant.name.as_mut().take()")))
         .collect();
 
-    let enm = super::StoredEnum { name, variants };
+    let enm = super::StoredEnum {
+        name,
+        variants,
+        description: enm.description.take(),
+        variant_descriptions,
+    };
 
     let id = schema.push_enum(enm);
 
@@ -177,6 +192,8 @@ fn ingest_interface(schema: &mut Schema, iface: &mut FullType) {
             } else {
                 None
             },
+            arguments: ingest_arguments(schema, field),
+            description: field.description.take(),
         };
 
         field_ids.push(schema.push_field(field));
@@ -185,6 +202,8 @@ fn ingest_interface(schema: &mut Schema, iface: &mut FullType) {
     let interface = super::StoredInterface {
         name: std::mem::take(iface.name.as_mut().expect("iface.name.as_mut")),
         fields: field_ids,
+        implements_interfaces: implemented_interfaces(schema, iface),
+        description: iface.description.take(),
     };
 
     schema.push_interface(interface);
@@ -212,6 +231,8 @@ fn ingest_object(schema: &mut Schema, object: &mut FullType) {
             } else {
                 None
             },
+            arguments: ingest_arguments(schema, field),
+            description: field.description.take(),
         };
 
         field_ids.push(schema.push_field(field));
@@ -219,34 +240,116 @@ fn ingest_object(schema: &mut Schema, object: &mut FullType) {
 
     let object = super::StoredObject {
         name: object.name.take().expect("take object name"),
-        implements_interfaces: object
-            .interfaces
-            .as_ref()
-            .map(|ifaces| {
-                ifaces
-                    .iter()
-                    .map(|iface| {
-                        schema
-                            .names
-                            .get(iface.type_ref.name.as_ref().unwrap())
-                            .and_then(|type_id| type_id.as_interface_id())
-                            .ok_or_else(|| {
-                                format!(
-                                    "Unknown interface: {}",
-                                    iface.type_ref.name.as_ref().unwrap()
-                                )
-                            })
-                            .unwrap()
-                    })
-                    .collect()
-            })
-            .unwrap_or_default(),
+        implements_interfaces: implemented_interfaces(schema, object),
         fields: field_ids,
+        description: object.description.take(),
     };
 
     schema.push_object(object);
 }
 
+fn implemented_interfaces(schema: &Schema, ty: &FullType) -> Vec<InterfaceId> {
+    ty.interfaces
+        .as_ref()
+        .map(|ifaces| {
+            ifaces
+                .iter()
+                .map(|iface| {
+                    schema
+                        .names
+                        .get(iface.type_ref.name.as_ref().unwrap())
+                        .and_then(|type_id| type_id.as_interface_id())
+                        .ok_or_else(|| {
+                            format!(
+                                "Unknown interface: {}",
+                                iface.type_ref.name.as_ref().unwrap()
+                            )
+                        })
+                        .unwrap()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn ingest_arguments(schema: &mut Schema, field: &mut FullTypeFields) -> Vec<StoredFieldArgument> {
+    let arguments = field
+        .args
+        .iter_mut()
+        .flatten()
+        .flatten()
+        .map(|argument| &mut argument.input_value);
+
+    ingest_input_values(schema, arguments)
+}
+
+fn ingest_input_values<'a>(
+    schema: &mut Schema,
+    input_values: impl Iterator<Item = &'a mut InputValue>,
+) -> Vec<StoredFieldArgument> {
+    input_values
+        .map(|input_value| StoredFieldArgument {
+            name: std::mem::take(&mut input_value.name),
+            r#type: resolve_input_field_type(schema, &mut input_value.type_),
+            default_value: input_value
+                .default_value
+                .as_deref()
+                .and_then(parse_value_literal),
+        })
+        .collect()
+}
+
+fn ingest_directive(schema: &mut Schema, directive: &mut SchemaDirectives) {
+    let name = directive.name.take().expect("directive name");
+    let locations = directive
+        .locations
+        .iter()
+        .flatten()
+        .flatten()
+        .filter_map(|location| {
+            serde_json::to_value(location)
+                .ok()
+                .and_then(|location| location.as_str()?.parse().ok())
+        })
+        .collect();
+    let arguments = directive
+        .args
+        .iter_mut()
+        .flatten()
+        .flatten()
+        .map(|argument| &mut argument.input_value);
+    let arguments = ingest_input_values(schema, arguments);
+
+    schema.push_directive(StoredDirective {
+        name,
+        locations,
+        arguments,
+    });
+}
+
+/// Introspection returns default values as GraphQL literals in strings.
+pub(super) fn parse_value_literal(
+    literal: &str,
+) -> Option<graphql_parser::query::Value<'static, String>> {
+    use graphql_parser::query::{Definition, OperationDefinition, Selection};
+
+    let query = format!("{{ f(v: {}) }}", literal);
+    let document = graphql_parser::parse_query::<&str>(&query).ok()?;
+
+    match document.definitions.first()? {
+        Definition::Operation(OperationDefinition::SelectionSet(selection_set)) => {
+            match selection_set.items.first()? {
+                Selection::Field(field) => field
+                    .arguments
+                    .first()
+                    .map(|(_, value)| value.into_static()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 fn ingest_union(schema: &mut Schema, union: &mut FullType) {
     let variants = union
         .possible_types
@@ -266,6 +369,7 @@ fn ingest_union(schema: &mut Schema, union: &mut FullType) {
     let un = super::StoredUnion {
         name: union.name.take().expect("union.name.take"),
         variants,
+        description: union.description.take(),
     };
 
     schema.stored_unions.push(un);
@@ -273,6 +377,8 @@ fn ingest_union(schema: &mut Schema, union: &mut FullType) {
 
 fn ingest_input(schema: &mut Schema, input: &mut FullType) {
     let mut fields = Vec::new();
+    let mut field_descriptions = Vec::new();
+    let mut field_defaults = Vec::new();
 
     for field in input
         .input_fields
@@ -284,6 +390,14 @@ fn ingest_input(schema: &mut Schema, input: &mut FullType) {
             std::mem::take(&mut field.input_value.name),
             resolve_input_field_type(schema, &mut field.input_value.type_),
         ));
+        field_descriptions.push(field.input_value.description.take());
+        field_defaults.push(
+            field
+                .input_value
+                .default_value
+                .as_deref()
+                .and_then(parse_value_literal),
+        );
     }
 
     let input = super::StoredInputType {
@@ -292,6 +406,9 @@ fn ingest_input(schema: &mut Schema, input: &mut FullType) {
         // The one-of input spec is not stable yet, thus the introspection query does not have
         // `isOneOf`, so this is always false.
         is_one_of: false,
+        description: input.description.take(),
+        field_descriptions,
+        field_defaults,
     };
 
     schema.stored_inputs.push(input);
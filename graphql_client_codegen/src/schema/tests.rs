@@ -1,2 +1,4 @@
+mod extend_all_types;
 mod extend_object;
 mod github;
+mod interface_inheritance;
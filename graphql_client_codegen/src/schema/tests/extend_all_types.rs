@@ -0,0 +1,116 @@
+use crate::schema::{type_extension_errors, Schema, TypeId};
+
+const SCHEMA_JSON: &str = include_str!("extend_all_types_schema.json");
+const SCHEMA_GRAPHQL: &str = include_str!("extend_all_types_schema.graphql");
+
+#[test]
+fn ast_from_graphql_and_json_produce_the_same_schema() {
+    let json: graphql_introspection_query::introspection_response::IntrospectionResponse =
+        serde_json::from_str(SCHEMA_JSON).unwrap();
+    let graphql_parser_schema = graphql_parser::parse_schema(SCHEMA_GRAPHQL)
+        .unwrap()
+        .into_static();
+    let json = Schema::from(json);
+    let gql = Schema::from(graphql_parser_schema);
+
+    assert!(vecs_match(&json.stored_scalars, &gql.stored_scalars));
+
+    for schema in [&json, &gql] {
+        let field_names = |type_id: TypeId| -> Vec<&str> {
+            let fields = match type_id {
+                TypeId::Object(id) => &schema.get_object(id).fields,
+                TypeId::Interface(id) => &schema.get_interface(id).fields,
+                other => panic!("{:?} has no fields", other),
+            };
+
+            fields
+                .iter()
+                .map(|field_id| schema.get_field(*field_id).name.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            field_names(schema.find_type_id("Node")),
+            ["id", "createdAt"]
+        );
+        assert_eq!(
+            field_names(schema.find_type_id("User")),
+            ["id", "createdAt", "name", "status", "email"]
+        );
+
+        let search_result = match schema.find_type_id("SearchResult") {
+            TypeId::Union(id) => schema.get_union(id),
+            other => panic!("SearchResult is {:?}", other),
+        };
+        let members: Vec<&str> = search_result
+            .variants
+            .iter()
+            .map(|variant| variant.name(schema))
+            .collect();
+        assert_eq!(members, ["User", "Team"]);
+
+        let status = schema.get_enum(schema.find_type_id("Status").as_enum_id().unwrap());
+        assert_eq!(status.variants, ["ACTIVE", "SUSPENDED"]);
+        assert_eq!(status.variant_descriptions.len(), status.variants.len());
+
+        let user_filter =
+            schema.get_input(schema.find_type_id("UserFilter").as_input_id().unwrap());
+        let input_fields: Vec<&str> = user_filter
+            .fields
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(input_fields, ["name", "status"]);
+        assert_eq!(
+            user_filter.field_descriptions.len(),
+            user_filter.fields.len()
+        );
+        assert_eq!(
+            user_filter.field_defaults,
+            [
+                None,
+                Some(graphql_parser::query::Value::Enum("ACTIVE".to_owned()))
+            ]
+        );
+    }
+}
+
+#[test]
+fn invalid_type_extensions_are_reported() {
+    let schema = r#"
+        type Query { user: User }
+        type User { name: String }
+        enum Status { ACTIVE }
+        union SearchResult = User
+
+        extend type Missing { name: String }
+        extend interface User { id: ID }
+        extend type User { name: String }
+        extend enum Status { ACTIVE }
+        extend union SearchResult = User
+        extend input Status { value: String }
+        extend scalar ID @specifiedBy(url: "https://example.com")
+    "#;
+    let document = graphql_parser::parse_schema::<&str>(schema).unwrap();
+
+    let errors: Vec<_> = type_extension_errors(&document)
+        .into_iter()
+        .map(|(_position, message)| message)
+        .collect();
+
+    assert_eq!(
+        errors,
+        [
+            "Cannot extend unknown type `Missing`.",
+            "Cannot extend `User` with `extend interface`, because it is defined as `type`.",
+            "Field `User.name` is already defined.",
+            "Enum value `Status.ACTIVE` is already defined.",
+            "`User` is already a member of union `SearchResult`.",
+            "Cannot extend `Status` with `extend input`, because it is defined as `enum`.",
+        ]
+    );
+}
+
+fn vecs_match<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+    a.len() == b.len() && a.iter().all(|a| b.iter().any(|b| a == b))
+}
@@ -63,6 +63,7 @@ fn ast_from_graphql_and_json_produce_the_same_schema() {
                 j.name
             );
             assert_eq!(j.fields.len(), g.fields.len(), "{}", j.name);
+            assert_eq!(j.description, g.description, "{}", j.name);
         }
     }
 
@@ -0,0 +1,51 @@
+use crate::schema::Schema;
+
+const SCHEMA_JSON: &str = include_str!("interface_inheritance_schema.json");
+const SCHEMA_GRAPHQL: &str = include_str!("interface_inheritance_schema.graphql");
+
+#[test]
+fn interface_inheritance_is_stored_from_graphql_and_json() {
+    let json: graphql_introspection_query::introspection_response::IntrospectionResponse =
+        serde_json::from_str(SCHEMA_JSON).unwrap();
+    let graphql_parser_schema = graphql_parser::parse_schema(SCHEMA_GRAPHQL)
+        .unwrap()
+        .into_static();
+    let json = Schema::from(json);
+    let gql = Schema::from(graphql_parser_schema);
+
+    for schema in [&json, &gql] {
+        let interface_id = |name: &str| schema.find_type_id(name).as_interface_id().unwrap();
+        let implemented = |name: &str| -> Vec<&str> {
+            schema
+                .get_interface(interface_id(name))
+                .implements_interfaces
+                .iter()
+                .map(|id| schema.get_interface(*id).name.as_str())
+                .collect()
+        };
+
+        assert!(implemented("Node").is_empty());
+        assert_eq!(implemented("Resource"), ["Node"]);
+        assert_eq!(implemented("Image"), ["Resource", "Node"]);
+
+        assert!(schema.implements_interface(schema.find_type_id("Image"), interface_id("Node")));
+        assert!(!schema.implements_interface(schema.find_type_id("Node"), interface_id("Image")));
+
+        let possible_types = |name: &str| -> Vec<&str> {
+            schema
+                .possible_types(schema.find_type_id(name))
+                .into_iter()
+                .map(|id| schema.get_object(id).name.as_str())
+                .collect()
+        };
+
+        assert_eq!(possible_types("Node"), ["File", "Photo", "Team"]);
+        assert_eq!(possible_types("Resource"), ["File", "Photo"]);
+        assert_eq!(possible_types("Image"), ["Photo"]);
+
+        assert!(schema
+            .type_condition_applies(schema.find_type_id("Node"), schema.find_type_id("Image")));
+        assert!(!schema
+            .type_condition_applies(schema.find_type_id("Image"), schema.find_type_id("File")));
+    }
+}
@@ -0,0 +1,321 @@
+//! Finding and reading the files a schema is split across.
+
+use crate::{diagnostics::parse_error_location, read_file, BoxError, Diagnostic, GeneralError};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+/// The extensions of the SDL files picked up from schema directories.
+const SDL_EXTENSIONS: &[&str] = &["graphql", "graphqls", "gql"];
+
+/// Expands every directory and glob pattern in `schema_paths` to the schema files it contains.
+///
+/// Directories are searched recursively for SDL files. Glob patterns support `*` and `?` within
+/// a path segment, and `**` for any number of directories. Matches are sorted, so the order of
+/// the files does not depend on the file system.
+pub(crate) fn resolve_schema_paths(schema_paths: &[PathBuf]) -> Result<Vec<PathBuf>, BoxError> {
+    let mut files = Vec::new();
+
+    for schema_path in schema_paths {
+        let mut matches = if is_glob(schema_path) {
+            expand_glob(schema_path)?
+        } else if schema_path.is_dir() {
+            let mut matches = Vec::new();
+            walk_files(schema_path, &mut matches)?;
+            matches.retain(|path| is_sdl_file(path));
+            matches
+        } else {
+            files.push(schema_path.clone());
+            continue;
+        };
+
+        if matches.is_empty() {
+            return Err(GeneralError(format!(
+                "No schema files found at {}",
+                schema_path.display()
+            ))
+            .into());
+        }
+
+        matches.sort();
+        files.extend(matches);
+    }
+
+    Ok(files)
+}
+
+/// SDL schema files concatenated into one document, keeping track of where each file starts so
+/// positions in the document can be reported against the original files.
+pub(crate) struct SchemaSource {
+    text: String,
+    files: Vec<SchemaFile>,
+}
+
+struct SchemaFile {
+    path: PathBuf,
+    contents: String,
+    /// The line of the concatenated document the file starts on.
+    first_line: usize,
+}
+
+impl SchemaSource {
+    pub(crate) fn read(paths: &[PathBuf]) -> Result<SchemaSource, BoxError> {
+        let mut text = String::new();
+        let mut files = Vec::with_capacity(paths.len());
+        let mut first_line = 1;
+
+        for path in paths {
+            let contents = read_file(path)?;
+            let start = text.len();
+
+            text.push_str(&contents);
+            if !contents.ends_with('\n') {
+                text.push('\n');
+            }
+
+            files.push(SchemaFile {
+                path: path.clone(),
+                first_line,
+                contents,
+            });
+            first_line += text[start..].matches('\n').count();
+        }
+
+        Ok(SchemaSource { text, files })
+    }
+
+    /// The concatenated document.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// A diagnostic at the given position of the concatenated document.
+    pub(crate) fn diagnostic(&self, message: String, line: usize, column: usize) -> Diagnostic {
+        match self.file_at(line) {
+            Some(file) => Diagnostic::new(message)
+                .with_path(Some(&file.path))
+                .with_location(line - file.first_line + 1, column, Some(&file.contents)),
+            None => Diagnostic::new(message),
+        }
+    }
+
+    /// A diagnostic for a parser error, whose message contains its position in the concatenated
+    /// document.
+    pub(crate) fn parse_error_diagnostic(&self, message: String) -> Diagnostic {
+        let (line, column) = match parse_error_location(&message) {
+            Some(location) => location,
+            None => return Diagnostic::new(message),
+        };
+
+        let message = match self.file_at(line) {
+            Some(file) => message.replacen(
+                &format!("{}:{}", line, column),
+                &format!("{}:{}", line - file.first_line + 1, column),
+                1,
+            ),
+            None => message,
+        };
+
+        self.diagnostic(message, line, column)
+    }
+
+    fn file_at(&self, line: usize) -> Option<&SchemaFile> {
+        self.files.iter().rev().find(|file| file.first_line <= line)
+    }
+}
+
+fn is_sdl_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| SDL_EXTENSIONS.contains(&extension))
+        .unwrap_or(false)
+}
+
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+fn walk_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), BoxError> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            walk_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The directories searched for the files of the directory and glob schema paths, so that build
+/// scripts can be rerun when files are added to them.
+pub(crate) fn schema_directories(schema_paths: &[PathBuf]) -> Vec<PathBuf> {
+    schema_paths
+        .iter()
+        .filter_map(|schema_path| {
+            if is_glob(schema_path) {
+                Some(glob_base(schema_path))
+            } else if schema_path.is_dir() {
+                Some(schema_path.clone())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The leading segments of a glob pattern, before the first one with a wildcard.
+fn glob_base(pattern: &Path) -> PathBuf {
+    let base: PathBuf = pattern
+        .components()
+        .take_while(|component| !is_glob(Path::new(component.as_os_str())))
+        .collect();
+
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+fn expand_glob(pattern: &Path) -> Result<Vec<PathBuf>, BoxError> {
+    let base = glob_base(pattern);
+    let pattern_segments = pattern
+        .components()
+        .skip_while(|component| !is_glob(Path::new(component.as_os_str())))
+        .map(|component| {
+            component.as_os_str().to_str().ok_or_else(|| {
+                GeneralError(format!(
+                    "Schema path {} is not valid UTF-8",
+                    pattern.display()
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut candidates = Vec::new();
+    if base.is_dir() {
+        walk_files(&base, &mut candidates)?;
+    }
+
+    Ok(candidates
+        .into_iter()
+        .filter(|candidate| {
+            let relative = candidate.strip_prefix(&base).expect("walked from base");
+            let relative: Vec<Cow<'_, str>> = relative
+                .iter()
+                .map(|segment| segment.to_string_lossy())
+                .collect();
+            let relative: Vec<&str> = relative.iter().map(Cow::as_ref).collect();
+
+            segments_match(&pattern_segments, &relative)
+        })
+        .collect())
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => {
+            segments_match(rest, path) || (!path.is_empty() && segments_match(pattern, &path[1..]))
+        }
+        (Some((segment, rest)), Some((name, path_rest))) => {
+            wildcard_match(segment.as_bytes(), name.as_bytes()) && segments_match(rest, path_rest)
+        }
+        _ => false,
+    }
+}
+
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            wildcard_match(rest, name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name_rest))) => wildcard_match(rest, name_rest),
+        (Some((expected, rest)), Some((actual, name_rest))) => {
+            expected == actual && wildcard_match(rest, name_rest)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_segments_match() {
+        let matches = |pattern: &str, path: &str| {
+            let pattern: Vec<&str> = pattern.split('/').collect();
+            let path: Vec<&str> = path.split('/').collect();
+            segments_match(&pattern, &path)
+        };
+
+        assert!(matches("*.graphqls", "users.graphqls"));
+        assert!(!matches("*.graphqls", "users/users.graphqls"));
+        assert!(matches("**/*.graphqls", "users.graphqls"));
+        assert!(matches("**/*.graphqls", "domains/users/users.graphqls"));
+        assert!(matches("domains/?sers/*", "domains/users/schema.gql"));
+        assert!(!matches("**/*.graphqls", "users.json"));
+    }
+
+    #[test]
+    fn globs_are_expanded_by_path_component() {
+        let pattern = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("tests")
+            .join("*_schema.graphql");
+        let matches = expand_glob(&pattern).unwrap();
+
+        assert!(matches
+            .iter()
+            .any(|path| path.ends_with("star_wars_schema.graphql")));
+        assert!(matches.iter().all(|path| is_sdl_file(path)));
+    }
+
+    #[test]
+    fn schema_directories_are_the_directories_and_glob_bases() {
+        let tests_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("tests");
+        let schema_paths = [
+            tests_dir.join("star_wars_schema.graphql"),
+            tests_dir.clone(),
+            tests_dir.join("**").join("*.graphql"),
+        ];
+
+        assert_eq!(
+            schema_directories(&schema_paths),
+            [tests_dir.clone(), tests_dir]
+        );
+    }
+
+    #[test]
+    fn diagnostics_point_at_the_original_file() {
+        let source = SchemaSource {
+            text: "type A {\n  b: B\n}\ntype B {\n  a: A\n  c: C\n}\n".to_owned(),
+            files: vec![
+                SchemaFile {
+                    path: PathBuf::from("a.graphql"),
+                    contents: "type A {\n  b: B\n}\n".to_owned(),
+                    first_line: 1,
+                },
+                SchemaFile {
+                    path: PathBuf::from("b.graphql"),
+                    contents: "type B {\n  a: A\n  c: C\n}\n".to_owned(),
+                    first_line: 4,
+                },
+            ],
+        };
+
+        let diagnostic = source.diagnostic("Unknown type `C`".to_owned(), 6, 3);
+
+        assert_eq!(diagnostic.path(), Some(Path::new("b.graphql")));
+        assert_eq!(diagnostic.line(), Some(3));
+        assert_eq!(diagnostic.column(), Some(3));
+    }
+}
@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use crate::{generate_module_token_stream_from_string, CodegenMode, GraphQLClientCodegenOptions};
+use crate::{
+    generate_module_token_stream_from_string, well_known_scalars::ScalarCrate, CodegenMode,
+    Diagnostics, GraphQLClientCodegenOptions,
+};
 
 const KEYWORDS_QUERY: &str = include_str!("keywords_query.graphql");
 const KEYWORDS_SCHEMA_PATH: &str = "keywords_schema.graphql";
@@ -15,6 +18,39 @@ fn build_schema_path(path: &str) -> PathBuf {
         .join(path)
 }
 
+fn diagnostic_messages(error: &(dyn std::error::Error + 'static)) -> Vec<String> {
+    error
+        .downcast_ref::<Diagnostics>()
+        .expect("Errors should be reported as diagnostics")
+        .iter()
+        .map(|diagnostic| diagnostic.message().to_owned())
+        .collect()
+}
+
+/// Checks that the generated code parses as Rust items, and contains each of the `expected`
+/// snippets, as rendered by `TokenStream::to_string`. Returns that rendering.
+fn assert_generated_code_contains(
+    generated_tokens: proc_macro2::TokenStream,
+    expected: &[&str],
+) -> String {
+    let generated_code = generated_tokens.to_string();
+
+    if let Err(e) = syn::parse2::<syn::File>(generated_tokens) {
+        panic!("Error: {}\n Generated content: {}\n", e, &generated_code);
+    }
+
+    for expected in expected {
+        assert!(
+            generated_code.contains(expected),
+            "{} not found in {}",
+            expected,
+            generated_code
+        );
+    }
+
+    generated_code
+}
+
 #[test]
 fn schema_with_keywords_works() {
     let query_string = KEYWORDS_QUERY;
@@ -155,3 +191,513 @@ fn skip_serializing_none_should_generate_serde_skip_serializing() {
         }
     };
 }
+
+#[test]
+fn field_arguments_are_validated() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+
+    let cases = [
+        (
+            "query Q { hero(episode: JEDI, limit: 3) { __typename name } }",
+            "Unknown argument `limit` on field `Query.hero`.",
+        ),
+        (
+            "query Q { human { name } }",
+            "Field `Query.human` is missing the required argument `id` of type `ID!`.",
+        ),
+        (
+            "query Q { hero(episode: PADAWAN) { __typename name } }",
+            "Argument `episode` on field `Query.hero` has an invalid value (PADAWAN). Expected type `Episode`.",
+        ),
+        (
+            "query Q { human(id: null) { name } }",
+            "Argument `id` on field `Query.human` has an invalid value (null). Expected type `ID!`.",
+        ),
+        (
+            r#"mutation M { createReview(review: { stars: "five" }) { stars } }"#,
+            "Argument `review` on field `Mutation.createReview` has an invalid value ({stars: \"five\"}). Expected type `ReviewInput!`.",
+        ),
+        (
+            "mutation M { createReview(review: {}) { stars } }",
+            "Argument `review` on field `Mutation.createReview` is missing the required input field `ReviewInput.stars` of type `Int!`.",
+        ),
+        (
+            "mutation M { createReview(review: { stars: 5, favorite_color: { red: 1 } }) { stars } }",
+            "Argument `review` on field `Mutation.createReview` is missing the required input fields `ColorInput.green` of type `Int!` and `ColorInput.blue` of type `Int!`.",
+        ),
+        (
+            "mutation M($review: ReviewInput! = { commentary: \"Great\" }) { createReview(review: $review) { stars } }",
+            "The default value of variable `$review` is missing the required input field `ReviewInput.stars` of type `Int!`.",
+        ),
+    ];
+
+    for (query, expected_error) in cases.iter() {
+        let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        let error = generate_module_token_stream_from_string(query, &schema_path, options)
+            .expect_err("Invalid arguments should be rejected");
+
+        assert_eq!(diagnostic_messages(error.as_ref()), [*expected_error]);
+    }
+
+    let valid_query = r#"
+        query Q($id: ID!) {
+          human(id: $id) { name height(unit: FOOT) }
+          search(text: "Luke") { __typename }
+          reviews(episode: EMPIRE) { stars }
+        }
+    "#;
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    generate_module_token_stream_from_string(valid_query, &schema_path, options)
+        .expect("Valid arguments should be accepted");
+
+    let valid_mutation = r#"
+        mutation M($review: ReviewInput = { stars: 5 }) {
+          createReview(review: $review) { stars }
+          other: createReview(review: { stars: 4, favorite_color: null }) { stars }
+        }
+    "#;
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    generate_module_token_stream_from_string(valid_mutation, &schema_path, options)
+        .expect("Input objects with their required fields should be accepted");
+}
+
+#[test]
+fn query_documents_are_validated() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+
+    let cases = [
+        (
+            "query Q { hero { __typename } } query Q { hero { __typename } }",
+            "There can be only one operation named `Q`.",
+        ),
+        (
+            "query Q { human(id: 1) { ...F } } fragment F on Human { name } fragment F on Human { id }",
+            "There can be only one fragment named `F`.",
+        ),
+        (
+            "query Q { human(id: 1, id: 2) { name } }",
+            "There can be only one argument named `id`.",
+        ),
+        (
+            "query Q { human(id: $id) { name } }",
+            "Variable `$id` is not defined by operation `Q`.",
+        ),
+        (
+            "query Q($id: ID!, $episode: Episode) { human(id: $id) { name } }",
+            "Variable `$episode` is never used in operation `Q`.",
+        ),
+        (
+            "query Q($id: String!) { human(id: $id) { name } }",
+            "Variable `$id` of type `String!` is used in a position expecting type `ID!`.",
+        ),
+        (
+            "query Q($id: ID) { human(id: $id) { name } }",
+            "Variable `$id` of type `ID` is used in a position expecting type `ID!`.",
+        ),
+        (
+            "query Q($human: Human) { hero { __typename } }",
+            "Variable `$human` cannot be of non-input type `Human`.",
+        ),
+        (
+            "query Q { hero { __typename } } fragment F on Human { name }",
+            "Fragment `F` is never used.",
+        ),
+        (
+            "query Q { human(id: 1) { ...A } } fragment A on Human { ...B } fragment B on Human { ...A }",
+            "Cannot spread fragment `A` within itself via `B`.",
+        ),
+        (
+            "query Q { human(id: 1) { name: homePlanet name } }",
+            "Fields `name` conflict because `homePlanet` and `name` are different fields. Use different aliases on the fields to fetch both if this was intentional.",
+        ),
+        (
+            "query Q { human(id: 1) { height(unit: FOOT) height } }",
+            "Fields `height` conflict because they have differing arguments. Use different aliases on the fields to fetch both if this was intentional.",
+        ),
+        (
+            "query Q { hero { __typename ... on Human { value: height } ... on Droid { value: name } } }",
+            "Fields `value` conflict because they return conflicting types `Float` and `String!`. Use different aliases on the fields to fetch both if this was intentional.",
+        ),
+        (
+            "query Q @skip(if: true) { hero { __typename } }",
+            "Directive `@skip` may not be used on QUERY.",
+        ),
+        (
+            "query Q { hero @include { __typename } }",
+            "Directive `@include` is missing the required argument `if` of type `Boolean!`.",
+        ),
+    ];
+
+    for (query, expected_error) in cases.iter() {
+        let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+        let error = generate_module_token_stream_from_string(query, &schema_path, options)
+            .expect_err("Invalid documents should be rejected");
+        let messages = diagnostic_messages(error.as_ref());
+
+        assert!(
+            messages.iter().any(|message| message == expected_error),
+            "{:?} should contain {:?}",
+            messages,
+            expected_error
+        );
+    }
+
+    let valid_query = r#"
+        query Q($id: ID = "1000", $unit: LengthUnit, $withFriends: Boolean!) {
+          human(id: $id) { ...HumanFields }
+          hero {
+            __typename
+            ... on Human { value: name }
+            ... on Droid { value: name }
+            friends @include(if: $withFriends) { __typename }
+          }
+        }
+
+        fragment HumanFields on Human {
+          height(unit: $unit)
+        }
+    "#;
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    generate_module_token_stream_from_string(valid_query, &schema_path, options)
+        .expect("Valid documents should be accepted");
+}
+
+#[test]
+fn unknown_directives_are_passed_through_unless_denied() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let query = "query Q { hero @client { __typename } }";
+
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect("Client-only directives should be accepted");
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_deny_unknown_directives(true);
+    let error = generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect_err("Unknown directives should be rejected");
+
+    assert_eq!(
+        diagnostic_messages(error.as_ref()),
+        ["Unknown directive `@client`."]
+    );
+}
+
+#[test]
+fn diagnostics_are_aggregated_with_locations() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let query = "query Q {\n  hero(limit: 3) { __typename }\n  human { nope }\n}\n";
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_query_file(PathBuf::from("src/hero.graphql"));
+    let error = generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect_err("Invalid documents should be rejected");
+    let diagnostics = error.downcast_ref::<Diagnostics>().unwrap();
+
+    let locations: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.message(), diagnostic.line(), diagnostic.column()))
+        .collect();
+
+    assert_eq!(
+        locations,
+        [
+            (
+                "Unknown argument `limit` on field `Query.hero`.",
+                Some(2),
+                Some(3)
+            ),
+            (
+                "Field `Query.human` is missing the required argument `id` of type `ID!`.",
+                Some(3),
+                Some(3)
+            ),
+            ("No field named nope on Human", Some(3), Some(11)),
+        ]
+    );
+    assert!(error
+        .to_string()
+        .contains("  --> src/hero.graphql:2:3\n  |\n2 |   hero(limit: 3) { __typename }\n  |   ^"));
+}
+
+#[test]
+fn descriptions_are_rendered_as_doc_comments() {
+    let query = "query Users($filter: UserFilter) { users(filter: $filter) { name status } }";
+    let schema_path = build_schema_path("descriptions_schema.graphql");
+
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    assert_generated_code_contains(
+        generate_module_token_stream_from_string(query, &schema_path, options).unwrap(),
+        &[
+            "# [doc = \" A person with an account.\"] # [derive (Deserialize)]",
+            "# [doc = \" The name displayed on the profile.\"] pub name : String",
+            "# [doc = \" How available a user is.\"] # [derive",
+            "# [doc = \" Ready to chat.\"] ONLINE , OFFLINE ,",
+            "# [doc = \" Criteria for finding users.\"] # [derive (Serialize)]",
+            "# [doc = \" Only users whose name starts with this prefix.\"] # [serde (rename = \"namePrefix\")]",
+        ],
+    );
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_skip_descriptions(true);
+    let generated_code = generate_module_token_stream_from_string(query, &schema_path, options)
+        .unwrap()
+        .to_string();
+
+    assert!(!generated_code.contains("doc ="));
+}
+
+#[test]
+fn spreads_are_validated_against_inherited_interfaces() {
+    let schema_path = build_schema_path("../schema/tests/interface_inheritance_schema.graphql");
+
+    let query = "query Q { node(id: \"1\") { __typename ... on Image { width } } }";
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect("Image inherits from Node");
+
+    let query = "query Q { resources { __typename ... on Team { id } } }";
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    let error = generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect_err("Team is not a Resource");
+
+    assert_eq!(
+        diagnostic_messages(error.as_ref()),
+        ["The spread Resource... on Team is not valid."]
+    );
+}
+
+#[test]
+fn builder_writes_a_module_per_query_file() {
+    let out_dir =
+        std::env::temp_dir().join(format!("graphql_client_builder_{}", std::process::id()));
+
+    let generated = crate::Builder::new(build_schema_path("star_wars_schema.graphql"))
+        .query_path(build_schema_path("star_wars_query.graphql"))
+        .query_path(build_schema_path("foobars_query.graphql"))
+        .out_dir(&out_dir)
+        .mod_file("queries.rs")
+        .generate()
+        .unwrap_err();
+
+    // The foobars query does not match the star wars schema.
+    assert!(generated.to_string().contains("foobars"), "{}", generated);
+
+    let generated = crate::Builder::new(build_schema_path("star_wars_schema.graphql"))
+        .query_path(build_schema_path("star_wars_query.graphql"))
+        .out_dir(&out_dir)
+        .mod_file("queries.rs")
+        .generate()
+        .unwrap();
+
+    assert_eq!(
+        generated,
+        [
+            out_dir.join("star_wars_query.rs"),
+            out_dir.join("queries.rs")
+        ]
+    );
+
+    let module = std::fs::read_to_string(&generated[0]).unwrap();
+    assert!(module.contains("pub struct StarWarsQuery;"));
+    assert!(!module.starts_with("#!"));
+
+    let mod_file = std::fs::read_to_string(&generated[1]).unwrap();
+    assert!(mod_file.contains("pub mod star_wars_query {"));
+    assert!(mod_file.contains(&format!(
+        "include!({:?});",
+        generated[0].display().to_string()
+    )));
+
+    std::fs::remove_dir_all(out_dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths_are_reported_in_derive_mode() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = std::env::temp_dir().join(format!("graphql_client_non_utf8_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let schema_path = dir.join(std::ffi::OsStr::from_bytes(b"schema\xff.graphql"));
+    std::fs::copy(build_schema_path("star_wars_schema.graphql"), &schema_path).unwrap();
+
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Derive);
+    let error =
+        crate::generate_schema_types_token_stream(std::slice::from_ref(&schema_path), options)
+            .expect_err("include_str! needs UTF-8 paths");
+
+    assert_eq!(
+        error.to_string(),
+        format!("Path {} is not valid UTF-8", schema_path.display())
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn schema_types_cover_the_whole_schema() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    assert_generated_code_contains(
+        crate::generate_schema_types_token_stream(std::slice::from_ref(&schema_path), options)
+            .unwrap(),
+        &[
+            "pub enum Episode {",
+            "pub enum LengthUnit {",
+            "Other (String) ,",
+            "pub struct ReviewInput {",
+            "pub struct ColorInput {",
+        ],
+    );
+
+    // Operations re-export the shared types instead of defining them.
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_shared_types_module(syn::parse_quote!(crate::graphql_types));
+    let generated_code = generate_module_token_stream_from_string(
+        include_str!("star_wars_query.graphql"),
+        &schema_path,
+        options,
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated_code.contains("pub use crate :: graphql_types :: Episode ;"));
+    assert!(!generated_code.contains("pub enum Episode"));
+}
+
+#[test]
+fn scalars_are_mapped_to_rust_types() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_scalar_type("Int", syn::parse_quote!(i32));
+    let generated_code = generate_module_token_stream_from_string(
+        include_str!("star_wars_query.graphql"),
+        &schema_path,
+        options,
+    )
+    .unwrap()
+    .to_string();
+
+    assert!(generated_code.contains("type Int = i32 ;"));
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_scalar_type("String", syn::parse_quote!(Box<str>));
+    let error = generate_module_token_stream_from_string(
+        include_str!("star_wars_query.graphql"),
+        &schema_path,
+        options,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "The String scalar cannot be mapped to another type."
+    );
+}
+
+#[test]
+fn well_known_scalars_are_mapped_to_the_enabled_crates() {
+    let schema_path = build_schema_path("well_known_scalars_schema.graphql");
+    let query = "query Events($after: Instant) { events(after: $after) { id startsAt } }";
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_scalar_crates(vec![ScalarCrate::Time, ScalarCrate::Uuid]);
+    assert_generated_code_contains(
+        generate_module_token_stream_from_string(query, &schema_path, options).unwrap(),
+        &[
+            "type Instant = graphql_client :: time :: OffsetDateTime ;",
+            "type UUID = graphql_client :: uuid :: Uuid ;",
+            "# [serde (default , with = \"graphql_client::rfc3339\")] pub after : Option < Instant >",
+            "# [serde (default , with = \"graphql_client::rfc3339\")] # [serde (rename = \"startsAt\")] pub starts_at : Option < Instant >",
+        ],
+    );
+
+    // Explicit mappings take precedence.
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_scalar_crates(vec![ScalarCrate::Time]);
+    options.set_scalar_type("Instant", syn::parse_quote!(String));
+    let generated_code = generate_module_token_stream_from_string(query, &schema_path, options)
+        .unwrap()
+        .to_string();
+
+    assert!(generated_code.contains("type Instant = String ;"));
+    assert!(!generated_code.contains("rfc3339"));
+}
+
+#[test]
+fn typed_ids_are_reexported_from_the_shared_types_module() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let query = "query Ids { hero { __typename id } starship(id: \"1\") { id } }";
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_typed_ids(true);
+    options.set_shared_types_module(syn::parse_quote!(crate::graphql_types));
+    let generated_code = assert_generated_code_contains(
+        generate_module_token_stream_from_string(query, &schema_path, options).unwrap(),
+        &[
+            "pub use crate :: graphql_types :: CharacterId ;",
+            "pub use crate :: graphql_types :: StarshipId ;",
+            "pub id : CharacterId",
+            "pub id : StarshipId",
+        ],
+    );
+    assert!(!generated_code.contains("pub struct CharacterId"));
+}
+
+#[test]
+fn typed_id_inputs_are_checked_against_the_schema_and_query() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let query = "query Starship($id: ID!) { starship(id: $id) { id } }";
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_typed_ids(true);
+    options.set_typed_id_input("id", "Usr");
+    options.set_typed_id_input("shipId", "Starship");
+    options.set_typed_id_input("ReviewInput.stars", "Episode");
+    // Crate-wide variables are not declared by every query.
+    options.set_crate_wide_typed_id_input("humanId", "Human");
+    let error = generate_module_token_stream_from_string(query, &schema_path, options.clone())
+        .expect_err("Unknown typed ID inputs should be rejected");
+    let diagnostics = error.downcast_ref::<Diagnostics>().unwrap();
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message()).collect();
+
+    assert_eq!(
+        messages,
+        [
+            "The typed ID input `ReviewInput.stars` is not an `ID` input field of the schema.",
+            "The owner `Episode` of the typed ID input `ReviewInput.stars` is not an object or interface of the schema.",
+            "The owner `Usr` of the typed ID input `id` is not an object or interface of the schema.",
+            "The typed ID input `shipId` is not an `ID` variable of the query.",
+        ]
+    );
+
+    // The shared types have no variables to check.
+    options.set_typed_id_input("id", "Starship");
+    options.set_typed_id_input("ReviewInput.stars", "Review");
+    let error =
+        crate::generate_schema_types_token_stream(std::slice::from_ref(&schema_path), options)
+            .expect_err("Unknown typed ID input fields should be rejected");
+
+    assert_eq!(
+        error.to_string(),
+        "The typed ID input `ReviewInput.stars` is not an `ID` input field of the schema."
+    );
+}
+
+#[test]
+fn diagnostics_in_imported_fragments_point_at_their_file() {
+    let schema_path = build_schema_path("star_wars_schema.graphql");
+    let query = "#import \"./imported_fragments.graphql\"\nquery Q { hero { __typename ...HeroName ...HeroFriends } }\n";
+
+    let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Cli);
+    options.set_query_file(build_schema_path("query.graphql"));
+    let error = generate_module_token_stream_from_string(query, &schema_path, options)
+        .expect_err("Invalid documents should be rejected");
+    let diagnostics = error.downcast_ref::<Diagnostics>().unwrap();
+    let diagnostic = diagnostics.iter().next().unwrap();
+
+    assert_eq!(diagnostic.message(), "No field named nope on Character");
+    assert_eq!(
+        diagnostic.path(),
+        Some(build_schema_path("imported_fragments.graphql").as_path())
+    );
+    assert_eq!((diagnostic.line(), diagnostic.column()), (Some(9), Some(5)));
+    assert!(error.to_string().contains("9 |     nope\n"), "{}", error);
+}
@@ -10,6 +10,17 @@ impl GraphqlTypeQualifier {
     }
 }
 
+/// Render a type the way it is written in GraphQL documents, e.g. `[Int!]!`.
+pub(crate) fn graphql_type_string(name: &str, qualifiers: &[GraphqlTypeQualifier]) -> String {
+    qualifiers
+        .iter()
+        .rev()
+        .fold(name.to_owned(), |inner, qualifier| match qualifier {
+            GraphqlTypeQualifier::Required => format!("{}!", inner),
+            GraphqlTypeQualifier::List => format!("[{}]", inner),
+        })
+}
+
 pub fn graphql_parser_depth<'doc, T>(schema_type: &graphql_parser::schema::Type<'doc, T>) -> usize
 where
     T: graphql_parser::query::Text<'doc>,
@@ -0,0 +1,191 @@
+use crate::schema::StoredScalar;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The crates that well-known scalars can be mapped to, with the `scalar_crates` option.
+/// graphql_client re-exports them behind features of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ScalarCrate {
+    /// `DateTime` and `Date` as `chrono::DateTime<Utc>` and `chrono::NaiveDate`.
+    Chrono,
+    /// `DateTime` and `Date` as `time::OffsetDateTime` and `time::Date`.
+    Time,
+    /// `UUID` as `uuid::Uuid`.
+    Uuid,
+    /// `URL` and `URI` as `url::Url`.
+    Url,
+    /// `Decimal` as `rust_decimal::Decimal`.
+    RustDecimal,
+    /// `Bytes` as `serde_bytes::ByteBuf`, (de)serialized as base64 strings.
+    SerdeBytes,
+}
+
+impl std::str::FromStr for ScalarCrate {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.trim() {
+            "chrono" => Ok(ScalarCrate::Chrono),
+            "time" => Ok(ScalarCrate::Time),
+            "uuid" => Ok(ScalarCrate::Uuid),
+            "url" => Ok(ScalarCrate::Url),
+            "rust_decimal" => Ok(ScalarCrate::RustDecimal),
+            "serde_bytes" => Ok(ScalarCrate::SerdeBytes),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Parses a comma-separated list of crates, like `"chrono, uuid"`.
+pub fn parse_scalar_crates(list: &str) -> Result<Vec<ScalarCrate>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|scalar_crate| !scalar_crate.is_empty())
+        .map(|scalar_crate| {
+            scalar_crate.parse().map_err(|()| {
+                format!(
+                    "Invalid scalar crate `{}`, expected one of chrono, time, uuid, url, rust_decimal or serde_bytes",
+                    scalar_crate
+                )
+            })
+        })
+        .collect()
+}
+
+/// The Rust type of a well-known scalar, and the serde adapter its fields need, if any.
+pub(crate) struct WellKnownScalar {
+    pub(crate) rust_type: TokenStream,
+    pub(crate) serde_with: Option<&'static str>,
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    DateTime,
+    Date,
+    Uuid,
+    Url,
+    Decimal,
+    Bytes,
+}
+
+/// The type of the first of the enabled crates that knows the scalar.
+pub(crate) fn well_known_scalar(
+    scalar: &StoredScalar,
+    crates: &[ScalarCrate],
+) -> Option<WellKnownScalar> {
+    let kind = scalar
+        .specified_by_url
+        .as_deref()
+        .and_then(kind_by_url)
+        .or_else(|| kind_by_name(&scalar.name))?;
+    let candidates: &[ScalarCrate] = match kind {
+        Kind::DateTime | Kind::Date => &[ScalarCrate::Chrono, ScalarCrate::Time],
+        Kind::Uuid => &[ScalarCrate::Uuid],
+        Kind::Url => &[ScalarCrate::Url],
+        Kind::Decimal => &[ScalarCrate::RustDecimal],
+        Kind::Bytes => &[ScalarCrate::SerdeBytes],
+    };
+    let scalar_crate = candidates
+        .iter()
+        .find(|candidate| crates.contains(candidate))?;
+
+    let (rust_type, serde_with) = match (kind, scalar_crate) {
+        (Kind::DateTime, ScalarCrate::Chrono) => (
+            quote!(graphql_client::chrono::DateTime<graphql_client::chrono::Utc>),
+            None,
+        ),
+        (Kind::DateTime, _) => (
+            quote!(graphql_client::time::OffsetDateTime),
+            Some("graphql_client::rfc3339"),
+        ),
+        (Kind::Date, ScalarCrate::Chrono) => (quote!(graphql_client::chrono::NaiveDate), None),
+        (Kind::Date, _) => (quote!(graphql_client::time::Date), None),
+        (Kind::Uuid, _) => (quote!(graphql_client::uuid::Uuid), None),
+        (Kind::Url, _) => (quote!(graphql_client::url::Url), None),
+        (Kind::Decimal, _) => (quote!(graphql_client::rust_decimal::Decimal), None),
+        (Kind::Bytes, _) => (
+            quote!(graphql_client::serde_bytes::ByteBuf),
+            Some("graphql_client::base64"),
+        ),
+    };
+
+    Some(WellKnownScalar {
+        rust_type,
+        serde_with,
+    })
+}
+
+/// Matches the last segment of the URL, so that both `https://tools.ietf.org/html/rfc3339` and
+/// `https://www.rfc-editor.org/rfc/rfc3339.html` are recognized.
+fn kind_by_url(url: &str) -> Option<Kind> {
+    let url = url
+        .split('#')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let url = url.trim_end_matches('/');
+    let segment = url.rsplit('/').next().unwrap_or_default();
+    let segment = segment.trim_end_matches(".html").trim_end_matches(".txt");
+
+    match segment {
+        "rfc3339" | "date-time" => Some(Kind::DateTime),
+        "local-date" => Some(Kind::Date),
+        "rfc4122" | "rfc9562" => Some(Kind::Uuid),
+        "rfc3986" | "rfc3987" | "url.spec.whatwg.org" => Some(Kind::Url),
+        _ => None,
+    }
+}
+
+fn kind_by_name(name: &str) -> Option<Kind> {
+    match name {
+        "DateTime" => Some(Kind::DateTime),
+        "Date" | "LocalDate" => Some(Kind::Date),
+        "UUID" | "Uuid" => Some(Kind::Uuid),
+        "URL" | "Url" | "URI" | "Uri" => Some(Kind::Url),
+        "Decimal" | "BigDecimal" => Some(Kind::Decimal),
+        "Bytes" => Some(Kind::Bytes),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust_type(name: &str, url: Option<&str>, crates: &[ScalarCrate]) -> Option<String> {
+        let scalar = StoredScalar {
+            name: name.to_owned(),
+            specified_by_url: url.map(str::to_owned),
+        };
+        well_known_scalar(&scalar, crates).map(|scalar| scalar.rust_type.to_string())
+    }
+
+    #[test]
+    fn scalars_are_recognized_by_specified_by_url() {
+        let crates = [ScalarCrate::Uuid, ScalarCrate::Time];
+
+        assert_eq!(
+            rust_type("Id", Some("https://tools.ietf.org/html/rfc4122"), &crates).as_deref(),
+            Some("graphql_client :: uuid :: Uuid")
+        );
+        assert_eq!(
+            rust_type(
+                "Instant",
+                Some("https://scalars.graphql.org/andimarek/date-time.html"),
+                &crates
+            )
+            .as_deref(),
+            Some("graphql_client :: time :: OffsetDateTime")
+        );
+    }
+
+    #[test]
+    fn scalars_are_recognized_by_name() {
+        assert_eq!(
+            rust_type("DateTime", None, &[ScalarCrate::Time, ScalarCrate::Chrono]).as_deref(),
+            Some("graphql_client :: chrono :: DateTime < graphql_client :: chrono :: Utc >")
+        );
+        assert_eq!(rust_type("DateTime", None, &[ScalarCrate::Uuid]), None);
+        assert_eq!(rust_type("Timestamp", None, &[ScalarCrate::Chrono]), None);
+    }
+}
@@ -1,139 +1,359 @@
-use proc_macro2::TokenTree;
-use std::str::FromStr;
-use syn::Meta;
+use std::collections::BTreeSet;
+use syn::{ext::IdentExt, punctuated::Punctuated, LitStr, Token};
 
 use graphql_client_codegen::deprecation::DeprecationStrategy;
 use graphql_client_codegen::normalization::Normalization;
+use graphql_client_codegen::well_known_scalars::{parse_scalar_crates, ScalarCrate};
 
 const DEPRECATION_ERROR: &str = "deprecated must be one of 'allow', 'deny', or 'warn'";
 const NORMALIZATION_ERROR: &str = "normalization must be one of 'none' or 'rust'";
+const FRAGMENTS_OTHER_VARIANT_ERROR: &str = "fragments_other_variant must be 'true' or 'false'";
+
+/// The options accepted in `#[graphql(...)]` attributes.
+const OPTIONS: &[&str] = &[
+    "schema_path",
+    "query_path",
+    "query",
+    "variables_derives",
+    "response_derives",
+    "custom_scalars_module",
+    "scalars",
+    "scalar_crates",
+    "shared_types_module",
+    "extern_enums",
+    "variable_types",
+    "response_type",
+    "deprecated",
+    "normalization",
+    "fragments_other_variant",
+    "skip_serializing_none",
+    "skip_descriptions",
+    "builders",
+    "maybe_undefined",
+    "typed_ids",
+    "typed_id_inputs",
+    "minify_query",
+    "deny_unknown_directives",
+];
+
+/// The options of the `#[graphql(...)]` attributes on the struct under derive. They can be split
+/// across several attributes, but each of them can only be given once.
+#[derive(Default)]
+pub struct GraphQLAttributes {
+    /// The first attribute, for errors about missing options.
+    span: Option<proc_macro2::Span>,
+    /// Either `schema_path = "..."` or `schema_path("...", "...")`.
+    pub schema_paths: Vec<LitStr>,
+    pub query_path: Option<LitStr>,
+    pub query: Option<LitStr>,
+    pub variables_derives: Option<String>,
+    pub response_derives: Option<String>,
+    pub custom_scalars_module: Option<syn::Path>,
+    pub scalars: Vec<(String, syn::Type)>,
+    /// `scalar_crates = "chrono, uuid"`.
+    pub scalar_crates: Option<Vec<ScalarCrate>>,
+    pub shared_types_module: Option<syn::Path>,
+    pub extern_enums: Option<Vec<String>>,
+    pub variable_types: Option<Vec<String>>,
+    pub response_type: Option<String>,
+    pub deprecated: Option<DeprecationStrategy>,
+    pub normalization: Option<Normalization>,
+    pub fragments_other_variant: Option<bool>,
+    /// The flags below are `None` when absent, so that the crate-wide defaults apply.
+    pub skip_serializing_none: Option<bool>,
+    pub skip_descriptions: Option<bool>,
+    pub builders: Option<bool>,
+    pub maybe_undefined: Option<bool>,
+    pub typed_ids: Option<bool>,
+    /// `typed_id_inputs(userId = "User", StarInput.repositoryId = "Repository")`.
+    pub typed_id_inputs: Vec<(String, String)>,
+    pub minify_query: Option<bool>,
+    pub deny_unknown_directives: Option<bool>,
+}
 
-pub fn ident_exists(ast: &syn::DeriveInput, ident: &str) -> Result<(), syn::Error> {
-    let attribute = ast
-        .attrs
-        .iter()
-        .find(|attr| attr.path().is_ident("graphql"))
-        .ok_or_else(|| syn::Error::new_spanned(ast, "The graphql attribute is missing"))?;
-
-    if let Meta::List(list) = &attribute.meta {
-        for item in list.tokens.clone().into_iter() {
-            if let TokenTree::Ident(ident_) = item {
-                if ident_ == ident {
-                    return Ok(());
+impl GraphQLAttributes {
+    /// Parses every `#[graphql(...)]` attribute of the struct, reporting all the invalid ones.
+    pub fn from_ast(ast: &syn::DeriveInput) -> Result<Self, syn::Error> {
+        let mut attributes = GraphQLAttributes::default();
+        let mut seen = BTreeSet::new();
+        let mut errors: Option<syn::Error> = None;
+
+        for attribute in ast.attrs.iter().filter(|a| a.path().is_ident("graphql")) {
+            attributes
+                .span
+                .get_or_insert_with(|| syn::spanned::Spanned::span(attribute));
+
+            let result =
+                attribute.parse_nested_meta(|meta| attributes.parse_option(meta, &mut seen));
+
+            if let Err(error) = result {
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
                 }
             }
         }
+
+        if attributes.span.is_none() {
+            return Err(syn::Error::new_spanned(
+                ast,
+                "The graphql attribute is missing",
+            ));
+        }
+
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(attributes),
+        }
     }
 
-    Err(syn::Error::new_spanned(
-        ast,
-        format!("Ident `{}` not found", ident),
-    ))
-}
+    /// The span of the `#[graphql]` attribute.
+    pub fn span(&self) -> proc_macro2::Span {
+        self.span.unwrap_or_else(proc_macro2::Span::call_site)
+    }
 
-/// Extract an configuration parameter specified in the `graphql` attribute.
-pub fn extract_attr(ast: &syn::DeriveInput, attr: &str) -> Result<String, syn::Error> {
-    let attribute = ast
-        .attrs
-        .iter()
-        .find(|a| a.path().is_ident("graphql"))
-        .ok_or_else(|| syn::Error::new_spanned(ast, "The graphql attribute is missing"))?;
-
-    if let Meta::List(list) = &attribute.meta {
-        let mut iter = list.tokens.clone().into_iter();
-        while let Some(item) = iter.next() {
-            if let TokenTree::Ident(ident) = item {
-                if ident == attr {
-                    iter.next();
-                    if let Some(TokenTree::Literal(lit)) = iter.next() {
-                        let lit_str: syn::LitStr = syn::parse_str(&lit.to_string())?;
-                        return Ok(lit_str.value());
-                    }
-                }
+    fn parse_option(
+        &mut self,
+        meta: syn::meta::ParseNestedMeta<'_>,
+        seen: &mut BTreeSet<String>,
+    ) -> Result<(), syn::Error> {
+        let key = match meta.path.get_ident() {
+            Some(key) => key.to_string(),
+            None => return Err(meta.error("Expected the name of an option")),
+        };
+
+        if !OPTIONS.contains(&key.as_str()) {
+            return Err(meta.error(unknown_option_message(&key)));
+        }
+
+        if !seen.insert(key.clone()) {
+            return Err(meta.error(format!("`{}` is given more than once", key)));
+        }
+
+        match key.as_str() {
+            "schema_path" if meta.input.peek(syn::token::Paren) => {
+                self.schema_paths = parse_list(&meta)?.into_iter().collect()
+            }
+            "schema_path" => self.schema_paths = vec![parse_value(&meta)?],
+            "query_path" => self.query_path = Some(parse_value(&meta)?),
+            "query" => self.query = Some(parse_value(&meta)?),
+            "variables_derives" => self.variables_derives = Some(parse_value(&meta)?.value()),
+            "response_derives" => self.response_derives = Some(parse_value(&meta)?.value()),
+            "custom_scalars_module" => {
+                self.custom_scalars_module = Some(parse_value(&meta)?.parse()?)
+            }
+            "scalars" => self.scalars = parse_scalars(&meta)?,
+            "scalar_crates" => {
+                let value = parse_value(&meta)?;
+                self.scalar_crates = Some(
+                    parse_scalar_crates(&value.value())
+                        .map_err(|err| syn::Error::new(value.span(), err))?,
+                );
             }
+            "shared_types_module" => self.shared_types_module = Some(parse_value(&meta)?.parse()?),
+            "extern_enums" => self.extern_enums = Some(parse_list_values(&meta)?),
+            "variable_types" => self.variable_types = Some(parse_list_values(&meta)?),
+            "response_type" => self.response_type = Some(parse_value(&meta)?.value()),
+            "deprecated" => {
+                let value = parse_value(&meta)?;
+                let strategy = value.value().to_lowercase().parse();
+                self.deprecated =
+                    Some(strategy.map_err(|_| syn::Error::new(value.span(), DEPRECATION_ERROR))?);
+            }
+            "normalization" => {
+                let value = parse_value(&meta)?;
+                let normalization = value.value().to_lowercase().parse();
+                self.normalization = Some(
+                    normalization
+                        .map_err(|_| syn::Error::new(value.span(), NORMALIZATION_ERROR))?,
+                );
+            }
+            // Either a bare flag, or `fragments_other_variant = "true"`.
+            "fragments_other_variant" if meta.input.peek(Token![=]) => {
+                let value = parse_value(&meta)?;
+                self.fragments_other_variant =
+                    Some(value.value().parse().map_err(|_| {
+                        syn::Error::new(value.span(), FRAGMENTS_OTHER_VARIANT_ERROR)
+                    })?);
+            }
+            "fragments_other_variant" => self.fragments_other_variant = Some(true),
+            "skip_serializing_none" => self.skip_serializing_none = Some(parse_flag(&meta, &key)?),
+            "skip_descriptions" => self.skip_descriptions = Some(parse_flag(&meta, &key)?),
+            "builders" => self.builders = Some(parse_flag(&meta, &key)?),
+            "maybe_undefined" => self.maybe_undefined = Some(parse_flag(&meta, &key)?),
+            "typed_ids" => self.typed_ids = Some(parse_flag(&meta, &key)?),
+            "typed_id_inputs" => self.typed_id_inputs = parse_typed_id_inputs(&meta)?,
+            "minify_query" => self.minify_query = Some(parse_flag(&meta, &key)?),
+            "deny_unknown_directives" => {
+                self.deny_unknown_directives = Some(parse_flag(&meta, &key)?)
+            }
+            _ => unreachable!("every option is handled"),
         }
+
+        Ok(())
     }
+}
 
-    Err(syn::Error::new_spanned(
-        ast,
-        format!("Attribute `{}` not found", attr),
-    ))
+/// `key = "value"`
+fn parse_value(meta: &syn::meta::ParseNestedMeta<'_>) -> Result<LitStr, syn::Error> {
+    meta.value()?.parse()
 }
 
-/// Extract a list of configuration parameter values specified in the `graphql` attribute.
-pub fn extract_attr_list(ast: &syn::DeriveInput, attr: &str) -> Result<Vec<String>, syn::Error> {
-    let attribute = ast
-        .attrs
-        .iter()
-        .find(|a| a.path().is_ident("graphql"))
-        .ok_or_else(|| syn::Error::new_spanned(ast, "The graphql attribute is missing"))?;
-
-    let mut result = Vec::new();
-
-    if let Meta::List(list) = &attribute.meta {
-        let mut iter = list.tokens.clone().into_iter();
-        while let Some(item) = iter.next() {
-            if let TokenTree::Ident(ident) = item {
-                if ident == attr {
-                    if let Some(TokenTree::Group(group)) = iter.next() {
-                        for token in group.stream() {
-                            if let TokenTree::Literal(lit) = token {
-                                let lit_str: syn::LitStr = syn::parse_str(&lit.to_string())?;
-                                result.push(lit_str.value());
-                            }
-                        }
-                        return Ok(result);
-                    }
-                }
-            }
-        }
+/// `key("a", "b")`
+fn parse_list(meta: &syn::meta::ParseNestedMeta<'_>) -> Result<Vec<LitStr>, syn::Error> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+    let list = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+
+    if list.is_empty() {
+        return Err(meta.error("Expected at least one value"));
     }
 
-    if result.is_empty() {
-        Err(syn::Error::new_spanned(
-            ast,
-            format!("Attribute list `{}` not found or empty", attr),
-        ))
-    } else {
-        Ok(result)
+    Ok(list.into_iter().collect())
+}
+
+fn parse_list_values(meta: &syn::meta::ParseNestedMeta<'_>) -> Result<Vec<String>, syn::Error> {
+    Ok(parse_list(meta)?.iter().map(LitStr::value).collect())
+}
+
+/// `key(Scalar = "RustType", ...)`
+fn parse_scalars(
+    meta: &syn::meta::ParseNestedMeta<'_>,
+) -> Result<Vec<(String, syn::Type)>, syn::Error> {
+    parse_mapping(meta)?
+        .into_iter()
+        .map(|(name, rust_type)| {
+            let rust_type = rust_type.parse().map_err(|_| {
+                syn::Error::new(
+                    rust_type.span(),
+                    format!("The type of `{}` must be a Rust type", name),
+                )
+            })?;
+
+            Ok((name.to_string(), rust_type))
+        })
+        .collect()
+}
+
+/// `key(name = "value", ...)`
+fn parse_mapping(
+    meta: &syn::meta::ParseNestedMeta<'_>,
+) -> Result<Vec<(syn::Ident, LitStr)>, syn::Error> {
+    let mut mapping = Vec::new();
+
+    meta.parse_nested_meta(|entry| {
+        let name = match entry.path.get_ident() {
+            Some(name) => name.clone(),
+            None => return Err(entry.error("Expected a name")),
+        };
+        let value: LitStr = entry.value()?.parse()?;
+
+        mapping.push((name, value));
+        Ok(())
+    })?;
+
+    if mapping.is_empty() {
+        return Err(meta.error("Expected at least one value"));
     }
+
+    Ok(mapping)
 }
 
-/// Get the deprecation from a struct attribute in the derive case.
-pub fn extract_deprecation_strategy(
-    ast: &syn::DeriveInput,
-) -> Result<DeprecationStrategy, syn::Error> {
-    extract_attr(ast, "deprecated")?
-        .to_lowercase()
-        .as_str()
-        .parse()
-        .map_err(|_| syn::Error::new_spanned(ast, DEPRECATION_ERROR.to_owned()))
+/// `key(variable = "Type", Input.field = "Type", ...)`
+fn parse_typed_id_inputs(
+    meta: &syn::meta::ParseNestedMeta<'_>,
+) -> Result<Vec<(String, String)>, syn::Error> {
+    let mut mapping = Vec::new();
+
+    meta.parse_nested_meta(|entry| {
+        let mut name = match entry.path.get_ident() {
+            Some(name) => name.to_string(),
+            None => return Err(entry.error("Expected a name")),
+        };
+        if entry.input.peek(Token![.]) {
+            entry.input.parse::<Token![.]>()?;
+            let field = entry.input.call(syn::Ident::parse_any)?;
+            name = format!("{}.{}", name, field);
+        }
+        let owner: LitStr = entry.value()?.parse()?;
+
+        mapping.push((name, owner.value()));
+        Ok(())
+    })?;
+
+    if mapping.is_empty() {
+        return Err(meta.error("Expected at least one value"));
+    }
+
+    Ok(mapping)
 }
 
-/// Get the deprecation from a struct attribute in the derive case.
-pub fn extract_normalization(ast: &syn::DeriveInput) -> Result<Normalization, syn::Error> {
-    extract_attr(ast, "normalization")?
-        .to_lowercase()
-        .as_str()
-        .parse()
-        .map_err(|_| syn::Error::new_spanned(ast, NORMALIZATION_ERROR))
+/// Flags are bare idents, or take a boolean to turn off a crate-wide default: `builders = false`.
+fn parse_flag(meta: &syn::meta::ParseNestedMeta<'_>, key: &str) -> Result<bool, syn::Error> {
+    if meta.input.is_empty() || meta.input.peek(Token![,]) {
+        return Ok(true);
+    }
+
+    let value = meta.value()?;
+    let value: syn::LitBool = value.parse().map_err(|err| {
+        syn::Error::new(err.span(), format!("`{}` must be `true` or `false`", key))
+    })?;
+
+    Ok(value.value)
 }
 
-pub fn extract_fragments_other_variant(ast: &syn::DeriveInput) -> bool {
-    extract_attr(ast, "fragments_other_variant")
-        .ok()
-        .and_then(|s| FromStr::from_str(s.as_str()).ok())
-        .unwrap_or(false)
+fn unknown_option_message(key: &str) -> String {
+    let closest = OPTIONS
+        .iter()
+        .map(|option| (edit_distance(key, option), option))
+        .min();
+
+    match closest {
+        Some((distance, option)) if distance <= 3 => {
+            format!("Unknown option `{}`. Did you mean `{}`?", key, option)
+        }
+        _ => format!(
+            "Unknown option `{}`. Expected one of: {}",
+            key,
+            OPTIONS.join(", ")
+        ),
+    }
 }
 
-pub fn extract_skip_serializing_none(ast: &syn::DeriveInput) -> bool {
-    ident_exists(ast, "skip_serializing_none").is_ok()
+/// The Levenshtein distance between two words.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn parse(input: &str) -> Result<GraphQLAttributes, syn::Error> {
+        GraphQLAttributes::from_ast(&syn::parse_str(input).unwrap())
+    }
+
+    fn error(input: &str) -> String {
+        match parse(input) {
+            Ok(_) => panic!("parsed unexpectedly"),
+            Err(e) => e.to_string(),
+        }
+    }
+
     #[test]
     fn test_deprecation_strategy() {
         let input = "
@@ -145,11 +365,8 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert_eq!(
-            extract_deprecation_strategy(&parsed).unwrap(),
-            DeprecationStrategy::Warn
-        );
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.deprecated.unwrap(), DeprecationStrategy::Warn);
     }
 
     #[test]
@@ -163,11 +380,8 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert_eq!(
-            extract_deprecation_strategy(&parsed).unwrap(),
-            DeprecationStrategy::Deny
-        );
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.deprecated.unwrap(), DeprecationStrategy::Deny);
     }
 
     #[test]
@@ -181,11 +395,7 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        match extract_deprecation_strategy(&parsed) {
-            Ok(_) => panic!("parsed unexpectedly"),
-            Err(e) => assert_eq!(&format!("{}", e), DEPRECATION_ERROR),
-        };
+        assert_eq!(error(input), DEPRECATION_ERROR);
     }
 
     #[test]
@@ -199,8 +409,8 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(extract_fragments_other_variant(&parsed));
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.fragments_other_variant, Some(true));
     }
 
     #[test]
@@ -214,8 +424,8 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(!extract_fragments_other_variant(&parsed));
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.fragments_other_variant, Some(false));
     }
 
     #[test]
@@ -229,8 +439,7 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(!extract_fragments_other_variant(&parsed));
+        assert_eq!(error(input), FRAGMENTS_OTHER_VARIANT_ERROR);
     }
 
     #[test]
@@ -243,8 +452,8 @@ mod test {
         )]
         struct MyQuery;
         ";
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(!extract_fragments_other_variant(&parsed));
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.fragments_other_variant, None);
     }
 
     #[test]
@@ -258,8 +467,8 @@ mod test {
             )]
             struct MyQuery;
         "#;
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(extract_skip_serializing_none(&parsed));
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.skip_serializing_none, Some(true));
     }
 
     #[test]
@@ -272,8 +481,40 @@ mod test {
             )]
             struct MyQuery;
         "#;
-        let parsed = syn::parse_str(input).unwrap();
-        assert!(!extract_skip_serializing_none(&parsed));
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.skip_serializing_none, None);
+    }
+
+    #[test]
+    fn test_skip_descriptions() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(
+                schema_path = "x",
+                query_path = "x",
+                skip_descriptions
+            )]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.skip_descriptions, Some(true));
+        assert_eq!(parsed.skip_serializing_none, None);
+    }
+
+    #[test]
+    fn test_minify_query() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(
+                schema_path = "x",
+                query_path = "x",
+                minify_query
+            )]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.minify_query, Some(true));
+        assert_eq!(parsed.skip_descriptions, None);
     }
 
     #[test]
@@ -288,10 +529,10 @@ mod test {
             )]
             struct MyQuery;
         "#;
-        let parsed: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let parsed = parse(input).unwrap();
 
         assert_eq!(
-            extract_attr_list(&parsed, "extern_enums").ok().unwrap(),
+            parsed.extern_enums.unwrap(),
             vec!["Direction", "DistanceUnit"],
         );
     }
@@ -308,10 +549,10 @@ mod test {
             )]
             struct MyQuery;
         "#;
-        let parsed: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let parsed = parse(input).unwrap();
 
         assert_eq!(
-            extract_attr_list(&parsed, "variable_types").ok().unwrap(),
+            parsed.variable_types.unwrap(),
             vec!["extern_crate::Var1", "extern_crate::Var2"],
         );
     }
@@ -328,11 +569,177 @@ mod test {
             )]
             struct MyQuery;
         "#;
-        let parsed: syn::DeriveInput = syn::parse_str(input).unwrap();
+        let parsed = parse(input).unwrap();
+
+        assert_eq!(parsed.response_type.unwrap(), "extern_crate::Resp",);
+    }
 
+    #[test]
+    fn test_inline_query() {
+        let input = r##"
+            #[derive(GraphQLQuery)]
+            #[graphql(
+                schema_path = "x",
+                query = r#"query Q { "field" }"#,
+            )]
+            struct MyQuery;
+        "##;
+        let parsed = parse(input).unwrap();
+
+        assert_eq!(parsed.query.unwrap().value(), "query Q { \"field\" }");
+    }
+
+    #[test]
+    fn test_attributes_can_be_split() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y")]
+            #[graphql(response_derives = "Debug", skip_descriptions)]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+
+        assert_eq!(parsed.schema_paths[0].value(), "x");
+        assert_eq!(parsed.query_path.unwrap().value(), "y");
+        assert_eq!(parsed.response_derives.unwrap(), "Debug");
+        assert_eq!(parsed.skip_descriptions, Some(true));
+    }
+
+    #[test]
+    fn test_unknown_options_are_rejected() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", respone_derives = "Debug")]
+            struct MyQuery;
+        "#;
+        assert_eq!(
+            error(input),
+            "Unknown option `respone_derives`. Did you mean `response_derives`?"
+        );
+
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", serialize_everything)]
+            struct MyQuery;
+        "#;
+        assert!(error(input).starts_with(
+            "Unknown option `serialize_everything`. Expected one of: schema_path, query_path"
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_options_are_rejected() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y")]
+            #[graphql(query_path = "z")]
+            struct MyQuery;
+        "#;
+        assert_eq!(error(input), "`query_path` is given more than once");
+    }
+
+    #[test]
+    fn test_flags_take_booleans() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", builders = false, typed_ids = true)]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.builders, Some(false));
+        assert_eq!(parsed.typed_ids, Some(true));
+        assert_eq!(parsed.maybe_undefined, None);
+
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", skip_descriptions = "true")]
+            struct MyQuery;
+        "#;
+        assert_eq!(
+            error(input),
+            "`skip_descriptions` must be `true` or `false`"
+        );
+    }
+
+    #[test]
+    fn test_scalars_are_mapped_to_rust_types() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(
+                schema_path = "x",
+                query_path = "y",
+                scalars(Int = "i32", DateTime = "chrono::DateTime<chrono::Utc>")
+            )]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        let expected: [(String, syn::Type); 2] = [
+            ("Int".to_owned(), syn::parse_quote!(i32)),
+            (
+                "DateTime".to_owned(),
+                syn::parse_quote!(chrono::DateTime<chrono::Utc>),
+            ),
+        ];
+        assert_eq!(parsed.scalars, expected);
+    }
+
+    #[test]
+    fn test_scalars_must_map_to_rust_types() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", scalars(Int = "i 32"))]
+            struct MyQuery;
+        "#;
+        assert_eq!(error(input), "The type of `Int` must be a Rust type");
+    }
+
+    #[test]
+    fn test_scalar_crates_are_parsed() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", scalar_crates = "chrono, uuid")]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        assert_eq!(
+            parsed.scalar_crates,
+            Some(vec![ScalarCrate::Chrono, ScalarCrate::Uuid])
+        );
+    }
+
+    #[test]
+    fn test_scalar_crates_must_be_known() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(schema_path = "x", query_path = "y", scalar_crates = "chrono, jiff")]
+            struct MyQuery;
+        "#;
+        assert_eq!(
+            error(input),
+            "Invalid scalar crate `jiff`, expected one of chrono, time, uuid, url, rust_decimal or serde_bytes"
+        );
+    }
+
+    #[test]
+    fn test_typed_id_inputs_are_mapped_to_types() {
+        let input = r#"
+            #[derive(GraphQLQuery)]
+            #[graphql(
+                schema_path = "x",
+                query_path = "y",
+                typed_ids,
+                typed_id_inputs(userId = "User", StarInput.repositoryId = "Repository")
+            )]
+            struct MyQuery;
+        "#;
+        let parsed = parse(input).unwrap();
+        assert_eq!(parsed.typed_ids, Some(true));
         assert_eq!(
-            extract_attr(&parsed, "response_type").ok().unwrap(),
-            "extern_crate::Resp",
+            parsed.typed_id_inputs,
+            [
+                ("userId".to_owned(), "User".to_owned()),
+                ("StarInput.repositoryId".to_owned(), "Repository".to_owned())
+            ]
         );
     }
 }
@@ -0,0 +1,410 @@
+//! The function-like macros. In `graphql!`, the query document is written as tokens, and the
+//! generated code is the one of the derive on a struct named after the operation, in a block
+//! evaluating to that struct.
+//! `schema_types!` generates the types of the schema shared by the operations.
+
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::quote;
+
+const OPERATION_KINDS: &[&str] = &["query", "mutation", "subscription"];
+
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, syn::Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let (options, start) = parse_options(&tokens);
+
+    let document_span = tokens
+        .get(start)
+        .map(TokenTree::span)
+        .unwrap_or_else(Span::call_site);
+    let (name, document) = name_operation(tokens[start..].to_vec()).ok_or_else(|| {
+        syn::Error::new(
+            document_span,
+            "The query document must contain a query, mutation or subscription.",
+        )
+    })?;
+
+    let mut source = String::new();
+    print_document(document, &mut source)?;
+
+    let query = syn::LitStr::new(&source, document_span);
+    let ident = Ident::new(&name, Span::call_site());
+    let ast: syn::DeriveInput = syn::parse_quote! {
+        #[graphql(#(#options,)* query = #query)]
+        struct #ident;
+    };
+    let generated = crate::expand_derive(&ast)?;
+
+    // A block expression, so that the operation is a value, as in `let viewer = graphql!(...)`.
+    Ok(quote! {
+        {
+            struct #ident;
+
+            #generated
+
+            #ident
+        }
+    })
+}
+
+/// Expands `schema_types!` to the enums, input objects and custom scalars of the schema. It only
+/// takes options.
+pub(crate) fn expand_schema_types(input: TokenStream) -> Result<TokenStream, syn::Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let (options, start) = parse_options(&tokens);
+
+    if let Some(token) = tokens.get(start) {
+        return Err(syn::Error::new(
+            token.span(),
+            "Expected a `key = \"value\"` option.",
+        ));
+    }
+
+    let ast: syn::DeriveInput = syn::parse_quote! {
+        #[graphql(#(#options),*)]
+        struct SchemaTypes;
+    };
+
+    crate::expand_schema_types(&ast)
+}
+
+/// The leading `key = "value"` and `key(...)` options, as `#[graphql]` attribute options, and
+/// the index of the first token after them.
+fn parse_options(tokens: &[TokenTree]) -> (Vec<TokenStream>, usize) {
+    let mut options = Vec::new();
+    let mut start = 0;
+
+    loop {
+        let (key, value, len) = match &tokens[start..] {
+            [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value), ..]
+                if eq.as_char() == '=' =>
+            {
+                (key, quote!(= #value), 3)
+            }
+            // Anonymous operations with variables, like `query($id: ID!)`, are not options.
+            [TokenTree::Ident(key), TokenTree::Group(group), ..]
+                if group.delimiter() == Delimiter::Parenthesis
+                    && !OPERATION_KINDS.iter().any(|kind| key == kind) =>
+            {
+                (key, quote!(#group), 2)
+            }
+            _ => break,
+        };
+
+        let key = if key == "schema" {
+            Ident::new("schema_path", key.span())
+        } else {
+            key.clone()
+        };
+        options.push(quote!(#key #value));
+        start += len;
+
+        if let Some(TokenTree::Punct(comma)) = tokens.get(start) {
+            if comma.as_char() == ',' {
+                start += 1;
+            }
+        }
+    }
+
+    (options, start)
+}
+
+/// Finds the first operation of the document and returns its name. Anonymous operations are
+/// named after their first field and their kind, and the name is inserted in the document.
+fn name_operation(mut document: Vec<TokenTree>) -> Option<(String, Vec<TokenTree>)> {
+    let mut index = 0;
+
+    while index < document.len() {
+        match &document[index] {
+            TokenTree::Ident(ident) if ident == "fragment" => {
+                // Skip the fragment up to the end of its selection set.
+                index += document[index..].iter().position(is_selection_set)?;
+            }
+            TokenTree::Ident(ident) if OPERATION_KINDS.iter().any(|kind| ident == kind) => {
+                if let Some(TokenTree::Ident(name)) = document.get(index + 1) {
+                    return Some((name.to_string(), document));
+                }
+
+                let selection_set = document[index..]
+                    .iter()
+                    .find(|token| is_selection_set(token))?;
+                let name = anonymous_operation_name(selection_set, &ident.to_string());
+                document.insert(index + 1, Ident::new(&name, ident.span()).into());
+
+                return Some((name, document));
+            }
+            token if is_selection_set(token) => {
+                let name = anonymous_operation_name(token, "query");
+                let span = token.span();
+                document.splice(
+                    index..index,
+                    vec![
+                        Ident::new("query", span).into(),
+                        Ident::new(&name, span).into(),
+                    ],
+                );
+
+                return Some((name, document));
+            }
+            _ => (),
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+fn is_selection_set(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+}
+
+/// `ViewerQuery` for `query { viewer { login } }`.
+fn anonymous_operation_name(selection_set: &TokenTree, kind: &str) -> String {
+    let first_field = match selection_set {
+        TokenTree::Group(group) => match group.stream().into_iter().next() {
+            Some(TokenTree::Ident(field)) => field.to_string(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    };
+
+    first_field
+        .split('_')
+        .chain(std::iter::once(kind))
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .concat()
+}
+
+/// Prints the tokens as GraphQL source. Tokens are separated by spaces, except after joint
+/// punctuation and the prefixes of `...Fragment`, `$variable`, `@directive` and `-1`.
+///
+/// Literals are copied as is, so the ones that would mean something else in GraphQL than in
+/// Rust are rejected.
+fn print_document(
+    tokens: impl IntoIterator<Item = TokenTree>,
+    source: &mut String,
+) -> Result<(), syn::Error> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut joined = true;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if !joined {
+            source.push(' ');
+        }
+        joined = false;
+
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+
+                source.push_str(open);
+                print_document(group.stream(), source)?;
+                source.push_str(close);
+            }
+            TokenTree::Punct(punct) => {
+                source.push(punct.as_char());
+                joined = punct.spacing() == Spacing::Joint || "$-.@".contains(punct.as_char());
+            }
+            TokenTree::Ident(ident) => source.push_str(&ident.to_string()),
+            TokenTree::Literal(literal) => {
+                check_literal(literal, &tokens[index..])?;
+                source.push_str(&literal.to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a literal, followed by the `rest` of the tokens, reads the same in GraphQL.
+fn check_literal(literal: &Literal, rest: &[TokenTree]) -> Result<(), syn::Error> {
+    // `"""text"""` is tokenized as the `""`, `"text"` and `""` strings.
+    if let [_, text @ TokenTree::Literal(_), TokenTree::Literal(close), ..] = rest {
+        if literal.to_string() == "\"\"" && close.to_string() == "\"\"" && is_string(text) {
+            return Err(syn::Error::new(
+                literal.span(),
+                "Block strings cannot be written in `graphql!`. Use a string with `\\n` escapes, or a query file.",
+            ));
+        }
+    }
+
+    let text = literal.to_string();
+    let valid = match text.strip_prefix('"') {
+        Some(content) => matches!(
+            content.strip_suffix('"'),
+            Some(content) if is_graphql_string_content(content)
+        ),
+        None => is_graphql_number(&text),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(syn::Error::new(
+            literal.span(),
+            format!(
+                "`{}` is not a GraphQL value. Strings can only use the `\\\"`, `\\\\`, `\\n`, `\\r` and `\\t` escapes, and numbers are written like `3`, `2.5` or `1e3`.",
+                text
+            ),
+        ))
+    }
+}
+
+fn is_string(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Literal(literal) if literal.to_string().starts_with('"'))
+}
+
+/// The escapes that GraphQL and Rust strings share. Line breaks are not allowed in GraphQL
+/// strings.
+fn is_graphql_string_content(content: &str) -> bool {
+    let mut chars = content.chars();
+
+    while let Some(c) = chars.next() {
+        let valid = match c {
+            '\\' => matches!(chars.next(), Some('"' | '\\' | 'n' | 'r' | 't')),
+            c => c == '\t' || !c.is_control(),
+        };
+
+        if !valid {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Whether the literal is a GraphQL `IntValue` or `FloatValue`, without its sign. Rust also
+/// allows suffixes, `_` separators, leading zeros and other bases.
+fn is_graphql_number(text: &str) -> bool {
+    fn digits(text: &str) -> (&str, &str) {
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(end)
+    }
+
+    let (integer, rest) = digits(text);
+    if integer.is_empty() || (integer.len() > 1 && integer.starts_with('0')) {
+        return false;
+    }
+
+    let rest = match rest.strip_prefix('.') {
+        Some(rest) => match digits(rest) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+
+    let rest = match rest.strip_prefix(['e', 'E']) {
+        Some(rest) => match digits(rest.strip_prefix(['+', '-']).unwrap_or(rest)) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(document: &str) -> (String, String) {
+        let tokens: TokenStream = document.parse().unwrap();
+        let (name, tokens) = name_operation(tokens.into_iter().collect()).unwrap();
+        let mut source = String::new();
+        print_document(tokens, &mut source).unwrap();
+        (name, source)
+    }
+
+    fn print_error(document: &str) -> Option<String> {
+        let tokens: TokenStream = document.parse().unwrap();
+        print_document(tokens, &mut String::new())
+            .err()
+            .map(|err| err.to_string())
+    }
+
+    #[test]
+    fn named_operations_keep_their_name() {
+        assert_eq!(
+            named("fragment F on User { id } query Viewer($first: Int = -1) { viewer { ...F @include(if: true) } }"),
+            (
+                "Viewer".to_owned(),
+                "fragment F on User {id} query Viewer ($first : Int = -1) {viewer {...F @include (if : true)}}"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn anonymous_operations_are_named_after_their_first_field() {
+        assert_eq!(
+            named("{ current_user { login } }"),
+            (
+                "CurrentUserQuery".to_owned(),
+                "query CurrentUserQuery {current_user {login}}".to_owned()
+            )
+        );
+        assert_eq!(
+            named("mutation($name: String) { rename(name: $name) }"),
+            (
+                "RenameMutation".to_owned(),
+                "mutation RenameMutation ($name : String) {rename (name : $name)}".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn literals_are_copied_when_they_mean_the_same_in_graphql() {
+        assert_eq!(
+            named(r#"query Q { search(text: "say \"hi\"\n", first: 10, score: 2.5e-3) }"#).1,
+            r#"query Q {search (text : "say \"hi\"\n" , first : 10 , score : 2.5e-3)}"#
+        );
+        assert_eq!(print_error(r#"{ a(list: ["" "b"]) }"#), None);
+    }
+
+    #[test]
+    fn literals_that_differ_in_graphql_are_rejected() {
+        assert!(print_error(r#"{ a(text: """block""") }"#)
+            .unwrap()
+            .starts_with("Block strings cannot be written in `graphql!`"));
+
+        for literal in [
+            r#"r"raw""#,
+            r#""\u{e9}""#,
+            r#""\x41""#,
+            r#""\0""#,
+            "'c'",
+            "0x10",
+            "1_000",
+            "007",
+            "1.",
+            "10u8",
+        ] {
+            let error = print_error(&format!("{{ a(value: {}) }}", literal));
+            assert!(
+                error
+                    .as_deref()
+                    .unwrap_or_default()
+                    .contains("is not a GraphQL value"),
+                "{} should be rejected: {:?}",
+                literal,
+                error
+            );
+        }
+    }
+}
@@ -3,8 +3,13 @@ extern crate proc_macro;
 /// Derive-related code. This will be moved into graphql_query_derive.
 mod attributes;
 
+use attributes::GraphQLAttributes;
+mod graphql_macro;
+
 use graphql_client_codegen::{
-    generate_module_token_stream, CodegenMode, GraphQLClientCodegenOptions,
+    generate_module_token_stream_from_string_with_schema_paths,
+    generate_module_token_stream_with_schema_paths, generate_schema_types_token_stream,
+    manifest::ManifestDefaults, CodegenMode, Diagnostics, GraphQLClientCodegenOptions,
 };
 use std::{
     env,
@@ -21,93 +26,352 @@ pub fn derive_graphql_query(input: proc_macro::TokenStream) -> proc_macro::Token
     }
 }
 
+/// Generates a typed operation from a schema and a query document written inline.
+///
+/// ```ignore
+/// let viewer = graphql_client::graphql!(
+///     schema = "schema.graphql",
+///     variables_derives = "Default",
+///     query Viewer {
+///         viewer {
+///             login
+///         }
+///     }
+/// );
+///
+/// fn build_query<Q: GraphQLQuery>(_operation: &Q, variables: Q::Variables) -> QueryBody<Q::Variables> {
+///     Q::build_query(variables)
+/// }
+///
+/// let body = build_query(&viewer, Default::default());
+/// ```
+///
+/// This expands to a block with a `Viewer` struct and a `viewer` module, as if the query had been
+/// given to `#[derive(GraphQLQuery)]`, and evaluates to the `Viewer` value. Their name comes from
+/// the operation, or from its first field and its kind (e.g. `ViewerQuery`) for anonymous
+/// operations. The other `key = "value"` options are the ones of the `#[graphql]` attribute.
+///
+/// Block strings, and the literals that Rust reads differently from GraphQL, like `"\u{e9}"` or
+/// `1_000`, are rejected.
+#[proc_macro]
+pub fn graphql(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match graphql_macro::expand(TokenStream::from(input)) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates the enums, input objects and custom scalars of a whole schema, for operations
+/// pointing to this module with `shared_types_module`.
+///
+/// ```ignore
+/// mod graphql_types {
+///     graphql_client::schema_types!(schema = "schema.graphql", variables_derives = "Debug");
+/// }
+///
+/// #[derive(GraphQLQuery)]
+/// #[graphql(
+///     schema_path = "schema.graphql",
+///     query_path = "queries/hero.graphql",
+///     shared_types_module = "crate::graphql_types"
+/// )]
+/// struct Hero;
+/// ```
+///
+/// The macro takes the `key = "value"` options of the `#[graphql]` attribute that apply to
+/// schema types, and should be the only item of its module. The derives of the shared types are
+/// the `variables_derives` and `response_derives` given here: the ones of the operations do not
+/// apply to them.
+#[proc_macro]
+pub fn schema_types(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match graphql_macro::expand_schema_types(TokenStream::from(input)) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 fn graphql_query_derive_inner(
     input: proc_macro::TokenStream,
 ) -> Result<proc_macro::TokenStream, syn::Error> {
     let input = TokenStream::from(input);
     let ast = syn::parse2(input)?;
-    let (query_path, schema_path) = build_query_and_schema_path(&ast)?;
-    let options = build_graphql_client_derive_options(&ast, query_path.clone())?;
 
-    generate_module_token_stream(query_path, &schema_path, options)
-        .map(Into::into)
-        .map_err(|err| {
-            syn::Error::new_spanned(
+    expand_derive(&ast).map(Into::into)
+}
+
+/// Generates the module and `GraphQLQuery` impl for the struct under derive.
+fn expand_derive(ast: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let attributes = GraphQLAttributes::from_ast(ast)?;
+    let cargo_manifest_dir = env::var("CARGO_MANIFEST_DIR").map_err(|_err| {
+        syn::Error::new_spanned(
+            ast,
+            "Error checking that the CARGO_MANIFEST_DIR env variable is defined.",
+        )
+    })?;
+    let defaults = read_manifest_defaults(Path::new(&cargo_manifest_dir), &attributes)?;
+    let (query, schema_paths) =
+        build_query_and_schema_paths(&cargo_manifest_dir, &attributes, &defaults)?;
+
+    match query {
+        QuerySource::Path(query_path) => {
+            let options = build_graphql_client_derive_options(
                 ast,
-                format!("Failed to generate GraphQLQuery impl: {}", err),
+                attributes,
+                &defaults,
+                Some(query_path.clone()),
+            )?;
+
+            generate_module_token_stream_with_schema_paths(query_path, &schema_paths, options)
+                .map_err(|err| codegen_error(ast, None, err.as_ref()))
+        }
+        QuerySource::Inline(query) => {
+            let options = build_graphql_client_derive_options(ast, attributes, &defaults, None)?;
+
+            generate_module_token_stream_from_string_with_schema_paths(
+                &query.value(),
+                &schema_paths,
+                options,
             )
-        })
+            .map_err(|err| codegen_error(ast, Some(&query), err.as_ref()))
+        }
+    }
 }
 
-fn build_query_and_schema_path(input: &syn::DeriveInput) -> Result<(PathBuf, PathBuf), syn::Error> {
+/// Generates the schema types for the options of `schema_types!`.
+fn expand_schema_types(ast: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let attributes = GraphQLAttributes::from_ast(ast)?;
     let cargo_manifest_dir = env::var("CARGO_MANIFEST_DIR").map_err(|_err| {
         syn::Error::new_spanned(
-            input,
+            ast,
             "Error checking that the CARGO_MANIFEST_DIR env variable is defined.",
         )
     })?;
 
-    let query_path = attributes::extract_attr(input, "query_path")?;
-    let query_path = format!("{}/{}", cargo_manifest_dir, query_path);
-    let query_path = Path::new(&query_path).to_path_buf();
-    let schema_path = attributes::extract_attr(input, "schema_path")?;
-    let schema_path = Path::new(&cargo_manifest_dir).join(schema_path);
-    Ok((query_path, schema_path))
+    if let Some(query) = attributes.query_path.as_ref().or(attributes.query.as_ref()) {
+        return Err(syn::Error::new(
+            query.span(),
+            "The schema types do not depend on a query.",
+        ));
+    }
+
+    let defaults = read_manifest_defaults(Path::new(&cargo_manifest_dir), &attributes)?;
+    let schema_paths = build_schema_paths(&cargo_manifest_dir, &attributes, &defaults)?;
+    let span = attributes.span();
+    let options = build_graphql_client_derive_options(ast, attributes, &defaults, None)?;
+
+    generate_schema_types_token_stream(&schema_paths, options)
+        .map_err(|err| syn::Error::new(span, format!("Failed to generate schema types: {}", err)))
+}
+
+/// Where the query document comes from: a `query_path` file, or a `query` string literal.
+enum QuerySource {
+    Path(PathBuf),
+    Inline(syn::LitStr),
+}
+
+/// Reports every diagnostic as its own compile error, pointing at the inline query when there is
+/// one, or else at the `#[graphql]` attribute.
+fn codegen_error(
+    ast: &syn::DeriveInput,
+    inline_query: Option<&syn::LitStr>,
+    err: &(dyn std::error::Error + 'static),
+) -> syn::Error {
+    let new_error = |message: &dyn std::fmt::Display| {
+        let message = format!("Failed to generate GraphQLQuery impl: {}", message);
+
+        if let Some(inline_query) = inline_query {
+            return syn::Error::new(inline_query.span(), message);
+        }
+
+        match ast
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("graphql"))
+        {
+            Some(attr) => syn::Error::new_spanned(attr, message),
+            None => syn::Error::new_spanned(ast, message),
+        }
+    };
+
+    let diagnostics = match err.downcast_ref::<Diagnostics>() {
+        Some(diagnostics) => diagnostics,
+        None => return new_error(&err),
+    };
+
+    diagnostics
+        .iter()
+        .map(|diagnostic| new_error(diagnostic))
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+        .unwrap_or_else(|| new_error(&err))
+}
+
+/// The defaults of `[package.metadata.graphql-client]` in the crate's `Cargo.toml`.
+fn read_manifest_defaults(
+    cargo_manifest_dir: &Path,
+    attributes: &GraphQLAttributes,
+) -> Result<ManifestDefaults, syn::Error> {
+    let manifest_path = cargo_manifest_dir.join("Cargo.toml");
+
+    if !manifest_path.is_file() {
+        return Ok(ManifestDefaults::default());
+    }
+
+    ManifestDefaults::read(&manifest_path)
+        .map_err(|err| syn::Error::new(attributes.span(), err.to_string()))
+}
+
+fn build_query_and_schema_paths(
+    cargo_manifest_dir: &str,
+    attributes: &GraphQLAttributes,
+    defaults: &ManifestDefaults,
+) -> Result<(QuerySource, Vec<PathBuf>), syn::Error> {
+    let query = match (&attributes.query_path, &attributes.query) {
+        (Some(_), Some(query)) => {
+            return Err(syn::Error::new(
+                query.span(),
+                "Only one of `query_path` and `query` can be given.",
+            ))
+        }
+        (Some(query_path), None) => {
+            let query_path = format!("{}/{}", cargo_manifest_dir, query_path.value());
+            QuerySource::Path(Path::new(&query_path).to_path_buf())
+        }
+        (None, Some(query)) => QuerySource::Inline(query.clone()),
+        (None, None) => {
+            return Err(syn::Error::new(
+                attributes.span(),
+                "Either `query_path` or `query` must be given.",
+            ))
+        }
+    };
+
+    Ok((
+        query,
+        build_schema_paths(cargo_manifest_dir, attributes, defaults)?,
+    ))
+}
+
+fn build_schema_paths(
+    cargo_manifest_dir: &str,
+    attributes: &GraphQLAttributes,
+    defaults: &ManifestDefaults,
+) -> Result<Vec<PathBuf>, syn::Error> {
+    // The attribute overrides the schema paths of the crate's `Cargo.toml`.
+    let schema_paths: Vec<PathBuf> = if attributes.schema_paths.is_empty() {
+        defaults.schema_paths()
+    } else {
+        attributes
+            .schema_paths
+            .iter()
+            .map(|schema_path| Path::new(cargo_manifest_dir).join(schema_path.value()))
+            .collect()
+    };
+
+    if schema_paths.is_empty() {
+        return Err(syn::Error::new(
+            attributes.span(),
+            "`schema_path` must be given, in the attribute or in the [package.metadata.graphql-client] table of Cargo.toml.",
+        ));
+    }
+
+    Ok(schema_paths)
 }
 
 fn build_graphql_client_derive_options(
     input: &syn::DeriveInput,
-    query_path: PathBuf,
+    attributes: GraphQLAttributes,
+    defaults: &ManifestDefaults,
+    query_path: Option<PathBuf>,
 ) -> Result<GraphQLClientCodegenOptions, syn::Error> {
-    let variables_derives = attributes::extract_attr(input, "variables_derives").ok();
-    let response_derives = attributes::extract_attr(input, "response_derives").ok();
-    let custom_scalars_module = attributes::extract_attr(input, "custom_scalars_module").ok();
-    let extern_enums = attributes::extract_attr_list(input, "extern_enums").ok();
-    let fragments_other_variant: bool = attributes::extract_fragments_other_variant(input);
-    let skip_serializing_none: bool = attributes::extract_skip_serializing_none(input);
-    let custom_variable_types = attributes::extract_attr_list(input, "variable_types").ok();
-    let custom_response_type = attributes::extract_attr(input, "response_type").ok();
-
     let mut options = GraphQLClientCodegenOptions::new(CodegenMode::Derive);
-    options.set_query_file(query_path);
-    options.set_fragments_other_variant(fragments_other_variant);
-    options.set_skip_serializing_none(skip_serializing_none);
+    if let Some(query_path) = query_path {
+        options.set_query_file(query_path);
+    }
+
+    // The crate-wide defaults, which the attributes below override.
+    defaults
+        .apply(&mut options)
+        .map_err(|err| syn::Error::new(attributes.span(), err.to_string()))?;
+
+    if let Some(fragments_other_variant) = attributes.fragments_other_variant {
+        options.set_fragments_other_variant(fragments_other_variant);
+    }
+    if let Some(skip_serializing_none) = attributes.skip_serializing_none {
+        options.set_skip_serializing_none(skip_serializing_none);
+    }
+    if let Some(skip_descriptions) = attributes.skip_descriptions {
+        options.set_skip_descriptions(skip_descriptions);
+    }
+    if let Some(builders) = attributes.builders {
+        options.set_builders(builders);
+    }
+    if let Some(maybe_undefined) = attributes.maybe_undefined {
+        options.set_maybe_undefined(maybe_undefined);
+    }
+    if let Some(typed_ids) = attributes.typed_ids {
+        options.set_typed_ids(typed_ids);
+    }
+    for (name, owner) in attributes.typed_id_inputs {
+        options.set_typed_id_input(name, owner);
+    }
+    if let Some(minify_query) = attributes.minify_query {
+        options.set_minify_query(minify_query);
+    }
+    if let Some(deny_unknown_directives) = attributes.deny_unknown_directives {
+        options.set_deny_unknown_directives(deny_unknown_directives);
+    }
 
-    if let Some(variables_derives) = variables_derives {
+    if let Some(variables_derives) = attributes.variables_derives {
         options.set_variables_derives(variables_derives);
     };
 
-    if let Some(response_derives) = response_derives {
+    if let Some(response_derives) = attributes.response_derives {
         options.set_response_derives(response_derives);
     };
 
     // The user can determine what to do about deprecations.
-    if let Ok(deprecation_strategy) = attributes::extract_deprecation_strategy(input) {
+    if let Some(deprecation_strategy) = attributes.deprecated {
         options.set_deprecation_strategy(deprecation_strategy);
     };
 
     // The user can specify the normalization strategy.
-    if let Ok(normalization) = attributes::extract_normalization(input) {
+    if let Some(normalization) = attributes.normalization {
         options.set_normalization(normalization);
     };
 
     // The user can give a path to a module that provides definitions for the custom scalars.
-    if let Some(custom_scalars_module) = custom_scalars_module {
-        let custom_scalars_module = syn::parse_str(&custom_scalars_module)?;
-
+    if let Some(custom_scalars_module) = attributes.custom_scalars_module {
         options.set_custom_scalars_module(custom_scalars_module);
     }
 
+    // The user can map scalars to Rust types, instead of defining aliases for them.
+    for (scalar, rust_type) in attributes.scalars {
+        options.set_scalar_type(scalar, rust_type);
+    }
+
+    // The user can map the well-known scalars to the crates graphql_client re-exports.
+    if let Some(scalar_crates) = attributes.scalar_crates {
+        options.set_scalar_crates(scalar_crates);
+    }
+
+    // The user can point to a module that defines the schema types, instead of generating them.
+    if let Some(shared_types_module) = attributes.shared_types_module {
+        options.set_shared_types_module(shared_types_module);
+    }
+
     // The user can specify a list of enums types that are defined externally, rather than generated by this library
-    if let Some(extern_enums) = extern_enums {
+    if let Some(extern_enums) = attributes.extern_enums {
         options.set_extern_enums(extern_enums);
     }
 
-    if let Some(custom_variable_types) = custom_variable_types {
+    if let Some(custom_variable_types) = attributes.variable_types {
         options.set_custom_variable_types(custom_variable_types);
     }
 
-    if let Some(custom_response_type) = custom_response_type {
+    if let Some(custom_response_type) = attributes.response_type {
         options.set_custom_response_type(custom_response_type);
     }
 
@@ -118,3 +382,35 @@ fn build_graphql_client_derive_options(
 
     Ok(options)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_turn_off_crate_wide_flags() {
+        let manifest_dir = std::env::temp_dir().join(format!(
+            "graphql_query_derive_defaults_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&manifest_dir).unwrap();
+        let manifest_path = manifest_dir.join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[package.metadata.graphql-client]\nmaybe_undefined = true\nbuilders = true\n",
+        )
+        .unwrap();
+        let defaults = ManifestDefaults::read(&manifest_path).unwrap();
+
+        let input: syn::DeriveInput = syn::parse_quote! {
+            #[graphql(schema_path = "x", query_path = "y", maybe_undefined = false)]
+            struct MyQuery;
+        };
+        let attributes = GraphQLAttributes::from_ast(&input).unwrap();
+        let options =
+            build_graphql_client_derive_options(&input, attributes, &defaults, None).unwrap();
+
+        assert!(!*options.maybe_undefined());
+        assert!(*options.builders());
+    }
+}